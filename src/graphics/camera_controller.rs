@@ -1,27 +1,104 @@
+use std::time::Instant;
+
+use cgmath::{InnerSpace, Rotation, Rotation3};
 use winit::{
-    event::{ElementState, KeyEvent, WindowEvent},
+    event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent},
     keyboard::{KeyCode, PhysicalKey},
 };
 
+/// how input moves the camera
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ControllerMode {
+    /// left drag rotates around the target, right drag pans, scroll zooms
+    #[default]
+    Orbit,
+    /// orbit that keeps the world y axis pointing up
+    TurnTable,
+    /// wasd (plus q/e) moves the eye, left drag looks around
+    Fly,
+    /// input is ignored
+    Fixed,
+}
+
 pub struct CameraController {
-    speed: f32,
+    pub mode: ControllerMode,
+    pub speed: f32,
+    /// radians of rotation per pixel dragged
+    pub sensitivity: f32,
+    /// fraction of the distance to the target covered per scroll line
+    pub zoom_sensitivity: f32,
+    /// share of the last motion carried into each following frame once input stops, 0.0 stops dead
+    pub inertia: f32,
     is_forward_pressed: bool,
     is_backward_pressed: bool,
     is_left_pressed: bool,
     is_right_pressed: bool,
+    is_up_pressed: bool,
+    is_down_pressed: bool,
+    is_rotating: bool,
+    is_panning: bool,
+    cursor: Option<(f32, f32)>,
+    rotate: (f32, f32),
+    pan: (f32, f32),
+    zoom: f32,
+    rotate_velocity: (f32, f32),
+    pan_velocity: (f32, f32),
+    zoom_velocity: (f32, f32),
+}
+
+/// the new input if there was any, otherwise the previous motion decayed by `inertia`
+fn carry(input: (f32, f32), velocity: &mut (f32, f32), inertia: f32) -> (f32, f32) {
+    if input != (0.0, 0.0) {
+        *velocity = input;
+    }
+    else {
+        velocity.0 *= inertia;
+        velocity.1 *= inertia;
+    }
+    *velocity
 }
 
 impl CameraController {
     pub fn new(speed: f32) -> Self {
         Self {
+            mode: ControllerMode::default(),
             speed,
+            sensitivity: 0.005,
+            zoom_sensitivity: 0.1,
+            inertia: 0.0,
             is_forward_pressed: false,
             is_backward_pressed: false,
             is_left_pressed: false,
             is_right_pressed: false,
+            is_up_pressed: false,
+            is_down_pressed: false,
+            is_rotating: false,
+            is_panning: false,
+            cursor: None,
+            rotate: (0.0, 0.0),
+            pan: (0.0, 0.0),
+            zoom: 0.0,
+            rotate_velocity: (0.0, 0.0),
+            pan_velocity: (0.0, 0.0),
+            zoom_velocity: (0.0, 0.0),
         }
     }
 
+    /// switches mode and drops any motion still carried over from the previous one
+    pub fn set_mode(&mut self, mode: ControllerMode) {
+        self.mode = mode;
+        self.rotate_velocity = (0.0, 0.0);
+        self.pan_velocity = (0.0, 0.0);
+        self.zoom_velocity = (0.0, 0.0);
+    }
+
+    /// true while motion is still coasting from inertia
+    pub fn is_moving(&self) -> bool {
+        let moving = |velocity: (f32, f32)| velocity.0.abs() > 0.01 || velocity.1.abs() > 0.01;
+        self.mode != ControllerMode::Fixed
+        && (moving(self.rotate_velocity) || moving(self.pan_velocity) || moving(self.zoom_velocity))
+    }
+
     #[allow(dead_code)]
     pub fn process_events(&mut self, event: &WindowEvent) -> bool {
         match event {
@@ -52,15 +129,88 @@ impl CameraController {
                         self.is_right_pressed = is_pressed;
                         true
                     }
+                    KeyCode::KeyE | KeyCode::Space => {
+                        self.is_up_pressed = is_pressed;
+                        true
+                    }
+                    KeyCode::KeyQ | KeyCode::ShiftLeft => {
+                        self.is_down_pressed = is_pressed;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let is_pressed = *state == ElementState::Pressed;
+                match button {
+                    MouseButton::Left => {
+                        self.is_rotating = is_pressed;
+                        true
+                    }
+                    MouseButton::Right | MouseButton::Middle => {
+                        self.is_panning = is_pressed;
+                        true
+                    }
                     _ => false,
                 }
             }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = (position.x as f32, position.y as f32);
+                if let Some(last) = self.cursor {
+                    let delta = (position.0 - last.0, position.1 - last.1);
+                    if self.is_rotating {
+                        self.rotate.0 += delta.0;
+                        self.rotate.1 += delta.1;
+                    }
+                    if self.is_panning {
+                        self.pan.0 += delta.0;
+                        self.pan.1 += delta.1;
+                    }
+                }
+                self.cursor = Some(position);
+                self.is_rotating || self.is_panning
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor = None;
+                false
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.zoom += match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32 / 40.0,
+                };
+                true
+            }
             _ => false,
         }
     }
 
-    pub fn update_camera(&self, camera: &mut Camera) {
-        use cgmath::InnerSpace;
+    pub fn update_camera(&mut self, camera: &mut Camera) {
+        let rotate = carry(std::mem::take(&mut self.rotate), &mut self.rotate_velocity, self.inertia);
+        let pan = carry(std::mem::take(&mut self.pan), &mut self.pan_velocity, self.inertia);
+        let (zoom, _) = carry((std::mem::take(&mut self.zoom), 0.0), &mut self.zoom_velocity, self.inertia);
+
+        match self.mode {
+            ControllerMode::Fixed => {}
+            ControllerMode::Orbit | ControllerMode::TurnTable => {
+                self.orbit_with_keys(camera);
+                self.orbit(camera, rotate);
+                self.pan_view(camera, pan);
+
+                let offset = camera.eye - camera.target;
+                let scale = (1.0 - zoom * self.zoom_sensitivity).max(0.1);
+                if (offset * scale).magnitude() > camera.znear {
+                    camera.eye = camera.target + offset * scale;
+                }
+            }
+            ControllerMode::Fly => {
+                self.fly(camera, rotate, zoom);
+                self.pan_view(camera, pan);
+            }
+        }
+    }
+
+    fn orbit_with_keys(&self, camera: &mut Camera) {
         let forward = camera.target - camera.eye;
         let forward_norm = forward.normalize();
         let forward_mag = forward.magnitude();
@@ -90,6 +240,84 @@ impl CameraController {
             camera.eye = camera.target - (forward - right * self.speed).normalize() * forward_mag;
         }
     }
+
+    fn orbit(&self, camera: &mut Camera, rotate: (f32, f32)) {
+        if rotate == (0.0, 0.0) {
+            return;
+        }
+
+        let up = match self.mode {
+            ControllerMode::TurnTable => cgmath::Vector3::unit_y(),
+            _ => camera.up.normalize(),
+        };
+
+        let yaw = cgmath::Quaternion::from_axis_angle(up, cgmath::Rad(-rotate.0 * self.sensitivity));
+        let offset = yaw.rotate_vector(camera.eye - camera.target);
+
+        let right = (-offset).cross(up).normalize();
+        let pitch = cgmath::Quaternion::from_axis_angle(right, cgmath::Rad(-rotate.1 * self.sensitivity));
+        let pitched = pitch.rotate_vector(offset);
+
+        match self.mode {
+            ControllerMode::TurnTable => {
+                camera.up = up;
+                // stop short of the poles, past them the view would flip
+                camera.eye = match pitched.normalize().dot(up).abs() < 0.99 {
+                    true => camera.target + pitched,
+                    false => camera.target + offset,
+                };
+            }
+            _ => {
+                camera.up = (pitch * yaw).rotate_vector(up);
+                camera.eye = camera.target + pitched;
+            }
+        }
+    }
+
+    fn pan_view(&self, camera: &mut Camera, pan: (f32, f32)) {
+        if pan == (0.0, 0.0) {
+            return;
+        }
+
+        let forward = camera.target - camera.eye;
+        let distance = forward.magnitude();
+        let right = forward.cross(camera.up).normalize();
+        let up = right.cross(forward).normalize();
+
+        let translation = (up * pan.1 - right * pan.0) * distance * self.sensitivity;
+        camera.eye += translation;
+        camera.target += translation;
+    }
+
+    fn fly(&self, camera: &mut Camera, look: (f32, f32), zoom: f32) {
+        let distance = (camera.target - camera.eye).magnitude();
+        let mut forward = (camera.target - camera.eye).normalize();
+        let up = camera.up.normalize();
+
+        if look != (0.0, 0.0) {
+            let yaw = cgmath::Quaternion::from_axis_angle(up, cgmath::Rad(-look.0 * self.sensitivity));
+            let turned = yaw.rotate_vector(forward);
+            let right = turned.cross(up).normalize();
+            let pitch = cgmath::Quaternion::from_axis_angle(right, cgmath::Rad(-look.1 * self.sensitivity));
+            let pitched = pitch.rotate_vector(turned);
+            forward = match pitched.dot(up).abs() < 0.99 {
+                true => pitched,
+                false => turned,
+            };
+        }
+
+        let right = forward.cross(up).normalize();
+        let mut movement = forward * zoom;
+        if self.is_forward_pressed { movement += forward; }
+        if self.is_backward_pressed { movement -= forward; }
+        if self.is_right_pressed { movement += right; }
+        if self.is_left_pressed { movement -= right; }
+        if self.is_up_pressed { movement += up; }
+        if self.is_down_pressed { movement -= up; }
+
+        camera.eye += movement * self.speed;
+        camera.target = camera.eye + forward * distance;
+    }
 }
 
 #[rustfmt::skip]
@@ -100,6 +328,79 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
     0.0, 0.0, 0.0, 1.0,
 );
 
+const PROJECTION_TRANSITION_SECONDS: f32 = 0.35;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Perspective,
+    /// `size` is half of the visible height, in world units
+    Orthographic{size: f32},
+}
+
+/// Tracks the active projection and the blend between perspective and orthographic while switching.
+#[derive(Clone)]
+pub struct ProjectionState {
+    pub mode: Projection,
+    orthographic_size: f32,
+    blend_from: f32,
+    transition_start: Option<Instant>,
+}
+
+impl Default for ProjectionState {
+    fn default() -> Self {
+        Self {
+            mode: Projection::Perspective,
+            orthographic_size: 1.0,
+            blend_from: 0.0,
+            transition_start: None,
+        }
+    }
+}
+
+impl ProjectionState {
+    fn target_blend(&self) -> f32 {
+        match self.mode {
+            Projection::Perspective => 0.0,
+            Projection::Orthographic { size: _ } => 1.0,
+        }
+    }
+
+    /// 0.0 is fully perspective, 1.0 is fully orthographic
+    pub fn blend(&self) -> f32 {
+        match self.transition_start {
+            None => self.target_blend(),
+            Some(start) => {
+                let t = (start.elapsed().as_secs_f32() / PROJECTION_TRANSITION_SECONDS).min(1.0);
+                let t = t * t * (3.0 - 2.0 * t);
+                self.blend_from + (self.target_blend() - self.blend_from) * t
+            }
+        }
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        match self.transition_start {
+            None => false,
+            Some(start) => start.elapsed().as_secs_f32() < PROJECTION_TRANSITION_SECONDS,
+        }
+    }
+
+    fn switch(&mut self, mode: Projection) {
+        self.blend_from = self.blend();
+        self.mode = mode;
+        self.transition_start = Some(Instant::now());
+    }
+
+    /// changes projection without easing, for restoring a saved camera
+    pub fn snap_to(&mut self, mode: Projection) {
+        if let Projection::Orthographic { size } = mode {
+            self.orthographic_size = size;
+        }
+        self.mode = mode;
+        self.transition_start = None;
+    }
+}
+
+#[derive(Clone)]
 pub struct Camera {
     pub eye: cgmath::Point3<f32>,
     pub target: cgmath::Point3<f32>,
@@ -108,6 +409,22 @@ pub struct Camera {
     pub fovy: f32,
     pub znear: f32,
     pub zfar: f32,
+    pub projection: ProjectionState,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            eye: (0.0, 1.0, 4.0).into(),
+            target: (0.0, 0.0, 0.0).into(),
+            up: cgmath::Vector3::unit_y(),
+            aspect: 1.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+            projection: ProjectionState::default(),
+        }
+    }
 }
 
 impl Camera {
@@ -115,11 +432,87 @@ impl Camera {
         // 1.
         let view = cgmath::Matrix4::look_at_rh(self.eye, self.target, self.up);
         // 2.
-        let proj = cgmath::perspective(cgmath::Deg(self.fovy), self.aspect, self.znear, self.zfar);
+        let perspective = cgmath::perspective(cgmath::Deg(self.fovy), self.aspect, self.znear, self.zfar);
+
+        let blend = self.projection.blend();
+        let proj = if blend > 0.0 {
+            let size = self.projection.orthographic_size;
+            let orthographic = cgmath::ortho(
+                -size * self.aspect,
+                size * self.aspect,
+                -size,
+                size,
+                self.znear,
+                self.zfar
+            );
+            perspective * (1.0 - blend) + orthographic * blend
+        }
+        else {
+            perspective
+        };
 
         // 3.
         return OPENGL_TO_WGPU_MATRIX * proj * view;
     }
+
+    /// Switches to an orthographic projection, easing over from the current one.
+    pub fn set_orthographic(&mut self, size: f32) {
+        self.projection.orthographic_size = size;
+        self.projection.switch(Projection::Orthographic { size });
+    }
+
+    /// Switches back to a perspective projection, easing over from the current one.
+    pub fn set_perspective(&mut self) {
+        self.projection.switch(Projection::Perspective);
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.projection.is_transitioning()
+    }
+
+    /// Where `point` lands on a viewport of `size`, measured from the top left in the same units as `size`.
+    /// None when the point is behind the camera.
+    pub fn world_to_screen(&self, point: cgmath::Point3<f32>, size: (f32, f32)) -> Option<(f32, f32)> {
+        let mut camera = self.clone();
+        if size.1 > 0.0 {
+            camera.aspect = size.0 / size.1;
+        }
+        let clip = camera.build_view_projection_matrix() * point.to_homogeneous();
+        if clip.w <= 0.0 {
+            return None
+        }
+        let (x, y) = (clip.x / clip.w, clip.y / clip.w);
+        Some(((x + 1.0) / 2.0 * size.0, (1.0 - y) / 2.0 * size.1))
+    }
+
+    /// Centers the camera on the box between `min` and `max` and backs off until all of it is in view,
+    /// keeping the current viewing direction.
+    pub fn zoom_to_extents(&mut self, min: cgmath::Point3<f32>, max: cgmath::Point3<f32>) {
+        let center = cgmath::Point3::new(
+            (min.x + max.x) / 2.0,
+            (min.y + max.y) / 2.0,
+            (min.z + max.z) / 2.0,
+        );
+        let radius = ((max - min).magnitude() / 2.0).max(f32::EPSILON);
+
+        let direction = match (self.target - self.eye).magnitude() > f32::EPSILON {
+            true => (self.target - self.eye).normalize(),
+            false => -cgmath::Vector3::unit_z(),
+        };
+
+        let half_fovy = cgmath::Rad::from(cgmath::Deg(self.fovy)).0 / 2.0;
+        let half_fovx = (half_fovy.tan() * self.aspect).atan();
+        let distance = radius / half_fovy.min(half_fovx).sin();
+
+        self.target = center;
+        self.eye = center - direction * distance;
+
+        let size = radius / self.aspect.min(1.0);
+        self.projection.orthographic_size = size;
+        if let Projection::Orthographic { size: _ } = self.projection.mode {
+            self.projection.mode = Projection::Orthographic { size };
+        }
+    }
     pub fn bindgroup_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
         device.create_bind_group_layout(
             &wgpu::BindGroupLayoutDescriptor {
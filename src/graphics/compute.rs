@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+/// a compiled compute shader with its resources bound, ready to dispatch
+pub struct ComputeJob {
+    pipeline: wgpu::ComputePipeline,
+    bind_group: Option<wgpu::BindGroup>,
+    pub workgroups: (u32, u32, u32),
+}
+
+/// compiles compute shaders on the shared device, pipelines are kept per label until the source changes
+#[derive(Default)]
+pub struct ComputeRunner {
+    pipelines: HashMap<String, (String, wgpu::ComputePipeline)>,
+    /// dispatched in order before every viewport redraw
+    pub passes: Vec<(String, ComputeJob)>,
+}
+
+impl ComputeRunner {
+    /// the shader needs exactly one `@compute` entry point, `resources` are bound to group 0 in binding order
+    pub fn job(
+        &mut self,
+        device: &wgpu::Device,
+        label: &str,
+        wgsl: &str,
+        workgroups: (u32, u32, u32),
+        resources: &[wgpu::BindingResource],
+    ) -> Result<ComputeJob, ()> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let pipeline = match self.pipelines.get(label) {
+            Some((source, pipeline)) if source == wgsl => pipeline.clone(),
+            _ => {
+                let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some(label),
+                    source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+                });
+                let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: Some(label),
+                    layout: None,
+                    module: &module,
+                    entry_point: None,
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    cache: None,
+                });
+                self.pipelines.insert(label.to_string(), (wgsl.to_string(), pipeline.clone()));
+                pipeline
+            }
+        };
+
+        let bind_group = match resources.is_empty() {
+            true => None,
+            false => Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(label),
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &resources.iter().enumerate().map(|(binding, resource)| wgpu::BindGroupEntry {
+                    binding: binding as u32,
+                    resource: resource.clone(),
+                }).collect::<Vec<_>>(),
+            })),
+        };
+
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            tracing::error!(target: "telera::render", "compute shader {} failed: {}", label, error);
+            self.pipelines.remove(label);
+            return Err(())
+        }
+
+        Ok(ComputeJob {
+            pipeline,
+            bind_group,
+            workgroups,
+        })
+    }
+
+    pub fn dispatch<'a>(device: &wgpu::Device, queue: &wgpu::Queue, jobs: impl Iterator<Item = &'a ComputeJob>) {
+        let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Compute Encoder"),
+        });
+        {
+            let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("ComputePass"),
+                timestamp_writes: None,
+            });
+            for job in jobs {
+                compute_pass.set_pipeline(&job.pipeline);
+                if let Some(bind_group) = &job.bind_group {
+                    compute_pass.set_bind_group(0, bind_group, &[]);
+                }
+                let (x, y, z) = job.workgroups;
+                compute_pass.dispatch_workgroups(x, y, z);
+            }
+        }
+        queue.submit(std::iter::once(command_encoder.finish()));
+    }
+}
@@ -0,0 +1,171 @@
+use cgmath::Point3;
+
+use crate::graphics::camera_controller::Camera;
+
+/// lines past this are dropped for the frame
+const MAX_DEBUG_VERTICES: usize = 1 << 16;
+const SPHERE_SEGMENTS: usize = 24;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DebugVertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+
+impl DebugVertex {
+    fn buffer_description() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<DebugVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ],
+        }
+    }
+}
+
+/// immediate mode line drawing, everything queued since the last `clear` is drawn on top of the scene
+pub struct DebugDraw {
+    vertices: Vec<DebugVertex>,
+    buffer: wgpu::Buffer,
+    pipeline: Option<wgpu::RenderPipeline>,
+}
+
+impl DebugDraw {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("debug_vertex_buffer"),
+            size: (std::mem::size_of::<DebugVertex>() * MAX_DEBUG_VERTICES) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            vertices: Vec::new(),
+            buffer,
+            pipeline: None,
+        }
+    }
+
+    pub fn build_pipeline(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, multi_sample_count: u32) {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Debug Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("debug_shader.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Debug Render Pipeline Layout"),
+            bind_group_layouts: &[&Camera::bindgroup_layout(device)],
+            push_constant_ranges: &[],
+        });
+
+        self.pipeline = Some(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Debug Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[DebugVertex::buffer_description()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                ..Default::default()
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: multi_sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        }));
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    pub fn line(&mut self, a: Point3<f32>, b: Point3<f32>, color: [f32; 3]) {
+        if self.vertices.len() + 2 > MAX_DEBUG_VERTICES {
+            return;
+        }
+        self.vertices.push(DebugVertex { position: a.into(), color });
+        self.vertices.push(DebugVertex { position: b.into(), color });
+    }
+
+    pub fn aabb(&mut self, min: Point3<f32>, max: Point3<f32>, color: [f32; 3]) {
+        let corner = |index: usize| Point3::new(
+            if index & 1 == 0 { min.x } else { max.x },
+            if index & 2 == 0 { min.y } else { max.y },
+            if index & 4 == 0 { min.z } else { max.z },
+        );
+        // every pair of corners that differ in exactly one axis is an edge
+        for a in 0..8 {
+            for axis in [1, 2, 4] {
+                if a & axis == 0 {
+                    self.line(corner(a), corner(a | axis), color);
+                }
+            }
+        }
+    }
+
+    /// three circles, one around each axis
+    pub fn sphere(&mut self, center: Point3<f32>, radius: f32, color: [f32; 3]) {
+        let point = |axis: usize, angle: f32| {
+            let (sin, cos) = (angle.sin() * radius, angle.cos() * radius);
+            match axis {
+                0 => Point3::new(center.x, center.y + cos, center.z + sin),
+                1 => Point3::new(center.x + cos, center.y, center.z + sin),
+                _ => Point3::new(center.x + cos, center.y + sin, center.z),
+            }
+        };
+        for axis in 0..3 {
+            for segment in 0..SPHERE_SEGMENTS {
+                let a = segment as f32 / SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+                let b = (segment + 1) as f32 / SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+                self.line(point(axis, a), point(axis, b), color);
+            }
+        }
+    }
+
+    /// expects the camera bind group to already be set at index 0
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
+        if self.vertices.is_empty() {
+            return;
+        }
+        if let Some(pipeline) = &self.pipeline {
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&self.vertices));
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_vertex_buffer(0, self.buffer.slice(..));
+            render_pass.draw(0..self.vertices.len() as u32, 0..1);
+        }
+    }
+}
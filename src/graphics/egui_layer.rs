@@ -0,0 +1,65 @@
+use egui_wgpu::ScreenDescriptor;
+use wgpu::{Device, Queue, RenderPass, TextureFormat};
+use winit::{event::WindowEvent, window::Window};
+
+/// an egui context drawn over one viewport's ui, for `App::debug_ui`
+pub struct EguiLayer {
+    state: egui_winit::State,
+    renderer: egui_wgpu::Renderer,
+    /// tessellated in `prepare`, drawn in `render`
+    frame: Option<(Vec<egui::ClippedPrimitive>, ScreenDescriptor)>,
+    /// textures egui is done with, freed once the frame that last used them was drawn
+    freed: Vec<egui::TextureId>,
+}
+
+impl EguiLayer {
+    pub fn new(window: &Window, device: &Device, format: TextureFormat, multi_sample_count: u32) -> Self {
+        let state = egui_winit::State::new(
+            egui::Context::default(),
+            egui::ViewportId::ROOT,
+            window,
+            Some(window.scale_factor() as f32),
+            window.theme(),
+            Some(device.limits().max_texture_dimension_2d as usize),
+        );
+        // drawn in the ui's pass, so it has to match its depth and samples
+        let renderer = egui_wgpu::Renderer::new(device, format, Some(wgpu::TextureFormat::Depth32Float), multi_sample_count, false);
+        Self { state, renderer, frame: None, freed: Vec::new() }
+    }
+
+    /// feeds a window event to egui, `consumed` is set when egui used it
+    pub fn on_window_event(&mut self, window: &Window, event: &WindowEvent) -> egui_winit::EventResponse {
+        self.state.on_window_event(window, event)
+    }
+
+    /// runs `ui` and uploads what it drew, true when egui wants to be drawn again right away
+    pub fn prepare(&mut self, window: &Window, device: &Device, queue: &Queue, size: [u32; 2], ui: impl FnMut(&egui::Context)) -> bool {
+        for id in self.freed.drain(..) {
+            self.renderer.free_texture(&id);
+        }
+
+        let input = self.state.take_egui_input(window);
+        let output = self.state.egui_ctx().run(input, ui);
+        self.state.handle_platform_output(window, output.platform_output);
+
+        let primitives = self.state.egui_ctx().tessellate(output.shapes, output.pixels_per_point);
+        let screen = ScreenDescriptor { size_in_pixels: size, pixels_per_point: output.pixels_per_point };
+        for (id, delta) in &output.textures_delta.set {
+            self.renderer.update_texture(device, queue, *id, delta);
+        }
+        // the frame's render pass is already open when the layer draws, so buffers go up in their own submit
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("egui") });
+        let callbacks = self.renderer.update_buffers(device, queue, &mut encoder, &primitives, &screen);
+        queue.submit(callbacks.into_iter().chain(std::iter::once(encoder.finish())));
+
+        self.freed = output.textures_delta.free;
+        self.frame = Some((primitives, screen));
+        output.viewport_output.get(&egui::ViewportId::ROOT).is_some_and(|viewport| viewport.repaint_delay.is_zero())
+    }
+
+    pub fn render(&self, render_pass: &mut RenderPass<'static>) {
+        if let Some((primitives, screen)) = &self.frame {
+            self.renderer.render(render_pass, primitives, screen);
+        }
+    }
+}
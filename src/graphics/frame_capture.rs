@@ -0,0 +1,210 @@
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use image::{ImageBuffer, Luma, RgbaImage};
+
+/// what a frame is read back for, the callback gets the pixels once the gpu is done with them
+pub enum CaptureRequest {
+    Color(Box<dyn FnOnce(RgbaImage)>),
+    /// 0 at the near plane and 1 at the far plane, only single sampled depth can be copied
+    Depth(Box<dyn FnOnce(ImageBuffer<Luma<f32>, Vec<f32>>)>),
+}
+
+/// a copy that was submitted, read once its buffer is mapped so the frame that asked for it never waits on the gpu
+pub struct PendingCapture {
+    capture: FrameCapture,
+    mapped: Receiver<Result<(), wgpu::BufferAsyncError>>,
+    request: CaptureRequest,
+}
+
+impl PendingCapture {
+    /// hands the pixels to the request if the gpu is done, gives the capture back if it isn't yet
+    pub fn deliver(self) -> Option<Self> {
+        match self.mapped.try_recv() {
+            Err(TryRecvError::Empty) => Some(self),
+            Ok(Ok(())) => {
+                let bytes = self.capture.mapped_bytes();
+                match self.request {
+                    CaptureRequest::Color(deliver) => {
+                        if let Some(image) = self.capture.to_image(bytes) {
+                            deliver(image);
+                        }
+                    }
+                    CaptureRequest::Depth(deliver) => {
+                        if let Some(depth) = self.capture.to_depth(bytes) {
+                            deliver(depth);
+                        }
+                    }
+                }
+                None
+            }
+            // the request is dropped, its task never finishes
+            _ => None,
+        }
+    }
+}
+
+pub struct FrameCapture {
+    pub buffer: wgpu::Buffer,
+    pub width: u32,
+    pub height: u32,
+    pub padded_bytes_per_row: u32,
+    pub format: wgpu::TextureFormat,
+}
+
+impl FrameCapture {
+    pub fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        let unpadded_bytes_per_row = width.max(1) * bytes_per_texel(format);
+        let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(alignment) * alignment;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame_capture_buffer"),
+            size: (padded_bytes_per_row * height.max(1)) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            buffer,
+            width: width.max(1),
+            height: height.max(1),
+            padded_bytes_per_row,
+            format,
+        }
+    }
+
+    pub fn copy_texture(&self, command_encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) {
+        command_encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_bytes_per_row),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// starts mapping the buffer, once the copy from `copy_texture` was submitted
+    pub fn map(self, request: CaptureRequest) -> PendingCapture {
+        let (sender, mapped) = mpsc::channel();
+        self.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        PendingCapture { capture: self, mapped, request }
+    }
+
+    /// blocks until the copy submitted with `copy_texture` is finished, returns the rows with padding removed
+    fn read_bytes(&self, device: &wgpu::Device) -> Option<Vec<u8>> {
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        let Ok(Ok(())) = receiver.recv() else {
+            return None
+        };
+        Some(self.mapped_bytes())
+    }
+
+    /// the rows with padding removed, the buffer has to be mapped
+    fn mapped_bytes(&self) -> Vec<u8> {
+        let slice = self.buffer.slice(..);
+        let row_bytes = self.width * bytes_per_texel(self.format);
+        let mut bytes = Vec::<u8>::with_capacity((row_bytes * self.height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(self.padded_bytes_per_row as usize) {
+                bytes.extend_from_slice(&row[..row_bytes as usize]);
+            }
+        }
+        self.buffer.unmap();
+        bytes
+    }
+
+    pub fn read(&self, device: &wgpu::Device) -> Option<RgbaImage> {
+        self.to_image(self.read_bytes(device)?)
+    }
+
+    fn to_image(&self, mut pixels: Vec<u8>) -> Option<RgbaImage> {
+        // hdr surfaces hold linear half floats, bring them back to 8 bit srgb
+        if self.format == wgpu::TextureFormat::Rgba16Float {
+            pixels = pixels
+                .chunks_exact(8)
+                .flat_map(|texel| {
+                    let channel = |i: usize| f16_to_f32(u16::from_ne_bytes([texel[i * 2], texel[i * 2 + 1]]));
+                    [
+                        linear_to_srgb(channel(0)),
+                        linear_to_srgb(channel(1)),
+                        linear_to_srgb(channel(2)),
+                        (channel(3).clamp(0.0, 1.0) * 255.0).round() as u8,
+                    ]
+                })
+                .collect();
+        }
+
+        if matches!(
+            self.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        RgbaImage::from_raw(self.width, self.height, pixels)
+    }
+
+    /// for captures of a `Depth32Float` texture, 0 is the near plane and 1 the far plane
+    fn to_depth(&self, bytes: Vec<u8>) -> Option<ImageBuffer<Luma<f32>, Vec<f32>>> {
+        let depth = bytes
+            .chunks_exact(4)
+            .map(|texel| f32::from_ne_bytes([texel[0], texel[1], texel[2], texel[3]]))
+            .collect();
+        ImageBuffer::from_raw(self.width, self.height, depth)
+    }
+}
+
+fn bytes_per_texel(format: wgpu::TextureFormat) -> u32 {
+    format.block_copy_size(Some(wgpu::TextureAspect::All)).unwrap_or(4)
+}
+
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let encoded = match value <= 0.0031308 {
+        true => value * 12.92,
+        false => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+    };
+    (encoded * 255.0).round() as u8
+}
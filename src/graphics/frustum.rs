@@ -0,0 +1,34 @@
+use cgmath::{InnerSpace, Matrix, Matrix4, Point3, Vector4};
+
+/// the six clip planes of a view projection, normals point inwards
+pub struct Frustum {
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    /// expects wgpu clip space, depth running 0 to 1
+    pub fn from_view_projection(view_proj: &Matrix4<f32>) -> Self {
+        let row = |index: usize| view_proj.row(index);
+        let planes = [
+            row(3) + row(0),
+            row(3) - row(0),
+            row(3) + row(1),
+            row(3) - row(1),
+            row(2),
+            row(3) - row(2),
+        ].map(|plane| plane / plane.truncate().magnitude());
+
+        Self { planes }
+    }
+
+    /// false only when the box is entirely outside one of the planes
+    pub fn intersects_aabb(&self, min: Point3<f32>, max: Point3<f32>) -> bool {
+        self.planes.iter().all(|plane| {
+            // the corner furthest along the plane normal
+            let x = if plane.x >= 0.0 { max.x } else { min.x };
+            let y = if plane.y >= 0.0 { max.y } else { min.y };
+            let z = if plane.z >= 0.0 { max.z } else { min.z };
+            plane.x * x + plane.y * y + plane.z * z + plane.w >= 0.0
+        })
+    }
+}
@@ -1,6 +1,27 @@
 use wgpu::{Device, Queue, RenderPass, SurfaceConfiguration};
 
-use crate::graphics::viewport::Viewport;
+use crate::graphics::{
+    depth_texture::DepthTexture,
+    frame_capture::{CaptureRequest, FrameCapture},
+    post_process::{PostProcessor, PostTargets},
+    viewport::Viewport,
+};
+
+/// the scene is drawn first, then post effects run if any are enabled, then the ui goes on top
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderStage {
+    Scene,
+    Interface,
+}
+
+/// where a hook added with `api.add_render_hook` draws in a viewport's frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderHookStage {
+    /// over the scene, post effects are applied to what it draws
+    AfterScene,
+    /// over the scene and its post effects, under the ui
+    BeforeUI,
+}
 
 pub struct GraphicsContext {
     pub instance: wgpu::Instance,
@@ -23,7 +44,8 @@ impl GraphicsContext {
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: None,
-                required_features: wgpu::Features::empty(),
+                // wireframe models are drawn filled where line rasterization isn't available
+                required_features: adapter.features() & wgpu::Features::POLYGON_MODE_LINE,
                 required_limits: wgpu::Limits::default(),
                 memory_hints: wgpu::MemoryHints::default(),
             },
@@ -44,14 +66,40 @@ impl GraphicsContext {
     pub fn drm() {}
 
     pub fn render<
-        F: for<'a, 'b> FnOnce(&'b mut RenderPass<'a>, &Device, &Queue, &SurfaceConfiguration),
+        F: for<'b> FnMut(RenderStage, &'b mut RenderPass<'static>, &Device, &Queue, &SurfaceConfiguration),
     >(
         &self,
-        view_port: &Viewport,
+        view_port: &mut Viewport,
         multi_sample_count: u32,
-        render_middleware: F,
+        post_processor: &mut PostProcessor,
+        mut render_middleware: F,
     ) -> Result<(), wgpu::SurfaceError> {
         let drawable = view_port.get_current_texture();
+        let drawable_view = drawable
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let capture_requests = std::mem::take(&mut view_port.capture_requests);
+        let capture_color = capture_requests.iter().any(|request| matches!(request, CaptureRequest::Color(_)));
+        let capture_view = match capture_color {
+            false => None,
+            true => {
+                if !view_port.capture_target.as_ref().is_some_and(|target| target.size() == drawable.texture.size() && target.format() == view_port.config.format) {
+                    view_port.capture_target = Some(self.device.create_texture(&wgpu::TextureDescriptor {
+                        size: drawable.texture.size(),
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format: view_port.config.format,
+                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+                        label: Some("capture_target"),
+                        view_formats: &[],
+                    }));
+                }
+                view_port.capture_target.as_ref().map(|target| target.create_view(&wgpu::TextureViewDescriptor::default()))
+            }
+        };
+        let frame_view = capture_view.as_ref().unwrap_or(&drawable_view);
 
         let mut command_encoder =
             self.device
@@ -59,86 +107,230 @@ impl GraphicsContext {
                     label: Some("Render Encoder"),
                 });
 
-        if multi_sample_count == 1 {
-            let mut render_pass: RenderPass =
-                command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("RenderPass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &drawable
-                            .texture
-                            .create_view(&wgpu::TextureViewDescriptor::default()), //&view_port.multi_sample_texture.view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(wgpu::Color {
-                                r: 0.15,
-                                g: 0.15,
-                                b: 0.15,
-                                a: 1.0,
-                            }),
-                            store: wgpu::StoreOp::Store,
-                        },
-                    })],
-                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                        view: &view_port.depth_texture.view,
-                        depth_ops: Some(wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(1.0),
-                            store: wgpu::StoreOp::Store,
-                        }),
-                        stencil_ops: None,
-                    }),
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                });
+        let clear_color = match multi_sample_count {
+            1 => wgpu::Color {
+                r: 0.15,
+                g: 0.15,
+                b: 0.15,
+                a: 1.0,
+            },
+            _ => wgpu::Color::WHITE,
+        };
 
-            render_middleware(
-                &mut render_pass,
-                &self.device,
-                &self.queue,
-                &view_port.config,
-            );
-        } else {
-            let mut render_pass: RenderPass =
-                command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("RenderPass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &view_port.multi_sample_texture.view,
-                        resolve_target: Some(
-                            &drawable
-                                .texture
-                                .create_view(&wgpu::TextureViewDescriptor::default()),
-                        ),
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(wgpu::Color {
-                                r: 1.0,
-                                g: 1.0,
-                                b: 1.0,
-                                a: 1.0,
-                            }),
-                            store: wgpu::StoreOp::Store,
-                        },
-                    })],
-                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                        view: &view_port.depth_texture.view,
-                        depth_ops: Some(wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(1.0),
-                            store: wgpu::StoreOp::Store,
-                        }),
-                        stencil_ops: None,
-                    }),
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                });
+        match post_processor.is_active() {
+            false => {
+                let mut render_pass = begin_pass(
+                    &mut command_encoder,
+                    view_port,
+                    frame_view,
+                    multi_sample_count,
+                    wgpu::LoadOp::Clear(1.0),
+                    clear_color,
+                );
+
+                render_middleware(RenderStage::Scene, &mut render_pass, &self.device, &self.queue, &view_port.config);
+                render_middleware(RenderStage::Interface, &mut render_pass, &self.device, &self.queue, &view_port.config);
+            }
+            true => {
+                if !view_port.post_targets.as_ref().is_some_and(|targets| targets.matches(&view_port.config)) {
+                    view_port.post_targets = Some(PostTargets::new(&self.device, &view_port.config));
+                }
+                let Some(targets) = &view_port.post_targets else {
+                    unreachable!()
+                };
+
+                {
+                    let mut render_pass = begin_pass(
+                        &mut command_encoder,
+                        view_port,
+                        &targets.views[0],
+                        multi_sample_count,
+                        wgpu::LoadOp::Clear(1.0),
+                        clear_color,
+                    );
+                    render_middleware(RenderStage::Scene, &mut render_pass, &self.device, &self.queue, &view_port.config);
+                }
+
+                let output = post_processor.apply(&self.device, &self.queue, &mut command_encoder, targets);
+
+                // depth is kept so ui drawn with depth testing still sits correctly against the scene
+                let mut render_pass = begin_pass(
+                    &mut command_encoder,
+                    view_port,
+                    frame_view,
+                    multi_sample_count,
+                    wgpu::LoadOp::Load,
+                    clear_color,
+                );
+                post_processor.blit(&self.device, &mut render_pass, targets, output, multi_sample_count);
+                render_middleware(RenderStage::Interface, &mut render_pass, &self.device, &self.queue, &view_port.config);
+            }
+        }
+
+        if let Some(capture_view) = &capture_view {
+            let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("PresentPass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &drawable_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(clear_color),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            post_processor.present(&self.device, &mut render_pass, capture_view, view_port.config.format);
+        }
 
-            render_middleware(
-                &mut render_pass,
+        let mut captures = Vec::new();
+        for request in capture_requests {
+            let texture = match &request {
+                CaptureRequest::Color(_) => view_port.capture_target.as_ref(),
+                // multisampled depth can't be copied, `capture_viewport_depth` turns those down before they get here
+                CaptureRequest::Depth(_) => Some(&view_port.depth_texture.texture).filter(|texture| texture.sample_count() == 1),
+            };
+            let Some(texture) = texture else {
+                continue
+            };
+            let frame_capture = FrameCapture::new(
                 &self.device,
-                &self.queue,
-                &view_port.config,
+                texture.width(),
+                texture.height(),
+                texture.format(),
             );
+            frame_capture.copy_texture(&mut command_encoder, texture);
+            captures.push((frame_capture, request));
         }
 
         self.queue.submit(std::iter::once(command_encoder.finish()));
+
+        for (frame_capture, request) in captures {
+            view_port.pending_captures.push_back(frame_capture.map(request));
+        }
+        self.read_captures(view_port, false);
+
         drawable.present();
         Ok(())
     }
+
+    /// hands finished captures to their requests in the order they were drawn. `wait` blocks until all of them are done
+    pub fn read_captures(&self, view_port: &mut Viewport, wait: bool) {
+        if view_port.pending_captures.is_empty() {
+            return
+        }
+        self.device.poll(match wait {
+            true => wgpu::Maintain::Wait,
+            false => wgpu::Maintain::Poll,
+        });
+        while let Some(capture) = view_port.pending_captures.pop_front() {
+            if let Some(capture) = capture.deliver() {
+                view_port.pending_captures.push_front(capture);
+                break
+            }
+        }
+    }
+}
+
+impl GraphicsContext {
+    /// draws into a texture of the config's size and format instead of a window, then reads it back
+    pub fn render_offscreen(
+        &self,
+        config: &SurfaceConfiguration,
+        render: impl FnOnce(&mut RenderPass, &Device, &Queue, &SurfaceConfiguration),
+    ) -> Result<image::RgbaImage, ()> {
+        let target = self.device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: config.width.max(1),
+                height: config.height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            label: Some("offscreen_target"),
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_texture = DepthTexture::new(&self.device, config, 1);
+
+        let mut command_encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Offscreen Encoder"),
+        });
+        {
+            let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("OffscreenPass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.15, g: 0.15, b: 0.15, a: 1.0 }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_texture.view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render(&mut render_pass, &self.device, &self.queue, config);
+        }
+
+        let frame_capture = FrameCapture::new(&self.device, config.width, config.height, config.format);
+        frame_capture.copy_texture(&mut command_encoder, &target);
+        self.queue.submit(std::iter::once(command_encoder.finish()));
+
+        let frame = frame_capture.read(&self.device).ok_or(());
+        target.destroy();
+        depth_texture.texture.destroy();
+        frame
+    }
+}
+
+/// the pass doesn't borrow the encoder so layers like egui's, which want a `'static` pass, can draw into it
+fn begin_pass(
+    command_encoder: &mut wgpu::CommandEncoder,
+    view_port: &Viewport,
+    target: &wgpu::TextureView,
+    multi_sample_count: u32,
+    depth_load: wgpu::LoadOp<f32>,
+    clear_color: wgpu::Color,
+) -> RenderPass<'static> {
+    let (view, resolve_target) = match multi_sample_count {
+        1 => (target, None),
+        _ => (&view_port.multi_sample_texture.view, Some(target)),
+    };
+
+    command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("RenderPass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(clear_color),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+            view: &view_port.depth_texture.view,
+            depth_ops: Some(wgpu::Operations {
+                load: depth_load,
+                store: wgpu::StoreOp::Store,
+            }),
+            stencil_ops: None,
+        }),
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    })
+    .forget_lifetime()
 }
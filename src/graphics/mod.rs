@@ -1,8 +1,21 @@
 pub mod camera_controller;
+pub mod compute;
+pub mod debug_draw;
 pub mod depth_texture;
+#[cfg(feature = "egui")]
+pub mod egui_layer;
+pub mod frame_capture;
+pub mod frustum;
 pub mod graphics_context;
 pub mod model;
 pub mod multi_sample_texture;
+pub mod plot;
+pub mod post_process;
+pub mod recorder;
+pub mod scene_file;
+pub mod scene_overlay;
 pub mod scene_renderer;
+pub mod skybox;
 pub mod texture;
-pub mod viewport;
\ No newline at end of file
+pub mod viewport;
+pub mod world_label;
\ No newline at end of file
@@ -6,7 +6,7 @@ pub use cgmath::Quaternion;
 pub use cgmath::Euler;
 use cgmath::{Deg, Matrix4, Rotation3, Vector4};
 use gltf::Gltf;
-use wgpu::util::DeviceExt;
+use wgpu::util::{DeviceExt, StagingBelt};
 
 use crate::texture::Texture;
 
@@ -231,19 +231,176 @@ pub struct Model {
     pub transform_buffer: wgpu::Buffer,
     pub transform: Transform,
     pub transform_dirty: bool,
+    /// drawn as edges only, needs `POLYGON_MODE_LINE` support from the adapter
+    pub wireframe: bool,
+    /// bounding box of the base mesh before any transform, computed on load
+    pub local_bounds: Option<(cgmath::Point3<f32>, cgmath::Point3<f32>)>,
+    /// outlines every shown instance
+    pub selected: bool,
+    /// indices into `mesh.instances` that are outlined
+    pub selected_instances: std::collections::HashSet<usize>,
     pub transform_bind_group: wgpu::BindGroup,
     pub dir: String,
     pub filename: String,
 
 }
 
+/// how a material's alpha is treated, mirrors the gltf alphaMode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    Opaque,
+    /// fragments with alpha below `cutoff` are dropped, the rest are opaque
+    Mask{cutoff: f32},
+    /// alpha blended, drawn after opaque models from back to front
+    Blend,
+}
+
+impl BlendMode {
+    pub fn from_gltf(material: &gltf::Material) -> Self {
+        match material.alpha_mode() {
+            gltf::material::AlphaMode::Opaque => BlendMode::Opaque,
+            gltf::material::AlphaMode::Mask => BlendMode::Mask { cutoff: material.alpha_cutoff().unwrap_or(0.5) },
+            gltf::material::AlphaMode::Blend => BlendMode::Blend,
+        }
+    }
+
+    pub fn alpha_cutoff(&self) -> f32 {
+        match self {
+            BlendMode::Mask { cutoff } => *cutoff,
+            _ => 0.0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct MaterialUniform {
+    pub alpha_cutoff: f32,
+    pub _padding: [f32; 3],
+}
+
 #[allow(dead_code)]
 pub struct Material {
     pub name: String,
     pub diffuse_texture: Texture,
+    /// false once a texture from `api.load_texture` is bound, so unloading the model leaves it alone
+    pub owns_texture: bool,
+    pub blend_mode: BlendMode,
+    pub uniform_buffer: wgpu::Buffer,
     pub bind_group: wgpu::BindGroup,
 }
 
+impl Material {
+    pub fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        name: String,
+        diffuse_texture: Texture,
+        blend_mode: BlendMode,
+    ) -> Self {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("material_uniform_buffer"),
+            contents: bytemuck::cast_slice(&[MaterialUniform {
+                alpha_cutoff: blend_mode.alpha_cutoff(),
+                _padding: [0.0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ],
+            label: None,
+        });
+
+        Self {
+            name,
+            diffuse_texture,
+            owns_texture: true,
+            blend_mode,
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    /// swaps in a shared texture, keeping the blend mode
+    pub fn set_texture(&mut self, device: &wgpu::Device, diffuse_texture: &Texture) {
+        let mut material = Self::new(
+            device,
+            &Self::bindgroup_layout(device),
+            self.name.clone(),
+            diffuse_texture.clone(),
+            self.blend_mode,
+        );
+        material.owns_texture = false;
+
+        if self.owns_texture {
+            self.diffuse_texture.texture.destroy();
+        }
+        self.uniform_buffer.destroy();
+        *self = material;
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode, queue: &wgpu::Queue) {
+        self.blend_mode = blend_mode;
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[MaterialUniform {
+                alpha_cutoff: blend_mode.alpha_cutoff(),
+                _padding: [0.0; 3],
+            }]),
+        );
+    }
+
+    pub fn bindgroup_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("material_bind_group_layout"),
+        })
+    }
+}
+
 #[allow(dead_code)]
 pub struct Mesh {
     pub base: BaseMesh,
@@ -255,7 +412,10 @@ pub struct Mesh {
     
     pub instances_shown: u32,
     pub instance_lookup: HashMap<String, usize>,
+    /// every instance is uploaded again on the next frame
     pub instances_dirty: bool,
+    /// instances moved since the last upload, only these are copied when the whole buffer isn't dirty
+    pub dirty_instances: Vec<usize>,
     pub instances: Vec<Transform>,
     pub instance_buffer: wgpu::Buffer,
 }
@@ -271,9 +431,73 @@ pub struct BaseMesh {
     pub textures: Vec<TextureRaw>,
 }
 
+impl Model {
+    /// frees the model's buffers and textures right away instead of waiting for the drop
+    pub fn destroy(&self) {
+        self.mesh.vertex_buffer_raw.destroy();
+        self.mesh.index_buffer_raw.destroy();
+        self.mesh.instance_buffer.destroy();
+        self.transform_buffer.destroy();
+        for material in self.materials.iter() {
+            if material.owns_texture {
+                material.diffuse_texture.texture.destroy();
+            }
+            material.uniform_buffer.destroy();
+        }
+    }
+
+    /// world space bounds of every shown instance, `None` if nothing is shown
+    pub fn bounds(&self) -> Option<(cgmath::Point3<f32>, cgmath::Point3<f32>)> {
+        use cgmath::{Point3, Transform as _};
+
+        let (local_min, local_max) = self.local_bounds?;
+        if self.mesh.instances_shown == 0 {
+            return None
+        }
+
+        let model_matrix = Matrix4::from(self.transform.to_wgpu_buffer().model);
+
+        let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut max = Point3::new(f32::MIN, f32::MIN, f32::MIN);
+        // instance 0 is the default transform and is never drawn
+        for instance in self.mesh.instances.iter().skip(1).take(self.mesh.instances_shown as usize) {
+            let matrix = model_matrix * Matrix4::from(instance.to_wgpu_buffer().model);
+            for corner in 0..8 {
+                let local = Point3::new(
+                    if corner & 1 == 0 { local_min.x } else { local_max.x },
+                    if corner & 2 == 0 { local_min.y } else { local_max.y },
+                    if corner & 4 == 0 { local_min.z } else { local_max.z },
+                );
+                let world = matrix.transform_point(local);
+                min = min.zip(world, f32::min);
+                max = max.zip(world, f32::max);
+            }
+        }
+
+        Some((min, max))
+    }
+
+    /// bytes of gpu memory held by the model's buffers and textures
+    pub fn gpu_memory(&self) -> u64 {
+        let buffers = self.mesh.vertex_buffer_raw.size()
+            + self.mesh.index_buffer_raw.size()
+            + self.mesh.instance_buffer.size()
+            + self.transform_buffer.size();
+
+        let textures = self.materials.iter().filter(|material| material.owns_texture).map(|material| {
+            let size = material.diffuse_texture.texture.size();
+            size.width as u64 * size.height as u64 * size.depth_or_array_layers as u64 * 4
+        }).sum::<u64>();
+
+        buffers + textures
+    }
+}
+
 impl Mesh {
     pub fn add_instance(&mut self, instance_name: String, device: &wgpu::Device, transform: Option<Transform>){
-        self.instances_dirty = true;
+        // the new buffer starts out with every instance in it
+        self.instances_dirty = false;
+        self.dirty_instances.clear();
         self.instances_shown += 1;
         self.instance_lookup.insert(instance_name, self.instances.len());
         let transform = match transform {
@@ -298,6 +522,44 @@ impl Mesh {
         self.instance_buffer = instance_buffer;
     }
 
+    pub fn instance_moved(&mut self, instance: usize) {
+        self.dirty_instances.push(instance);
+    }
+
+    pub fn needs_upload(&self) -> bool {
+        self.instances_dirty || !self.dirty_instances.is_empty()
+    }
+
+    /// copies the changed instances into the instance buffer, each run of neighbouring instances in one copy
+    pub fn upload_instances(&mut self, command_encoder: &mut wgpu::CommandEncoder, staging: &mut StagingBelt, device: &wgpu::Device) {
+        if self.instances_dirty {
+            self.dirty_instances.clear();
+            self.dirty_instances.extend(0..self.instances.len());
+            self.instances_dirty = false;
+        }
+        self.dirty_instances.sort_unstable();
+        self.dirty_instances.dedup();
+
+        let stride = std::mem::size_of::<TransformMatrix>();
+        let mut remaining = self.dirty_instances.as_slice();
+        while let Some(&start) = remaining.first() {
+            let run = remaining.iter().enumerate().take_while(|(offset, instance)| **instance == start + offset).count();
+            remaining = &remaining[run..];
+
+            let Some(instances) = self.instances.get(start..start + run) else {
+                continue
+            };
+            let Some(size) = wgpu::BufferSize::new((run * stride) as u64) else {
+                continue
+            };
+            let mut view = staging.write_buffer(command_encoder, &self.instance_buffer, (start * stride) as u64, size, device);
+            for (bytes, instance) in view.chunks_exact_mut(stride).zip(instances) {
+                bytes.copy_from_slice(bytemuck::bytes_of(&instance.to_wgpu_buffer()));
+            }
+        }
+        self.dirty_instances.clear();
+    }
+
     pub fn get_instance_buffer_raw(&self) -> Vec<TransformMatrix> {
         let raw_buffer = self.instances.iter().map(
             |data| {
@@ -311,6 +573,14 @@ impl Mesh {
     }
 }
 
+fn local_bounds(vertices: &[Vertex]) -> Option<(cgmath::Point3<f32>, cgmath::Point3<f32>)> {
+    let first = cgmath::Point3::from(vertices.first()?.position);
+    Some(vertices.iter().fold((first, first), |(min, max), vertex| {
+        let position = cgmath::Point3::from(vertex.position);
+        (min.zip(position, f32::min), max.zip(position, f32::max))
+    }))
+}
+
 #[allow(dead_code)]
 pub fn load_model_gltf(
     file: PathBuf,
@@ -333,28 +603,7 @@ pub fn load_model_gltf(
     let gltf_reader = BufReader::new(gltf_cursor);
     let gltf = Gltf::from_reader(gltf_reader)?;
 
-    let texture_bind_group_layout =
-        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
-            label: Some("texture_bind_group_layout"),
-        });
+    let texture_bind_group_layout = Material::bindgroup_layout(device);
 
     // Load buffers
     let mut buffer_data = Vec::new();
@@ -393,21 +642,6 @@ pub fn load_model_gltf(
                 )
                 .expect("Couldn't load diffuse");
 
-                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    layout: &texture_bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
-                        },
-                    ],
-                    label: None,
-                });
-
                 let name = material.name().unwrap_or("Default Material").to_string();
 
                 let new_texture = TextureRaw {
@@ -416,11 +650,13 @@ pub fn load_model_gltf(
                 };
                 textures.push(new_texture);
 
-                materials.push(Material {
+                materials.push(Material::new(
+                    device,
+                    &texture_bind_group_layout,
                     name,
                     diffuse_texture,
-                    bind_group,
-                });
+                    BlendMode::from_gltf(&material),
+                ));
             }
             gltf::image::Source::Uri { uri, mime_type: _ } => {
                 let path = Path::new(&user_model_directory).join(uri);
@@ -428,21 +664,6 @@ pub fn load_model_gltf(
                 let diffuse_texture =
                     Texture::from_bytes(&device, &queue, &bytes, uri).unwrap();
 
-                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    layout: &texture_bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
-                        },
-                    ],
-                    label: None,
-                });
-
                 let name = material.name().unwrap_or("Default Material").to_string();
 
                 let new_texture = TextureRaw {
@@ -451,11 +672,13 @@ pub fn load_model_gltf(
                 };
                 textures.push(new_texture);
 
-                materials.push(Material {
+                materials.push(Material::new(
+                    device,
+                    &texture_bind_group_layout,
                     name,
                     diffuse_texture,
-                    bind_group,
-                });
+                    BlendMode::from_gltf(&material),
+                ));
             }
         };
     }
@@ -465,13 +688,13 @@ pub fn load_model_gltf(
 
     let mut index_offset: u32 = 0;
 
-    println!("scenes: {:?}", gltf.scenes().len());
+    tracing::debug!(target: "telera::scene", "scenes: {}", gltf.scenes().len());
     for scene in gltf.scenes() {
-        println!("nodes: {:?}", scene.nodes().len());
+        tracing::debug!(target: "telera::scene", "nodes: {}", scene.nodes().len());
         for node in scene.nodes() {
             let node_transform = Matrix4::from(node.transform().matrix());
             if let Some(mesh) = node.mesh() {
-                println!("primitives: {:?}", mesh.primitives().len());
+                tracing::debug!(target: "telera::scene", "primitives: {}", mesh.primitives().len());
                 for primitive in mesh.primitives() {
                     let reader = primitive.reader(|buffer| Some(&buffer_data[buffer.index()]));
 
@@ -567,6 +790,7 @@ pub fn load_model_gltf(
         instances_shown: 0,
         instance_lookup,
         instances_dirty: false,
+        dirty_instances: Vec::new(),
         instances,
         instance_buffer
     };
@@ -605,7 +829,9 @@ pub fn load_model_gltf(
         label: None,
     });
 
-    println!("loading mesh {:?} complete", file_name);
+    let local_bounds = local_bounds(&mesh.base.vertices);
+
+    tracing::debug!(target: "telera::scene", "loading mesh {:?} complete", file_name);
 
     Ok(Model {
         //meshes,
@@ -615,7 +841,11 @@ pub fn load_model_gltf(
         transform_bind_group,
         transform_buffer,
         transform_dirty: false,
-        dir: "".to_string(),
-        filename: "".to_string(),
+        wireframe: false,
+        local_bounds,
+        selected: false,
+        selected_instances: std::collections::HashSet::new(),
+        dir: user_model_directory,
+        filename: file_name,
     })
 }
@@ -0,0 +1,175 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    graphics::{camera_controller::Camera, model::Vertex},
+    Model,
+    Transform,
+};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct OutlineUniform {
+    color: [f32; 4],
+    viewport: [f32; 2],
+    width: f32,
+    _padding: f32,
+}
+
+/// selection highlight drawn as an inflated back face hull behind each selected model or instance
+///
+/// the scene is drawn in a single pass without a stencil buffer, so the hull goes down first
+/// and the model drawn over it leaves only the rim visible
+pub struct OutlinePass {
+    pub color: [f32; 4],
+    /// rim width in pixels
+    pub width: f32,
+    uniform_buffer: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    pipeline: Option<wgpu::RenderPipeline>,
+}
+
+impl OutlinePass {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("outline_uniform_buffer"),
+            contents: bytemuck::cast_slice(&[OutlineUniform {
+                color: [1.0, 0.6, 0.1, 1.0],
+                viewport: [1.0, 1.0],
+                width: 3.0,
+                _padding: 0.0,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("outline_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("outline_bind_group"),
+        });
+
+        Self {
+            color: [1.0, 0.6, 0.1, 1.0],
+            width: 3.0,
+            uniform_buffer,
+            bind_group_layout,
+            bind_group,
+            pipeline: None,
+        }
+    }
+
+    pub fn build_pipeline(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, multi_sample_count: u32) {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Outline Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("outline_shader.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Outline Render Pipeline Layout"),
+            bind_group_layouts: &[
+                &Camera::bindgroup_layout(device),
+                &self.bind_group_layout,
+                &Transform::bindgroup_layout(device),
+            ],
+            push_constant_ranges: &[],
+        });
+
+        self.pipeline = Some(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Outline Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::buffer_description(), Transform::buffer_description()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                front_face: wgpu::FrontFace::Ccw,
+                // only the far side of the hull, so it never covers the model's own front faces
+                cull_mode: Some(wgpu::Face::Front),
+                ..Default::default()
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: multi_sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        }));
+    }
+
+    pub fn update(&self, viewport_size: (f32, f32), queue: &wgpu::Queue) {
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[OutlineUniform {
+                color: self.color,
+                viewport: [viewport_size.0.max(1.0), viewport_size.1.max(1.0)],
+                width: self.width,
+                _padding: 0.0,
+            }]),
+        );
+    }
+
+    /// expects the model's vertex, index and transform bindings to already be set,
+    /// leaves the outline bound at group 1 so the caller has to rebind its material
+    pub fn draw(&self, model: &Model, render_pass: &mut wgpu::RenderPass) {
+        let Some(pipeline) = &self.pipeline else {
+            return
+        };
+        if !model.selected && model.selected_instances.is_empty() {
+            return
+        }
+
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+
+        let shown = 1..model.mesh.instances_shown + 1;
+        match model.selected {
+            true => render_pass.draw_indexed(0..model.mesh.num_elements, 0, shown),
+            false => {
+                for instance in model.selected_instances.iter().map(|instance| *instance as u32) {
+                    if shown.contains(&instance) {
+                        render_pass.draw_indexed(0..model.mesh.num_elements, 0, instance..instance + 1);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+struct InstanceInput {
+    @location(5) model_matrix_0: vec4<f32>,
+    @location(6) model_matrix_1: vec4<f32>,
+    @location(7) model_matrix_2: vec4<f32>,
+    @location(8) model_matrix_3: vec4<f32>,
+};
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct OutlineUniform {
+    color: vec4<f32>,
+    viewport: vec2<f32>,
+    width: f32,
+};
+@group(1) @binding(0)
+var<uniform> outline: OutlineUniform;
+
+struct ModelMatrix {
+    transform: mat4x4<f32>,
+};
+@group(2) @binding(0)
+var<uniform> model_matrix: ModelMatrix;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(2) normal: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+) -> @builtin(position) vec4<f32> {
+    let instance_matrix = mat4x4<f32>(
+        instance.model_matrix_0,
+        instance.model_matrix_1,
+        instance.model_matrix_2,
+        instance.model_matrix_3,
+    );
+    let world = camera.view_proj * model_matrix.transform * instance_matrix;
+
+    let clip = world * vec4<f32>(model.position, 1.0);
+    let clip_normal = (world * vec4<f32>(model.normal, 0.0)).xy;
+
+    // push the hull out along the screen space normal so the rim is the same width at any distance
+    var direction = vec2<f32>(0.0, 0.0);
+    if length(clip_normal) > 0.0 {
+        direction = normalize(clip_normal);
+    }
+    let offset = direction * outline.width * 2.0 / outline.viewport * clip.w;
+    return vec4<f32>(clip.xy + offset, clip.zw);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return outline.color;
+}
@@ -0,0 +1,95 @@
+struct OverlayUniform {
+    view_proj: mat4x4<f32>,
+    inverse_view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> overlay: OverlayUniform;
+
+const GRID_FADE_DISTANCE: f32 = 60.0;
+
+struct GridOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+@vertex
+fn vs_grid(@builtin(vertex_index) index: u32) -> GridOutput {
+    // a single triangle that covers the whole screen
+    let ndc = vec2<f32>(
+        f32((index << 1u) & 2u) * 2.0 - 1.0,
+        f32(index & 2u) * 2.0 - 1.0,
+    );
+
+    var out: GridOutput;
+    out.clip_position = vec4<f32>(ndc, 0.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+fn unproject(ndc: vec2<f32>, depth: f32) -> vec3<f32> {
+    let point = overlay.inverse_view_proj * vec4<f32>(ndc, depth, 1.0);
+    return point.xyz / point.w;
+}
+
+// 1.0 on a line of the given spacing, fading to 0.0 a pixel away from it
+fn grid_lines(coord: vec2<f32>, spacing: f32) -> f32 {
+    let scaled = coord / spacing;
+    let derivative = fwidth(scaled);
+    let distance = abs(fract(scaled - 0.5) - 0.5) / derivative;
+    return 1.0 - min(min(distance.x, distance.y), 1.0);
+}
+
+@fragment
+fn fs_grid(in: GridOutput) -> @location(0) vec4<f32> {
+    let near = unproject(in.ndc, 0.0);
+    let far = unproject(in.ndc, 1.0);
+
+    // where the view ray crosses the y = 0 ground plane
+    let t = -near.y / (far.y - near.y);
+    let point = near + t * (far - near);
+
+    // derivatives have to be taken before anything is discarded
+    let minor = grid_lines(point.xz, 1.0);
+    let major = grid_lines(point.xz, 10.0);
+    let axis_width = fwidth(point.xz);
+
+    var color = vec3<f32>(0.35, 0.35, 0.35);
+    var alpha = max(minor * 0.4, major * 0.8);
+    if abs(point.z) < axis_width.y {
+        color = vec3<f32>(0.8, 0.15, 0.15);
+        alpha = 1.0;
+    }
+    if abs(point.x) < axis_width.x {
+        color = vec3<f32>(0.15, 0.3, 0.8);
+        alpha = 1.0;
+    }
+    alpha *= clamp(1.0 - length(point - near) / GRID_FADE_DISTANCE, 0.0, 1.0);
+
+    if t < 0.0 || t > 1.0 || alpha <= 0.0 {
+        discard;
+    }
+    return vec4<f32>(color, alpha);
+}
+
+struct GizmoInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec3<f32>,
+};
+
+struct GizmoOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+};
+
+@vertex
+fn vs_gizmo(in: GizmoInput) -> GizmoOutput {
+    var out: GizmoOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_gizmo(in: GizmoOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.color, 1.0);
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use wgpu::util::DeviceExt;
+
+use crate::graphics::camera_controller::Camera;
+
+/// a point on a polyline or in a point cloud
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl From<[f32; 3]> for PlotVertex {
+    fn from(position: [f32; 3]) -> Self {
+        Self { position, color: [1.0, 1.0, 1.0, 1.0] }
+    }
+}
+
+impl From<([f32; 3], [f32; 4])> for PlotVertex {
+    fn from((position, color): ([f32; 3], [f32; 4])) -> Self {
+        Self { position, color }
+    }
+}
+
+/// one line segment, expanded into a screen space quad in the vertex shader
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SegmentInstance {
+    start: [f32; 3],
+    start_color: [f32; 4],
+    end: [f32; 3],
+    end_color: [f32; 4],
+    width: f32,
+}
+
+impl SegmentInstance {
+    fn buffer_description() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+            0 => Float32x3,
+            1 => Float32x4,
+            2 => Float32x3,
+            3 => Float32x4,
+            4 => Float32,
+        ];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SegmentInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PointInstance {
+    position: [f32; 3],
+    color: [f32; 4],
+    size: f32,
+}
+
+impl PointInstance {
+    fn buffer_description() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
+            0 => Float32x3,
+            1 => Float32x4,
+            2 => Float32,
+        ];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<PointInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PlotUniform {
+    viewport: [f32; 2],
+    _padding: [f32; 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlotKind {
+    Polyline,
+    Points,
+}
+
+pub struct Plot {
+    pub kind: PlotKind,
+    pub vertices: Vec<PlotVertex>,
+    /// line width or point diameter, in pixels
+    width: f32,
+    instances: u32,
+    buffer: wgpu::Buffer,
+}
+
+impl Plot {
+    pub fn new(device: &wgpu::Device, kind: PlotKind, vertices: Vec<PlotVertex>, width: f32) -> Self {
+        let contents = match kind {
+            PlotKind::Polyline => bytemuck::cast_slice(&segments(&vertices, width)).to_vec(),
+            PlotKind::Points => bytemuck::cast_slice(&points(&vertices, width)).to_vec(),
+        };
+        let instances = match kind {
+            PlotKind::Polyline => vertices.len().saturating_sub(1),
+            PlotKind::Points => vertices.len(),
+        } as u32;
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("plot_instance_buffer"),
+            // an empty buffer can't be bound, keep at least one instance worth of space
+            contents: match contents.is_empty() {
+                true => &[0; std::mem::size_of::<SegmentInstance>()][..],
+                false => contents.as_slice(),
+            },
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            kind,
+            vertices,
+            width,
+            instances,
+            buffer,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn set_width(&mut self, width: f32, queue: &wgpu::Queue) {
+        self.width = width;
+        if self.instances == 0 {
+            return;
+        }
+        match self.kind {
+            PlotKind::Polyline => queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&segments(&self.vertices, width))),
+            PlotKind::Points => queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&points(&self.vertices, width))),
+        }
+    }
+
+    pub fn destroy(&self) {
+        self.buffer.destroy();
+    }
+}
+
+fn segments(vertices: &[PlotVertex], width: f32) -> Vec<SegmentInstance> {
+    vertices.windows(2).map(|pair| SegmentInstance {
+        start: pair[0].position,
+        start_color: pair[0].color,
+        end: pair[1].position,
+        end_color: pair[1].color,
+        width,
+    }).collect()
+}
+
+fn points(vertices: &[PlotVertex], size: f32) -> Vec<PointInstance> {
+    vertices.iter().map(|vertex| PointInstance {
+        position: vertex.position,
+        color: vertex.color,
+        size,
+    }).collect()
+}
+
+/// polylines and point clouds, drawn after the models with a constant pixel width
+pub struct Plots {
+    pub plots: HashMap<String, Plot>,
+    uniform_buffer: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    line_pipeline: Option<wgpu::RenderPipeline>,
+    point_pipeline: Option<wgpu::RenderPipeline>,
+}
+
+impl Plots {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("plot_uniform_buffer"),
+            contents: bytemuck::cast_slice(&[PlotUniform {
+                viewport: [1.0, 1.0],
+                _padding: [0.0; 2],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("plot_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("plot_bind_group"),
+        });
+
+        Self {
+            plots: HashMap::new(),
+            uniform_buffer,
+            bind_group_layout,
+            bind_group,
+            line_pipeline: None,
+            point_pipeline: None,
+        }
+    }
+
+    pub fn build_pipelines(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, multi_sample_count: u32) {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Plot Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("plot_shader.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Plot Render Pipeline Layout"),
+            bind_group_layouts: &[&Camera::bindgroup_layout(device), &self.bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = |label: &str, entry_point: &str, instance: wgpu::VertexBufferLayout| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader_module,
+                    entry_point: Some(entry_point),
+                    buffers: &[instance],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader_module,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: multi_sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        self.line_pipeline = Some(pipeline("Plot Line Pipeline", "vs_segment", SegmentInstance::buffer_description()));
+        self.point_pipeline = Some(pipeline("Plot Point Pipeline", "vs_point", PointInstance::buffer_description()));
+    }
+
+    /// expects the camera bind group to already be set at index 0
+    pub fn render(&self, viewport_size: (f32, f32), render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
+        if self.plots.is_empty() {
+            return;
+        }
+        let (Some(line_pipeline), Some(point_pipeline)) = (&self.line_pipeline, &self.point_pipeline) else {
+            return;
+        };
+
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[PlotUniform {
+            viewport: [viewport_size.0.max(1.0), viewport_size.1.max(1.0)],
+            _padding: [0.0; 2],
+        }]));
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+
+        for plot in self.plots.values().filter(|plot| plot.instances > 0) {
+            match plot.kind {
+                PlotKind::Polyline => render_pass.set_pipeline(line_pipeline),
+                PlotKind::Points => render_pass.set_pipeline(point_pipeline),
+            }
+            render_pass.set_vertex_buffer(0, plot.buffer.slice(..));
+            render_pass.draw(0..6, 0..plot.instances);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct PlotUniform {
+    viewport: vec2<f32>,
+};
+@group(1) @binding(0)
+var<uniform> plot: PlotUniform;
+
+struct SegmentInput {
+    @location(0) start: vec3<f32>,
+    @location(1) start_color: vec4<f32>,
+    @location(2) end: vec3<f32>,
+    @location(3) end_color: vec4<f32>,
+    @location(4) width: f32,
+};
+
+struct PointInput {
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) size: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    // -1 to 1 across the quad, only used to round points
+    @location(1) local: vec2<f32>,
+    @location(2) @interpolate(flat) rounded: u32,
+};
+
+// two triangles, x picks the end of the quad and y the side
+fn corner(index: u32) -> vec2<f32> {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+    );
+    return corners[index];
+}
+
+@vertex
+fn vs_segment(@builtin(vertex_index) index: u32, segment: SegmentInput) -> VertexOutput {
+    var out: VertexOutput;
+    let c = corner(index);
+
+    let start = camera.view_proj * vec4<f32>(segment.start, 1.0);
+    let end = camera.view_proj * vec4<f32>(segment.end, 1.0);
+
+    // widen perpendicular to the segment as it appears on screen
+    let start_screen = start.xy / start.w * plot.viewport;
+    let end_screen = end.xy / end.w * plot.viewport;
+    var direction = end_screen - start_screen;
+    if length(direction) > 0.0 {
+        direction = normalize(direction);
+    }
+    let normal = vec2<f32>(-direction.y, direction.x);
+
+    let clip = mix(start, end, c.x);
+    let offset = normal * c.y * segment.width / plot.viewport * clip.w;
+
+    out.clip_position = vec4<f32>(clip.xy + offset, clip.zw);
+    out.color = mix(segment.start_color, segment.end_color, c.x);
+    out.local = vec2<f32>(0.0, c.y);
+    out.rounded = 0u;
+    return out;
+}
+
+@vertex
+fn vs_point(@builtin(vertex_index) index: u32, point: PointInput) -> VertexOutput {
+    var out: VertexOutput;
+    let local = vec2<f32>(corner(index).x * 2.0 - 1.0, corner(index).y);
+
+    let clip = camera.view_proj * vec4<f32>(point.position, 1.0);
+    let offset = local * point.size / plot.viewport * clip.w;
+
+    out.clip_position = vec4<f32>(clip.xy + offset, clip.zw);
+    out.color = point.color;
+    out.local = local;
+    out.rounded = 1u;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if in.rounded == 1u && length(in.local) > 1.0 {
+        discard;
+    }
+    return in.color;
+}
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+
+/// each effect's parameters get their own slot in the uniform buffer, spaced for dynamic offsets
+const PARAM_STRIDE: u64 = 256;
+const MAX_EFFECTS: usize = 16;
+
+/// screen space effects applied to the scene before the ui is drawn on top, in list order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostEffect {
+    Fxaa,
+    /// scales by exposure then compresses with the operator
+    Tonemap { operator: Tonemapper, exposure: f32 },
+    /// adds a blurred copy of everything brighter than threshold
+    Bloom { threshold: f32, intensity: f32 },
+    Gamma { gamma: f32 },
+}
+
+/// curves for mapping scene brightness onto the display range
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Tonemapper {
+    /// exposure only, values above 1 are left for hdr displays to show
+    Linear,
+    Reinhard,
+    /// filmic curve, clamps to 1
+    #[default]
+    Aces,
+}
+
+impl PostEffect {
+    fn entry_point(&self) -> &'static str {
+        match self {
+            PostEffect::Fxaa => "fs_fxaa",
+            PostEffect::Tonemap { .. } => "fs_tonemap",
+            PostEffect::Bloom { .. } => "fs_bloom",
+            PostEffect::Gamma { .. } => "fs_gamma",
+        }
+    }
+
+    fn params(&self) -> [f32; 4] {
+        match *self {
+            PostEffect::Fxaa => [0.0; 4],
+            PostEffect::Tonemap { operator, exposure } => [exposure, operator as u32 as f32, 0.0, 0.0],
+            PostEffect::Bloom { threshold, intensity } => [threshold, intensity, 0.0, 0.0],
+            PostEffect::Gamma { gamma } => [gamma.max(0.001), 0.0, 0.0, 0.0],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PostUniform {
+    texel: [f32; 2],
+    _padding: [f32; 2],
+    params: [f32; 4],
+}
+
+/// the scene is drawn into one of these, each effect reads one and writes the other
+pub struct PostTargets {
+    size: (u32, u32),
+    format: wgpu::TextureFormat,
+    pub views: [wgpu::TextureView; 2],
+}
+
+impl PostTargets {
+    pub fn new(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Self {
+        let view = |label: &str| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                size: wgpu::Extent3d {
+                    width: config.width.max(1),
+                    height: config.height.max(1),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: config.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                label: Some(label),
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        Self {
+            size: (config.width, config.height),
+            format: config.format,
+            views: [view("post_target_a"), view("post_target_b")],
+        }
+    }
+
+    pub fn matches(&self, config: &wgpu::SurfaceConfiguration) -> bool {
+        self.size == (config.width, config.height) && self.format == config.format
+    }
+}
+
+pub struct PostProcessor {
+    pub effects: Vec<PostEffect>,
+    uniform_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    shader_module: wgpu::ShaderModule,
+    /// built the first time a format, sample count and entry point combination is used
+    pipelines: HashMap<(wgpu::TextureFormat, u32, bool, &'static str), wgpu::RenderPipeline>,
+}
+
+impl PostProcessor {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("post_uniform_buffer"),
+            size: PARAM_STRIDE * (MAX_EFFECTS as u64 + 1),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(std::mem::size_of::<PostUniform>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("post_bind_group_layout"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post Process Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Post Process Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("post_shader.wgsl").into()),
+        });
+
+        Self {
+            effects: Vec::new(),
+            uniform_buffer,
+            sampler,
+            bind_group_layout,
+            pipeline_layout,
+            shader_module,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// with nothing enabled the scene is drawn straight to the surface
+    pub fn is_active(&self) -> bool {
+        !self.effects.is_empty()
+    }
+
+    fn pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        multi_sample_count: u32,
+        depth: bool,
+        entry_point: &'static str,
+    ) -> &wgpu::RenderPipeline {
+        self.pipelines.entry((format, multi_sample_count, depth, entry_point)).or_insert_with(|| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&self.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &self.shader_module,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.shader_module,
+                    entry_point: Some(entry_point),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                // the final blit shares a pass with the ui, which has a depth attachment
+                depth_stencil: match depth {
+                    false => None,
+                    true => Some(wgpu::DepthStencilState {
+                        format: wgpu::TextureFormat::Depth32Float,
+                        depth_write_enabled: false,
+                        depth_compare: wgpu::CompareFunction::Always,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                },
+                multisample: wgpu::MultisampleState {
+                    count: multi_sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        })
+    }
+
+    fn bind_group(&self, device: &wgpu::Device, input: &wgpu::TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &self.uniform_buffer,
+                        offset: 0,
+                        size: wgpu::BufferSize::new(std::mem::size_of::<PostUniform>() as u64),
+                    }),
+                },
+            ],
+            label: Some("post_bind_group"),
+        })
+    }
+
+    /// runs every effect over the scene in `targets.views[0]`, returns the index of the view holding the result
+    pub fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        targets: &PostTargets,
+    ) -> usize {
+        let texel = [1.0 / targets.size.0.max(1) as f32, 1.0 / targets.size.1.max(1) as f32];
+        let effects = self.effects.iter().take(MAX_EFFECTS).copied().collect::<Vec<_>>();
+
+        for (slot, effect) in effects.iter().enumerate() {
+            queue.write_buffer(
+                &self.uniform_buffer,
+                slot as u64 * PARAM_STRIDE,
+                bytemuck::cast_slice(&[PostUniform {
+                    texel,
+                    _padding: [0.0; 2],
+                    params: effect.params(),
+                }]),
+            );
+        }
+
+        let mut input = 0;
+        for (slot, effect) in effects.iter().enumerate() {
+            let bind_group = self.bind_group(device, &targets.views[input]);
+            let pipeline = self.pipeline(device, targets.format, 1, false, effect.entry_point());
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("PostProcessPass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &targets.views[1 - input],
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[(slot as u64 * PARAM_STRIDE) as u32]);
+            render_pass.draw(0..3, 0..1);
+
+            input = 1 - input;
+        }
+        input
+    }
+
+    /// copies a whole frame drawn offscreen into a pass without depth or multisampling that targets the surface
+    pub fn present(
+        &mut self,
+        device: &wgpu::Device,
+        render_pass: &mut wgpu::RenderPass,
+        frame: &wgpu::TextureView,
+        format: wgpu::TextureFormat,
+    ) {
+        let bind_group = self.bind_group(device, frame);
+        let pipeline = self.pipeline(device, format, 1, false, "fs_copy");
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[0]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// copies the processed scene into a pass that targets the surface, before the ui is drawn
+    pub fn blit(
+        &mut self,
+        device: &wgpu::Device,
+        render_pass: &mut wgpu::RenderPass,
+        targets: &PostTargets,
+        output: usize,
+        multi_sample_count: u32,
+    ) {
+        let bind_group = self.bind_group(device, &targets.views[output]);
+        let pipeline = self.pipeline(device, targets.format, multi_sample_count, true, "fs_copy");
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[0]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
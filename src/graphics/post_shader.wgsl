@@ -0,0 +1,110 @@
+struct PostUniform {
+    texel: vec2<f32>,
+    params: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var t_input: texture_2d<f32>;
+@group(0) @binding(1)
+var s_input: sampler;
+@group(0) @binding(2)
+var<uniform> post: PostUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// one triangle that covers the whole screen
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn sample_input(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(t_input, s_input, uv, 0.0);
+}
+
+const LUMA = vec3<f32>(0.299, 0.587, 0.114);
+
+@fragment
+fn fs_copy(in: VertexOutput) -> @location(0) vec4<f32> {
+    return sample_input(in.uv);
+}
+
+@fragment
+fn fs_fxaa(in: VertexOutput) -> @location(0) vec4<f32> {
+    let center = sample_input(in.uv);
+    let luma_nw = dot(sample_input(in.uv + vec2<f32>(-1.0, -1.0) * post.texel).rgb, LUMA);
+    let luma_ne = dot(sample_input(in.uv + vec2<f32>(1.0, -1.0) * post.texel).rgb, LUMA);
+    let luma_sw = dot(sample_input(in.uv + vec2<f32>(-1.0, 1.0) * post.texel).rgb, LUMA);
+    let luma_se = dot(sample_input(in.uv + vec2<f32>(1.0, 1.0) * post.texel).rgb, LUMA);
+    let luma_m = dot(center.rgb, LUMA);
+
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // blur along the edge, not across it
+    var direction = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * (0.25 / 8.0), 1.0 / 128.0);
+    let scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, vec2<f32>(-8.0), vec2<f32>(8.0)) * post.texel;
+
+    let near = 0.5 * (
+        sample_input(in.uv + direction * (1.0 / 3.0 - 0.5)).rgb +
+        sample_input(in.uv + direction * (2.0 / 3.0 - 0.5)).rgb
+    );
+    let far = near * 0.5 + 0.25 * (
+        sample_input(in.uv - direction * 0.5).rgb +
+        sample_input(in.uv + direction * 0.5).rgb
+    );
+    let luma_far = dot(far, LUMA);
+
+    let color = select(far, near, luma_far < luma_min || luma_far > luma_max);
+    return vec4<f32>(color, center.a);
+}
+
+@fragment
+fn fs_tonemap(in: VertexOutput) -> @location(0) vec4<f32> {
+    let input = sample_input(in.uv);
+    let color = max(input.rgb * post.params.x, vec3<f32>(0.0));
+    let operator = u32(post.params.y);
+    // 0 linear, 1 reinhard, 2 aces
+    if operator == 0u {
+        return vec4<f32>(color, input.a);
+    }
+    if operator == 1u {
+        return vec4<f32>(color / (vec3<f32>(1.0) + color), input.a);
+    }
+    let mapped = (color * (2.51 * color + 0.03)) / (color * (2.43 * color + 0.59) + 0.14);
+    return vec4<f32>(clamp(mapped, vec3<f32>(0.0), vec3<f32>(1.0)), input.a);
+}
+
+@fragment
+fn fs_bloom(in: VertexOutput) -> @location(0) vec4<f32> {
+    let input = sample_input(in.uv);
+    let threshold = post.params.x;
+    let intensity = post.params.y;
+
+    var glow = vec3<f32>(0.0);
+    for (var x = -2; x <= 2; x++) {
+        for (var y = -2; y <= 2; y++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * post.texel * 2.0;
+            glow += max(sample_input(in.uv + offset).rgb - vec3<f32>(threshold), vec3<f32>(0.0));
+        }
+    }
+    return vec4<f32>(input.rgb + glow / 25.0 * intensity, input.a);
+}
+
+@fragment
+fn fs_gamma(in: VertexOutput) -> @location(0) vec4<f32> {
+    let input = sample_input(in.uv);
+    return vec4<f32>(pow(input.rgb, vec3<f32>(1.0 / post.params.x)), input.a);
+}
@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::thread::JoinHandle;
+
+use image::RgbaImage;
+
+use super::frame_capture::CaptureRequest;
+
+/// frames waiting on the encoder before rendering blocks
+const FRAME_QUEUE: usize = 8;
+
+/// picked from the output path, `.gif` makes an animated gif, a path without an extension is
+/// treated as a directory of numbered pngs, anything else goes to ffmpeg
+enum Sink {
+    PngSequence(PathBuf),
+    Gif(image::codecs::gif::GifEncoder<std::fs::File>),
+    #[cfg(feature = "video_export")]
+    Video(Option<std::process::Child>),
+}
+
+impl Sink {
+    fn open(path: &PathBuf) -> Result<Self, ()> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            None => {
+                std::fs::create_dir_all(path).map_err(|e| tracing::error!(target: "telera::render", "failed to create {}: {}", path.display(), e))?;
+                Ok(Sink::PngSequence(path.clone()))
+            }
+            Some(extension) if extension.eq_ignore_ascii_case("gif") => {
+                let file = std::fs::File::create(path).map_err(|e| tracing::error!(target: "telera::render", "failed to create {}: {}", path.display(), e))?;
+                let mut encoder = image::codecs::gif::GifEncoder::new_with_speed(file, 10);
+                encoder.set_repeat(image::codecs::gif::Repeat::Infinite).map_err(|_| ())?;
+                Ok(Sink::Gif(encoder))
+            }
+            #[cfg(feature = "video_export")]
+            Some(_) => Ok(Sink::Video(None)),
+            #[cfg(not(feature = "video_export"))]
+            Some(_) => {
+                tracing::error!(target: "telera::render", "video recording requires the \"video_export\" feature, record to a .gif or a directory instead");
+                Err(())
+            }
+        }
+    }
+
+    fn write(&mut self, frame: RgbaImage, index: u64, path: &PathBuf, fps: u32) -> Result<(), ()> {
+        match self {
+            Sink::PngSequence(directory) => {
+                frame.save(directory.join(format!("frame_{:05}.png", index))).map_err(|_| ())
+            }
+            Sink::Gif(encoder) => {
+                let delay = image::Delay::from_numer_denom_ms(1000, fps);
+                encoder.encode_frame(image::Frame::from_parts(frame, 0, 0, delay)).map_err(|_| ())
+            }
+            #[cfg(feature = "video_export")]
+            Sink::Video(encoder) => {
+                use std::io::Write;
+                use std::process::{Command, Stdio};
+
+                if encoder.is_none() {
+                    // frames are piped into ffmpeg as raw rgba, the size is only known once the first frame arrives
+                    let child = Command::new("ffmpeg")
+                        .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pix_fmt", "rgba"])
+                        .args(["-s", &format!("{}x{}", frame.width(), frame.height())])
+                        .args(["-r", &fps.to_string()])
+                        .args(["-i", "-", "-pix_fmt", "yuv420p"])
+                        .arg(path)
+                        .stdin(Stdio::piped())
+                        .spawn()
+                        .map_err(|e| tracing::error!(target: "telera::render", "failed to start ffmpeg: {}", e))?;
+                    *encoder = Some(child);
+                }
+
+                match encoder.as_mut().and_then(|child| child.stdin.as_mut()) {
+                    Some(stdin) => stdin.write_all(frame.as_raw()).map_err(|_| ()),
+                    None => Err(()),
+                }
+            }
+        }
+    }
+
+    fn finish(self) {
+        #[cfg(feature = "video_export")]
+        if let Sink::Video(Some(mut encoder)) = self {
+            drop(encoder.stdin.take());
+            let _ = encoder.wait();
+        }
+    }
+}
+
+pub struct Recorder {
+    pub viewport: String,
+    pub path: PathBuf,
+    pub fps: u32,
+    pub frames_written: u64,
+    /// encoding happens on a worker so slow png and gif compression doesn't stall rendering
+    frames: Option<SyncSender<RgbaImage>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Recorder {
+    pub fn new(viewport: &str, path: PathBuf, fps: u32) -> Result<Self, ()> {
+        if fps == 0 {
+            return Err(())
+        }
+        let mut sink = Sink::open(&path)?;
+
+        let (sender, receiver) = sync_channel::<RgbaImage>(FRAME_QUEUE);
+        let worker_path = path.clone();
+        let worker = std::thread::spawn(move || {
+            for (index, frame) in receiver.iter().enumerate() {
+                if sink.write(frame, index as u64, &worker_path, fps).is_err() {
+                    tracing::error!(target: "telera::render", "failed to write frame {} to {}", index, worker_path.display());
+                    break
+                }
+            }
+            sink.finish();
+        });
+
+        Ok(Self {
+            viewport: viewport.to_string(),
+            path,
+            fps,
+            frames_written: 0,
+            frames: Some(sender),
+            worker: Some(worker),
+        })
+    }
+
+    /// time of the next frame on the recording's fixed timestep, in seconds
+    pub fn timestamp(&self) -> f32 {
+        self.frames_written as f32 / self.fps as f32
+    }
+
+    /// a capture that queues the frame for the worker once it's read back, fails once the worker has stopped after a write error
+    pub fn capture(&mut self) -> Result<CaptureRequest, ()> {
+        let frames = self.frames.clone().ok_or(())?;
+        if self.worker.as_ref().is_none_or(|worker| worker.is_finished()) {
+            return Err(())
+        }
+        // counted when requested so `timestamp` moves on with every frame drawn, not every frame read back
+        self.frames_written += 1;
+        Ok(CaptureRequest::Color(Box::new(move |frame| {
+            let _ = frames.send(frame);
+        })))
+    }
+
+    /// blocks until every queued frame is encoded
+    pub fn finish(mut self) {
+        drop(self.frames.take());
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use crate::rkyv::{Archive, Deserialize, Serialize};
+use crate::graphics::{
+    camera_controller::{Camera, Projection},
+    model::{Model, Quaternion, Transform},
+};
+
+/// what `api.save_scene` writes, models are stored as references to their gltf files rather than their meshes
+#[derive(Archive, Deserialize, Serialize, Debug, PartialEq, Clone, Default)]
+pub struct SceneFile {
+    pub models: Vec<SceneModel>,
+    pub cameras: Vec<SceneCamera>,
+    /// viewport name and the camera it shows
+    pub viewport_cameras: Vec<(String, String)>,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct SceneModel {
+    pub name: String,
+    pub path: String,
+    pub transform: SceneTransform,
+    /// in the order they were added, without the hidden default instance
+    pub instances: Vec<(String, SceneTransform)>,
+    pub wireframe: bool,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+pub struct SceneTransform {
+    pub position: [f32; 3],
+    /// w, x, y, z
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct SceneCamera {
+    pub name: String,
+    pub eye: [f32; 3],
+    pub target: [f32; 3],
+    pub up: [f32; 3],
+    pub fovy: f32,
+    pub znear: f32,
+    pub zfar: f32,
+    /// half height of the view when orthographic
+    pub orthographic: Option<f32>,
+}
+
+impl From<&Transform> for SceneTransform {
+    fn from(transform: &Transform) -> Self {
+        Self {
+            position: transform.position.into(),
+            rotation: [transform.rotation.s, transform.rotation.v.x, transform.rotation.v.y, transform.rotation.v.z],
+            scale: transform.scale.into(),
+        }
+    }
+}
+
+impl From<&SceneTransform> for Transform {
+    fn from(transform: &SceneTransform) -> Self {
+        let [w, x, y, z] = transform.rotation;
+        Self {
+            position: transform.position.into(),
+            rotation: Quaternion::new(w, x, y, z),
+            scale: transform.scale.into(),
+        }
+    }
+}
+
+impl SceneModel {
+    pub fn new(name: &str, model: &Model) -> Self {
+        let mut instances = model.mesh.instance_lookup.iter()
+            .filter(|(_, index)| **index != 0)
+            .filter_map(|(instance_name, index)| {
+                model.mesh.instances.get(*index).map(|transform| (*index, instance_name.clone(), SceneTransform::from(transform)))
+            })
+            .collect::<Vec<_>>();
+        instances.sort_by_key(|(index, _, _)| *index);
+
+        Self {
+            name: name.to_string(),
+            path: PathBuf::from(&model.dir).join(&model.filename).to_string_lossy().to_string(),
+            transform: SceneTransform::from(&model.transform),
+            instances: instances.into_iter().map(|(_, instance_name, transform)| (instance_name, transform)).collect(),
+            wireframe: model.wireframe,
+        }
+    }
+}
+
+impl SceneCamera {
+    pub fn new(name: &str, camera: &Camera) -> Self {
+        Self {
+            name: name.to_string(),
+            eye: camera.eye.into(),
+            target: camera.target.into(),
+            up: camera.up.into(),
+            fovy: camera.fovy,
+            znear: camera.znear,
+            zfar: camera.zfar,
+            orthographic: match camera.projection.mode {
+                Projection::Perspective => None,
+                Projection::Orthographic { size } => Some(size),
+            },
+        }
+    }
+
+    pub fn to_camera(&self) -> Camera {
+        let mut camera = Camera {
+            eye: self.eye.into(),
+            target: self.target.into(),
+            up: self.up.into(),
+            fovy: self.fovy,
+            znear: self.znear,
+            zfar: self.zfar,
+            ..Default::default()
+        };
+        if let Some(size) = self.orthographic {
+            camera.projection.snap_to(Projection::Orthographic { size });
+        }
+        camera
+    }
+}
+
+impl SceneFile {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ()> {
+        rkyv::to_bytes::<rkyv::rancor::Error>(self)
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| tracing::error!(target: "telera::scene", "failed to archive scene: {}", e))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
+        // archived data has to sit on an aligned buffer, a plain file read doesn't guarantee that
+        let mut aligned = rkyv::util::AlignedVec::<16>::new();
+        aligned.extend_from_slice(bytes);
+        rkyv::from_bytes::<SceneFile, rkyv::rancor::Error>(&aligned)
+            .map_err(|e| tracing::error!(target: "telera::scene", "failed to read scene: {}", e))
+    }
+}
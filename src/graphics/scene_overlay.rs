@@ -0,0 +1,253 @@
+use cgmath::SquareMatrix;
+use wgpu::util::DeviceExt;
+
+use crate::graphics::camera_controller::Camera;
+
+/// size of the axis gizmo as a fraction of the viewport height
+const GIZMO_RADIUS: f32 = 0.12;
+const GIZMO_MARGIN: f32 = 0.06;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct OverlayUniform {
+    view_proj: [[f32; 4]; 4],
+    inverse_view_proj: [[f32; 4]; 4],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct GizmoVertex {
+    position: [f32; 2],
+    color: [f32; 3],
+}
+
+impl GizmoVertex {
+    fn buffer_description() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<GizmoVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ],
+        }
+    }
+}
+
+/// editor helpers drawn by the scene renderer: a ground grid on y = 0 and an xyz gizmo in the bottom left corner
+pub struct SceneOverlay {
+    pub show_grid: bool,
+    pub show_axis_gizmo: bool,
+    uniform_buffer: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    gizmo_buffer: wgpu::Buffer,
+    grid_pipeline: Option<wgpu::RenderPipeline>,
+    gizmo_pipeline: Option<wgpu::RenderPipeline>,
+}
+
+impl SceneOverlay {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay_uniform_buffer"),
+            contents: bytemuck::cast_slice(&[OverlayUniform {
+                view_proj: cgmath::Matrix4::identity().into(),
+                inverse_view_proj: cgmath::Matrix4::identity().into(),
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("overlay_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("overlay_bind_group"),
+        });
+
+        let gizmo_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gizmo_vertex_buffer"),
+            size: (std::mem::size_of::<GizmoVertex>() * 6) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            show_grid: false,
+            show_axis_gizmo: false,
+            uniform_buffer,
+            bind_group_layout,
+            bind_group,
+            gizmo_buffer,
+            grid_pipeline: None,
+            gizmo_pipeline: None,
+        }
+    }
+
+    pub fn build_pipelines(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, multi_sample_count: u32) {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Overlay Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("overlay_shader.wgsl").into()),
+        });
+
+        let targets = [Some(wgpu::ColorTargetState {
+            format,
+            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        // the scene is drawn in painter's order, the overlay neither tests nor writes depth
+        let depth_stencil = Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        });
+        let multisample = wgpu::MultisampleState {
+            count: multi_sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        };
+
+        let grid_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Grid Render Pipeline Layout"),
+            bind_group_layouts: &[&self.bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        self.grid_pipeline = Some(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Grid Render Pipeline"),
+            layout: Some(&grid_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_grid"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_grid"),
+                targets: &targets,
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            depth_stencil: depth_stencil.clone(),
+            multisample,
+            multiview: None,
+            cache: None,
+        }));
+
+        let gizmo_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Gizmo Render Pipeline Layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        self.gizmo_pipeline = Some(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Gizmo Render Pipeline"),
+            layout: Some(&gizmo_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_gizmo"),
+                buffers: &[GizmoVertex::buffer_description()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                ..Default::default()
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_gizmo"),
+                targets: &targets,
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            depth_stencil,
+            multisample,
+            multiview: None,
+            cache: None,
+        }));
+    }
+
+    /// drawn before the models so they sit on top of it
+    pub fn render_grid(&self, camera: &Camera, render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
+        if !self.show_grid {
+            return;
+        }
+        if let Some(pipeline) = &self.grid_pipeline {
+            let view_proj = camera.build_view_projection_matrix();
+            queue.write_buffer(
+                &self.uniform_buffer,
+                0,
+                bytemuck::cast_slice(&[OverlayUniform {
+                    view_proj: view_proj.into(),
+                    inverse_view_proj: view_proj.invert().unwrap_or(cgmath::Matrix4::identity()).into(),
+                }]),
+            );
+
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    /// drawn after the models so it is never covered
+    pub fn render_gizmo(&self, camera: &Camera, render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
+        if !self.show_axis_gizmo {
+            return;
+        }
+        if let Some(pipeline) = &self.gizmo_pipeline {
+            let view = cgmath::Matrix4::look_at_rh(camera.eye, camera.target, camera.up);
+
+            let radius = (GIZMO_RADIUS / camera.aspect, GIZMO_RADIUS);
+            let center = (
+                -1.0 + (GIZMO_MARGIN + GIZMO_RADIUS) / camera.aspect,
+                -1.0 + GIZMO_MARGIN + GIZMO_RADIUS,
+            );
+
+            let axes = [
+                (cgmath::Vector4::unit_x(), [0.8, 0.15, 0.15]),
+                (cgmath::Vector4::unit_y(), [0.15, 0.7, 0.15]),
+                (cgmath::Vector4::unit_z(), [0.15, 0.3, 0.8]),
+            ];
+            let mut vertices = Vec::<GizmoVertex>::with_capacity(6);
+            for (axis, color) in axes {
+                let direction = view * axis;
+                vertices.push(GizmoVertex {
+                    position: [center.0, center.1],
+                    color,
+                });
+                vertices.push(GizmoVertex {
+                    position: [center.0 + direction.x * radius.0, center.1 + direction.y * radius.1],
+                    color,
+                });
+            }
+            queue.write_buffer(&self.gizmo_buffer, 0, bytemuck::cast_slice(&vertices));
+
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_vertex_buffer(0, self.gizmo_buffer.slice(..));
+            render_pass.draw(0..6, 0..1);
+        }
+    }
+}
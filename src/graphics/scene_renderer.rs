@@ -1,41 +1,72 @@
-use wgpu::util::DeviceExt;
+use std::collections::HashMap;
+
+use wgpu::util::{DeviceExt, StagingBelt};
 
 use crate::{
     graphics::{
         camera_controller::{
             Camera,
             CameraController,
-            CameraUniform
+            CameraUniform,
         },
-        model::Vertex,
+        debug_draw::DebugDraw,
+        frustum::Frustum,
+        outline::OutlinePass,
+        plot::Plots,
+        model::{BlendMode, Material, Vertex},
+        scene_overlay::SceneOverlay,
+        skybox::{Environment, Skybox},
+        world_label::WorldLabels,
     },
-    texture::Texture,
     Model,
     Transform
 };
 
+pub const DEFAULT_CAMERA: &str = "default";
+/// instance uploads go through staging chunks of this many bytes, kept and reused from frame to frame
+const STAGING_CHUNK: u64 = 64 * 1024;
+
+/// counts for the most recent scene render, models only
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RenderStats {
+    pub draw_calls: u32,
+    pub triangles: u64,
+    pub instances: u64,
+    /// models skipped because none of their instances were in view
+    pub culled: u32,
+}
+
 pub struct SceneRenderer {
     pub camera_controller: CameraController,
-    pub camera: Camera,
+    pub cameras: HashMap<String, Camera>,
+    /// the camera used for the next render, set per viewport before drawing
+    pub active_camera: String,
     camera_uniform: CameraUniform,
     camera_buffer: wgpu::Buffer,
     camera_bind_group: wgpu::BindGroup,
 
     pub render_pipeline: Option<wgpu::RenderPipeline>,
+    wireframe_pipeline: Option<wgpu::RenderPipeline>,
+    blend_pipeline: Option<wgpu::RenderPipeline>,
+    pub debug_draw: DebugDraw,
+    pub outline: OutlinePass,
+    pub plots: Plots,
+    /// size in pixels of the viewport being drawn
+    pub viewport_size: (f32, f32),
+    pub skybox: Option<Skybox>,
+    pub overlay: SceneOverlay,
+    pub frustum_culling: bool,
+    pub stats: RenderStats,
+    pub labels: WorldLabels,
+    /// surface format and sample count the pipelines were built for
+    target: Option<(wgpu::TextureFormat, u32)>,
+    staging: StagingBelt,
 }
 
 #[allow(dead_code)]
 impl SceneRenderer {
     pub fn new(device: &wgpu::Device) -> Self {
-        let camera = Camera {
-            eye: (0.0, 1.0, 4.0).into(),
-            target: (0.0, 0.0, 0.0).into(),
-            up: cgmath::Vector3::unit_y(),
-            aspect: 1.0,
-            fovy: 45.0,
-            znear: 0.1,
-            zfar: 100.0,
-        };
+        let camera = Camera::default();
 
         let mut camera_uniform = CameraUniform::new();
         camera_uniform.update_view_proj(&camera);
@@ -57,13 +88,47 @@ impl SceneRenderer {
         
         Self {
             camera_controller: CameraController::new(5.0),
-            camera,
+            cameras: HashMap::from([(DEFAULT_CAMERA.to_string(), camera)]),
+            active_camera: DEFAULT_CAMERA.to_string(),
             camera_uniform,
             camera_buffer,
             camera_bind_group,
 
             render_pipeline: None,
+            wireframe_pipeline: None,
+            blend_pipeline: None,
+            debug_draw: DebugDraw::new(device),
+            outline: OutlinePass::new(device),
+            plots: Plots::new(device),
+            viewport_size: (1.0, 1.0),
+            skybox: None,
+            overlay: SceneOverlay::new(device),
+            frustum_culling: true,
+            stats: RenderStats::default(),
+            labels: WorldLabels::default(),
+            target: None,
+            staging: StagingBelt::new(STAGING_CHUNK),
+        }
+    }
+
+    /// copies the instances moved since the last frame, before the frame's render pass starts
+    pub fn upload_instances(&mut self, models: &mut HashMap<String, Model>, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if !models.values().any(|model| model.mesh.needs_upload()) {
+            return
+        }
+        let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Instance Upload Encoder"),
+        });
+        for model in models.values_mut().filter(|model| model.mesh.needs_upload()) {
+            model.mesh.upload_instances(&mut command_encoder, &mut self.staging, device);
         }
+        self.staging.finish();
+        queue.submit(std::iter::once(command_encoder.finish()));
+        self.staging.recall();
+    }
+
+    pub fn camera(&mut self) -> &mut Camera {
+        self.cameras.entry(self.active_camera.clone()).or_default()
     }
 
     pub fn build_shaders(
@@ -75,38 +140,118 @@ impl SceneRenderer {
         let mut pipeline_builder = ScenePipeline::new(config.format);
         pipeline_builder.add_buffer_layout(Vertex::buffer_description());
         pipeline_builder.add_buffer_layout(Transform::buffer_description());
+        let bindgroup_layouts = [
+            &Camera::bindgroup_layout(device),
+            &Material::bindgroup_layout(device),
+            &Transform::bindgroup_layout(device),
+        ];
         let render_pipeline = pipeline_builder.build_pipeline(
             &device,
-            &[
-                &Camera::bindgroup_layout(device),
-                &Texture::bindgroup_layout(device),
-                &Transform::bindgroup_layout(device),
-            ],
+            &bindgroup_layouts,
             multi_sample_count,
         );
 
         self.render_pipeline = Some(render_pipeline);
+
+        pipeline_builder.set_blend(wgpu::BlendState::ALPHA_BLENDING, false);
+        self.blend_pipeline = Some(pipeline_builder.build_pipeline(
+            &device,
+            &bindgroup_layouts,
+            multi_sample_count,
+        ));
+        pipeline_builder.set_blend(wgpu::BlendState::REPLACE, true);
+
+        if device.features().contains(wgpu::Features::POLYGON_MODE_LINE) {
+            pipeline_builder.set_polygon_mode(wgpu::PolygonMode::Line);
+            self.wireframe_pipeline = Some(pipeline_builder.build_pipeline(
+                &device,
+                &bindgroup_layouts,
+                multi_sample_count,
+            ));
+        }
+        self.debug_draw.build_pipeline(device, config.format, multi_sample_count);
+        self.outline.build_pipeline(device, config.format, multi_sample_count);
+        self.plots.build_pipelines(device, config.format, multi_sample_count);
+
+        self.overlay.build_pipelines(device, config.format, multi_sample_count);
+
+        self.target = Some((config.format, multi_sample_count));
+        if let Some(skybox) = &mut self.skybox {
+            skybox.build_pipeline(device, config.format, multi_sample_count);
+        }
+    }
+
+    pub fn set_environment(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, environment: &Environment) -> Result<(), ()> {
+        let mut skybox = Skybox::new(device, queue, environment)?;
+        if let Some(skybox) = &self.skybox {
+            skybox.destroy();
+        }
+        if let Some((format, multi_sample_count)) = self.target {
+            skybox.build_pipeline(device, format, multi_sample_count);
+        }
+        self.skybox = Some(skybox);
+        Ok(())
     }
 
-    pub fn render(&mut self, models: &mut [Model], render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
+    pub fn clear_environment(&mut self) {
+        if let Some(skybox) = self.skybox.take() {
+            skybox.destroy();
+        }
+    }
+
+    /// applies controller input to the active camera and uploads it, call once per frame before drawing
+    pub fn update_camera(&mut self, queue: &wgpu::Queue) {
+        let camera = self.cameras.entry(self.active_camera.clone()).or_default();
+        self.camera_controller.update_camera(camera);
+        self.camera_uniform.update_view_proj(camera);
+        queue.write_buffer(
+            &self.camera_buffer,
+            0,
+            bytemuck::cast_slice(&[self.camera_uniform]),
+        );
+    }
+
+    /// draws the skybox, if there is one, behind everything else
+    pub fn render_environment(&mut self, render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
+        if let Some(skybox) = &self.skybox {
+            let camera = self.cameras.entry(self.active_camera.clone()).or_default();
+            skybox.render(camera, render_pass, queue);
+        }
+    }
+
+    pub fn render(&mut self, models: &mut HashMap<String, Model>, render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
         match self.render_pipeline.as_mut() {
             None => return,
             Some(render_pipeline) => {
 
                 //render_pass.set_viewport(``x``, y, w, h, min_depth, max_depth);
 
-                self.camera_controller.update_camera(&mut self.camera);
-                self.camera_uniform.update_view_proj(&self.camera);
-                queue.write_buffer(
-                    &self.camera_buffer,
-                    0,
-                    bytemuck::cast_slice(&[self.camera_uniform]),
-                );
+                let camera = self.cameras.entry(self.active_camera.clone()).or_default();
+                self.overlay.render_grid(camera, render_pass, queue);
+
+                let frustum = Frustum::from_view_projection(&camera.build_view_projection_matrix());
+                self.outline.update(self.viewport_size, queue);
+                self.stats = RenderStats::default();
 
                 render_pass.set_pipeline(&render_pipeline);
                 render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
 
-                for model in models {
+                // opaque and masked models first, then blended ones from furthest to nearest
+                let mut models = models.values_mut().collect::<Vec<&mut Model>>();
+                let eye = camera.eye;
+                let is_blended = |model: &Model| model.materials.get(model.mesh.material)
+                    .is_some_and(|material| material.blend_mode == BlendMode::Blend);
+                let distance = |model: &Model| match model.bounds() {
+                    Some((min, max)) => cgmath::MetricSpace::distance2(eye, cgmath::EuclideanSpace::midpoint(min, max)),
+                    None => 0.0,
+                };
+                let mut order = (0..models.len()).filter(|index| !is_blended(&models[*index])).collect::<Vec<usize>>();
+                let mut blended = (0..models.len()).filter(|index| is_blended(&models[*index])).collect::<Vec<usize>>();
+                blended.sort_by(|a, b| distance(&models[*b]).total_cmp(&distance(&models[*a])));
+                order.append(&mut blended);
+
+                for index in order {
+                    let model = &mut *models[index];
                     if model.transform_dirty {
                         queue.write_buffer(
                             &model.transform_buffer,
@@ -115,16 +260,15 @@ impl SceneRenderer {
                         );
                         model.transform_dirty = false;
                     }
-                    if model.mesh.instances_dirty {
-                        queue.write_buffer(
-                            &model.mesh.instance_buffer, 
-                            0, 
-                            bytemuck::cast_slice(&model.mesh.get_instance_buffer_raw())
-                        );
-                        model.mesh.instances_dirty = false;
+                    if model.mesh.instances_shown == 0 {
+                        continue;
+                    }
+                    if self.frustum_culling
+                    && let Some((min, max)) = model.bounds()
+                    && !frustum.intersects_aabb(min, max) {
+                        self.stats.culled += 1;
+                        continue;
                     }
-                    let material = &model.materials[model.mesh.material];
-                    render_pass.set_bind_group(1, &material.bind_group, &[]);
                     render_pass.set_bind_group(2, &model.transform_bind_group, &[]);
                     render_pass.set_vertex_buffer(0, model.mesh.vertex_buffer_raw.slice(..));
                     render_pass.set_vertex_buffer(1, model.mesh.instance_buffer.slice(..));
@@ -132,10 +276,24 @@ impl SceneRenderer {
                         model.mesh.index_buffer_raw.slice(..),
                         wgpu::IndexFormat::Uint32,
                     );
-                    if model.mesh.instances_shown > 0 {
-                        render_pass.draw_indexed(0..model.mesh.num_elements, 0, 1..model.mesh.instances_shown+1);
+                    self.outline.draw(model, render_pass);
+
+                    match (model.wireframe, &self.wireframe_pipeline, &self.blend_pipeline) {
+                        (true, Some(wireframe_pipeline), _) => render_pass.set_pipeline(wireframe_pipeline),
+                        (false, _, Some(blend_pipeline)) if is_blended(model) => render_pass.set_pipeline(blend_pipeline),
+                        _ => render_pass.set_pipeline(&render_pipeline),
                     }
+                    let material = &model.materials[model.mesh.material];
+                    render_pass.set_bind_group(1, &material.bind_group, &[]);
+                    render_pass.draw_indexed(0..model.mesh.num_elements, 0, 1..model.mesh.instances_shown+1);
+                    self.stats.draw_calls += 1;
+                    self.stats.instances += model.mesh.instances_shown as u64;
+                    self.stats.triangles += (model.mesh.num_elements / 3) as u64 * model.mesh.instances_shown as u64;
                 }
+
+                self.plots.render(self.viewport_size, render_pass, queue);
+                self.debug_draw.render(render_pass, queue);
+                self.overlay.render_gizmo(camera, render_pass, queue);
             }
         }
     }
@@ -143,6 +301,9 @@ impl SceneRenderer {
 
 pub struct ScenePipeline {
     pixel_format: wgpu::TextureFormat,
+    polygon_mode: wgpu::PolygonMode,
+    blend: wgpu::BlendState,
+    depth_write: bool,
     vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'static>>,
 }
 
@@ -150,10 +311,22 @@ impl ScenePipeline {
     pub fn new(pixel_format: wgpu::TextureFormat) -> Self {
         Self {
             pixel_format,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            blend: wgpu::BlendState::REPLACE,
+            depth_write: true,
             vertex_buffer_layouts: Vec::new(),
         }
     }
 
+    pub fn set_polygon_mode(&mut self, polygon_mode: wgpu::PolygonMode) {
+        self.polygon_mode = polygon_mode;
+    }
+
+    pub fn set_blend(&mut self, blend: wgpu::BlendState, depth_write: bool) {
+        self.blend = blend;
+        self.depth_write = depth_write;
+    }
+
     pub fn add_buffer_layout(&mut self, layout: wgpu::VertexBufferLayout<'static>) {
         self.vertex_buffer_layouts.push(layout);
     }
@@ -181,7 +354,7 @@ impl ScenePipeline {
 
         let render_targets = [Some(wgpu::ColorTargetState {
             format: self.pixel_format,
-            blend: Some(wgpu::BlendState::REPLACE),
+            blend: Some(self.blend),
             write_mask: wgpu::ColorWrites::ALL,
         })];
 
@@ -200,7 +373,7 @@ impl ScenePipeline {
                 front_face: wgpu::FrontFace::Ccw,
                 cull_mode: Some(wgpu::Face::Back),
                 unclipped_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
+                polygon_mode: self.polygon_mode,
                 conservative: false,
             },
             fragment: Some(wgpu::FragmentState {
@@ -211,9 +384,9 @@ impl ScenePipeline {
             }),
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Always, // 1.
-                stencil: wgpu::StencilState::default(),       // 2.
+                depth_write_enabled: self.depth_write,
+                depth_compare: wgpu::CompareFunction::LessEqual, // 1.
+                stencil: wgpu::StencilState::default(),          // 2.
                 bias: wgpu::DepthBiasState::default(),
             }),
             multisample: wgpu::MultisampleState {
@@ -51,7 +51,18 @@ var t_diffuse: texture_2d<f32>;
 @group(1) @binding(1)
 var s_diffuse: sampler;
 
+struct MaterialUniform {
+    alpha_cutoff: f32,
+};
+@group(1) @binding(2)
+var<uniform> material: MaterialUniform;
+
 @fragment
 fn fs_main(in:VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    // zero for opaque and blended materials, so only masked ones discard
+    if color.a < material.alpha_cutoff {
+        discard;
+    }
+    return color;
 }
\ No newline at end of file
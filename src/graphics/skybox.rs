@@ -0,0 +1,272 @@
+use cgmath::SquareMatrix;
+use image::{DynamicImage, GenericImageView};
+use wgpu::util::DeviceExt;
+
+use crate::graphics::camera_controller::Camera;
+
+pub enum Environment {
+    /// a single panorama, usually loaded from an .hdr file
+    Equirectangular(DynamicImage),
+    /// faces ordered +x, -x, +y, -y, +z, -z, all the same size
+    Cubemap([DynamicImage; 6]),
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SkyboxUniform {
+    inverse_view_proj: [[f32; 4]; 4],
+    exposure: f32,
+    _padding: [f32; 3],
+}
+
+pub struct Skybox {
+    texture: wgpu::Texture,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    cube: bool,
+    pipeline: Option<wgpu::RenderPipeline>,
+    pub exposure: f32,
+}
+
+/// environment maps are kept as linear floats so hdr values survive, 8 bit images are assumed to be srgb
+fn linear_pixels(image: &DynamicImage) -> Vec<f32> {
+    let is_hdr = matches!(image, DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_));
+    let mut pixels = image.to_rgba32f().into_raw();
+    if !is_hdr {
+        for pixel in pixels.chunks_mut(4) {
+            for channel in &mut pixel[..3] {
+                *channel = channel.powf(2.2);
+            }
+        }
+    }
+    pixels
+}
+
+impl Skybox {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, environment: &Environment) -> Result<Self, ()> {
+        let faces: Vec<&DynamicImage> = match environment {
+            Environment::Equirectangular(image) => vec![image],
+            Environment::Cubemap(faces) => faces.iter().collect(),
+        };
+        let cube = faces.len() == 6;
+
+        let (width, height) = faces[0].dimensions();
+        if width == 0 || height == 0 || faces.iter().any(|face| face.dimensions() != (width, height)) {
+            return Err(())
+        }
+        if cube && width != height {
+            return Err(())
+        }
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: faces.len() as u32,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("skybox_texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba32Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        for (layer, face) in faces.iter().enumerate() {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    aspect: wgpu::TextureAspect::All,
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x: 0, y: 0, z: layer as u32 },
+                },
+                bytemuck::cast_slice(&linear_pixels(face)),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(16 * width),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            );
+        }
+
+        let view_dimension = match cube {
+            true => wgpu::TextureViewDimension::Cube,
+            false => wgpu::TextureViewDimension::D2,
+        };
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(view_dimension),
+            ..Default::default()
+        });
+        // 32 bit float textures can't be filtered without an optional device feature
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("skybox_uniform_buffer"),
+            contents: bytemuck::cast_slice(&[SkyboxUniform {
+                inverse_view_proj: cgmath::Matrix4::identity().into(),
+                exposure: 1.0,
+                _padding: [0.0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // the cube and panorama textures sit on different bindings so one shader module serves both
+        let texture_binding = match cube {
+            true => 3,
+            false => 0,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: texture_binding,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("skybox_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: texture_binding,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("skybox_bind_group"),
+        });
+
+        Ok(Self {
+            texture,
+            bind_group_layout,
+            bind_group,
+            uniform_buffer,
+            cube,
+            pipeline: None,
+            exposure: 1.0,
+        })
+    }
+
+    pub fn build_pipeline(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, multi_sample_count: u32) {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Skybox Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("skybox_shader.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Skybox Render Pipeline Layout"),
+            bind_group_layouts: &[&self.bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Skybox Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some(match self.cube {
+                    true => "fs_cube",
+                    false => "fs_equirectangular",
+                }),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            // the sky sits behind everything, it never writes depth
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: multi_sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        self.pipeline = Some(pipeline);
+    }
+
+    pub fn render(&self, camera: &Camera, render_pass: &mut wgpu::RenderPass, queue: &wgpu::Queue) {
+        if let Some(pipeline) = &self.pipeline {
+            let inverse_view_proj = camera.build_view_projection_matrix()
+                .invert()
+                .unwrap_or(cgmath::Matrix4::identity());
+            queue.write_buffer(
+                &self.uniform_buffer,
+                0,
+                bytemuck::cast_slice(&[SkyboxUniform {
+                    inverse_view_proj: inverse_view_proj.into(),
+                    exposure: self.exposure,
+                    _padding: [0.0; 3],
+                }]),
+            );
+
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    pub fn destroy(&self) {
+        self.texture.destroy();
+        self.uniform_buffer.destroy();
+    }
+}
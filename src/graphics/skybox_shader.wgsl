@@ -0,0 +1,58 @@
+struct SkyboxUniform {
+    inverse_view_proj: mat4x4<f32>,
+    exposure: f32,
+};
+@group(0) @binding(2)
+var<uniform> skybox: SkyboxUniform;
+
+@group(0) @binding(0)
+var t_equirectangular: texture_2d<f32>;
+@group(0) @binding(1)
+var s_environment: sampler;
+@group(0) @binding(3)
+var t_cube: texture_cube<f32>;
+
+const PI: f32 = 3.14159265;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // a single triangle that covers the whole screen
+    let ndc = vec2<f32>(
+        f32((index << 1u) & 2u) * 2.0 - 1.0,
+        f32(index & 2u) * 2.0 - 1.0,
+    );
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(ndc, 1.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+fn view_direction(ndc: vec2<f32>) -> vec3<f32> {
+    let near = skybox.inverse_view_proj * vec4<f32>(ndc, 0.0, 1.0);
+    let far = skybox.inverse_view_proj * vec4<f32>(ndc, 1.0, 1.0);
+    return normalize(far.xyz / far.w - near.xyz / near.w);
+}
+
+@fragment
+fn fs_equirectangular(in: VertexOutput) -> @location(0) vec4<f32> {
+    let direction = view_direction(in.ndc);
+    let uv = vec2<f32>(
+        atan2(direction.z, direction.x) / (2.0 * PI) + 0.5,
+        acos(clamp(direction.y, -1.0, 1.0)) / PI,
+    );
+    let color = textureSampleLevel(t_equirectangular, s_environment, uv, 0.0).rgb;
+    return vec4<f32>(color * skybox.exposure, 1.0);
+}
+
+@fragment
+fn fs_cube(in: VertexOutput) -> @location(0) vec4<f32> {
+    let direction = view_direction(in.ndc);
+    let color = textureSampleLevel(t_cube, s_environment, direction, 0.0).rgb;
+    return vec4<f32>(color * skybox.exposure, 1.0);
+}
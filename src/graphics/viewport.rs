@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
 use winit::dpi::PhysicalSize;
 use winit::event_loop::ActiveEventLoop;
@@ -6,17 +6,29 @@ use winit::window::{Window, WindowAttributes};
 
 use crate::graphics::{
     depth_texture::DepthTexture,
+    frame_capture::{CaptureRequest, PendingCapture},
     graphics_context::GraphicsContext,
     multi_sample_texture::MultiSampleTexture,
+    post_process::PostTargets,
 };
 
 pub struct Viewport {
     pub window: Arc<Window>,
     pub page: String,
+    /// pages drawn as modal layers over `page`, the last one is on top and the only one that gets input
+    pub dialogs: Vec<String>,
     pub surface: wgpu::Surface<'static>,
     pub config: wgpu::SurfaceConfiguration,
     pub depth_texture: DepthTexture,
     pub multi_sample_texture: MultiSampleTexture,
+    /// only allocated once a post effect is enabled
+    pub post_targets: Option<PostTargets>,
+    /// frames that are read back are drawn here and copied onto the surface, not every surface can be copied from
+    pub capture_target: Option<wgpu::Texture>,
+    /// read back from the next frame drawn
+    pub capture_requests: Vec<CaptureRequest>,
+    /// in the order they were drawn
+    pub pending_captures: VecDeque<PendingCapture>,
 }
 
 pub trait BuildViewport {
@@ -26,6 +38,7 @@ pub trait BuildViewport {
         page: String,
         ctx: &GraphicsContext,
         multi_sample_count: u32,
+        preferred_format: Option<wgpu::TextureFormat>,
     ) -> Viewport;
 }
 
@@ -36,6 +49,7 @@ impl BuildViewport for WindowAttributes {
         page: String,
         ctx: &GraphicsContext,
         multi_sample_count: u32,
+        preferred_format: Option<wgpu::TextureFormat>,
     ) -> Viewport {
         let window = Arc::new(event_loop.create_window(self).unwrap());
 
@@ -45,16 +59,20 @@ impl BuildViewport for WindowAttributes {
 
         let surface_capabilities = surface.get_capabilities(&ctx.adapter);
 
-        let surface_format = surface_capabilities
-            .formats
-            .iter()
-            .copied()
-            .filter(|f| f.is_srgb())
-            .next()
+        // the preferred format is only a request, a surface that can't present it falls back to srgb
+        let surface_format = preferred_format
+            .filter(|f| surface_capabilities.formats.contains(f))
+            .or_else(|| surface_capabilities.formats.iter().copied().find(|f| f.is_srgb()))
             .unwrap_or(surface_capabilities.formats[0]);
 
+        // copying out of the surface is needed for frame capture, but not every backend allows it
+        let usage = match surface_capabilities.usages.contains(wgpu::TextureUsages::COPY_SRC) {
+            true => wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            false => wgpu::TextureUsages::RENDER_ATTACHMENT,
+        };
+
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: surface_format,
             width: size.width,
             height: size.height,
@@ -74,10 +92,15 @@ impl BuildViewport for WindowAttributes {
         Viewport {
             window,
             page,
+            dialogs: Vec::new(),
             surface,
             config,
             depth_texture,
             multi_sample_texture,
+            post_targets: None,
+            capture_target: None,
+            capture_requests: Vec::new(),
+            pending_captures: VecDeque::new(),
         }
     }
 }
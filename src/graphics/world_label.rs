@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Point3};
+
+use crate::{
+    graphics::camera_controller::Camera,
+    ui_toolkit::ui_renderer::UIRenderer,
+};
+
+/// just behind the ui, which counts down from 0.1, and in front of anything in the scene
+const LABEL_DEPTH: f32 = 0.1001;
+
+/// text pinned to a point in the scene, drawn with the ui's text renderer so it shares its glyph atlas
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldLabel {
+    pub text: String,
+    pub position: Point3<f32>,
+    /// logical pixels when `billboard` is set, otherwise world units so it shrinks with distance
+    pub size: f32,
+    /// keeps the same size on screen at any distance
+    pub billboard: bool,
+    pub color: [u8; 4],
+}
+
+#[derive(Default)]
+pub struct WorldLabels {
+    labels: HashMap<usize, WorldLabel>,
+    next_id: usize,
+}
+
+impl WorldLabels {
+    pub fn add(&mut self, label: WorldLabel) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.labels.insert(id, label);
+        id
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut WorldLabel> {
+        self.labels.get_mut(&id)
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<WorldLabel> {
+        self.labels.remove(&id)
+    }
+
+    pub fn clear(&mut self) {
+        self.labels.clear();
+    }
+
+    /// projects every label into the viewport and hands it to the ui text pass, furthest first so near labels win
+    pub fn queue(&self, camera: &Camera, viewport_size: (f32, f32), dpi_scale: f32, ui_renderer: &mut UIRenderer) {
+        let up = camera.up.normalize();
+
+        let mut visible = self.labels.values().filter_map(|label| {
+            let (x, y) = camera.world_to_screen(label.position, viewport_size)?;
+            let font_size = match label.billboard {
+                true => label.size * dpi_scale,
+                false => {
+                    let (_, top) = camera.world_to_screen(label.position + up * label.size, viewport_size)?;
+                    (y - top).abs()
+                }
+            };
+            let distance = (label.position - camera.eye).magnitude2();
+            (font_size >= 1.0).then_some((distance, label, x, y, font_size))
+        }).collect::<Vec<_>>();
+        visible.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        for (_, label, x, y, font_size) in visible {
+            let [r, g, b, a] = label.color;
+            ui_renderer.draw_label(&label.text, font_size, x, y, glyphon::Color::rgba(r, g, b, a), LABEL_DEPTH);
+        }
+    }
+}
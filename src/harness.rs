@@ -0,0 +1,379 @@
+use std::{fmt::{self, Debug}, fs, path::{Path, PathBuf}, str::FromStr};
+
+use image::{Rgba, RgbaImage};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::{
+    App, API, Binder, EventHandler, ParserDataAccess, load_layout_directory,
+    ui_toolkit::page_set::set_layout_size,
+};
+
+/// format the harness renders in, the same bytes end up in the png
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+/// frames are a fixed time apart so scrolling comes out the same on every run
+const FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// why a harness call or a golden image check failed
+#[derive(Debug)]
+pub enum HarnessError {
+    /// `set_page` hasn't been called yet
+    NoPage,
+    /// no layout defines the page
+    UnknownPage(String),
+    /// nothing with the `id` config was laid out
+    NoElement(String),
+    /// the api has no ui renderer to draw with
+    NoRenderer,
+    /// the frame couldn't be read back from the gpu
+    ReadBack,
+    ReadImage { path: PathBuf, error: image::ImageError },
+    WriteImage { path: PathBuf, error: image::ImageError },
+    /// more of the render changed from the golden image than the tolerance allows, `diff` shows where
+    ImageChanged { path: PathBuf, changed: usize, total: usize, diff: PathBuf },
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HarnessError::NoPage => write!(f, "the harness has no page, call set_page first"),
+            HarnessError::UnknownPage(page) => write!(f, "there's no page named {}", page),
+            HarnessError::NoElement(id) => write!(f, "there's no element with the id {}", id),
+            HarnessError::NoRenderer => write!(f, "the api has no ui renderer"),
+            HarnessError::ReadBack => write!(f, "couldn't read the frame back from the gpu"),
+            HarnessError::ReadImage { path, error } => write!(f, "couldn't read golden image {}: {}", path.display(), error),
+            HarnessError::WriteImage { path, error } => write!(f, "couldn't write golden image {}: {}", path.display(), error),
+            HarnessError::ImageChanged { path, changed, total, diff } => write!(
+                f, "{} of {} pixels changed from golden image {}, see {}", changed, total, path.display(), diff.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+/// renders the app's pages without a window or event loop, for golden image tests,
+/// and drives them with simulated input for integration tests.
+/// ```ignore
+/// let mut harness = TestHarness::new(MyApp::default());
+/// let image = harness.render_to_image("main", (800, 600)).unwrap();
+/// assert_golden_image!(image, "tests/golden/main.png");
+///
+/// harness.click("name_input").unwrap();
+/// harness.type_text("hello").unwrap();
+/// harness.click("save_button").unwrap();
+/// ```
+/// `api.proxy()` works but what it sends goes nowhere, there's no loop to receive it.
+/// each page is laid out as a viewport named after it, for `api.element_bounds`
+pub struct TestHarness<UserEvents, UserApp>
+where
+    UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>+'static,
+    <UserEvents as FromStr>::Err: Debug+Default,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
+{
+    api: API,
+    binder: Binder<UserEvents, UserApp>,
+    app: UserApp,
+    /// the page frames lay out, `None` until one is set
+    page: Option<String>,
+    /// in pixels
+    size: (u32, u32),
+}
+
+impl<UserEvents, UserApp> TestHarness<UserEvents, UserApp>
+where
+    UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>+'static,
+    <UserEvents as FromStr>::Err: Debug+Default,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
+{
+    /// loads the layouts in `src/layouts` like `run` does and initializes the app. viewports it creates are never opened
+    pub fn new(app: UserApp) -> Self {
+        Self::with_layouts(app, "src/layouts")
+    }
+
+    pub fn with_layouts(mut app: UserApp, directory: impl AsRef<Path>) -> Self {
+        let (binder, _) = load_layout_directory(directory.as_ref()).unwrap_or_else(|_| (Binder::new(), Default::default()));
+        let mut api = API::new(None);
+        api.dpi_scale = 1.0;
+        // off the page until something is hovered
+        api.mouse_poistion = (-1.0, -1.0);
+        app.initialize(&mut api);
+        Self { api, binder, app, page: None, size: (800, 600) }
+    }
+
+    pub fn api(&mut self) -> &mut API {
+        &mut self.api
+    }
+
+    pub fn app(&mut self) -> &mut UserApp {
+        &mut self.app
+    }
+
+    /// for pages built in code, see `Page`
+    pub fn binder(&mut self) -> &mut Binder<UserEvents, UserApp> {
+        &mut self.binder
+    }
+
+    /// the page `frame` and the input methods lay out, `render_to_image` sets it too
+    pub fn set_page(&mut self, page: &str) {
+        self.page = Some(page.to_string());
+    }
+
+    /// in pixels, 800 x 600 to start with
+    pub fn set_size(&mut self, size: (u32, u32)) {
+        self.size = size;
+    }
+
+    /// lays out `page` at `size` in pixels and draws it, scene excluded. nothing is hovered unless the input methods moved the pointer.
+    /// events the layout sends are dispatched to the app like in a window
+    pub fn render_to_image(&mut self, page: &str, size: (u32, u32)) -> Result<RgbaImage, HarnessError> {
+        self.set_page(page);
+        self.set_size(size);
+        self.run_frame(true)?.ok_or(HarnessError::NoRenderer)
+    }
+
+    /// runs one frame like a window would, input set through `api()` since the last frame included
+    pub fn frame(&mut self) -> Result<(), HarnessError> {
+        self.run_frame(false).map(|_| ())
+    }
+
+    /// moves the pointer over the middle of the element with the `id` config and runs a frame, so the layout sees it hovered
+    pub fn hover(&mut self, id: &str) -> Result<(), HarnessError> {
+        let page = self.page.clone().ok_or(HarnessError::NoPage)?;
+        let bounds = match self.api.element_bounds(&page, id) {
+            Some(bounds) => bounds,
+            // nothing was laid out yet
+            None => {
+                self.frame()?;
+                self.api.element_bounds(&page, id).ok_or_else(|| HarnessError::NoElement(id.to_string()))?
+            }
+        };
+        let center = (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+        self.api.mouse_delta = (0.0, 0.0);
+        self.api.mouse_poistion = (center.0 * self.api.dpi_scale, center.1 * self.api.dpi_scale);
+        self.frame()
+    }
+
+    /// hovers the element, then presses and releases the left button over it, a frame each
+    pub fn click(&mut self, id: &str) -> Result<(), HarnessError> {
+        self.hover(id)?;
+
+        let api = &mut self.api;
+        api.left_mouse_pressed = true;
+        api.left_mouse_down = true;
+        api.x_at_click = api.mouse_poistion.0 / api.dpi_scale;
+        api.y_at_click = api.mouse_poistion.1 / api.dpi_scale;
+        self.frame()?;
+
+        let api = &mut self.api;
+        api.left_mouse_down = false;
+        api.left_mouse_released = true;
+        api.left_mouse_clicked = true;
+        self.frame()
+    }
+
+    /// types `text` into whatever has focus in one frame, click a text box first to focus it
+    pub fn type_text(&mut self, text: &str) -> Result<(), HarnessError> {
+        for character in text.chars() {
+            self.api.keys_pressed.push(match character {
+                ' ' => Key::Named(NamedKey::Space),
+                '\n' => Key::Named(NamedKey::Enter),
+                '\t' => Key::Named(NamedKey::Tab),
+                character => Key::Character(character.to_string().into()),
+            });
+        }
+        self.frame()
+    }
+
+    pub fn press_key(&mut self, key: Key) -> Result<(), HarnessError> {
+        self.api.keys_pressed.push(key);
+        self.frame()
+    }
+
+    /// hovers the element and turns the mouse wheel over it by `lines`, negative scrolls towards the end of the content
+    pub fn scroll(&mut self, id: &str, lines: f32) -> Result<(), HarnessError> {
+        self.hover(id)?;
+        self.api.scroll_delta_distance = (0.0, lines);
+        self.frame()
+    }
+
+    /// the image when `draw` is set
+    fn run_frame(&mut self, draw: bool) -> Result<Option<RgbaImage>, HarnessError> {
+        let page = self.page.clone().ok_or(HarnessError::NoPage)?;
+        let api = &mut self.api;
+
+        self.app.update(api);
+        for (name, page) in self.app.take_pages() {
+            self.binder.insert_page(&name, page);
+        }
+        for (event, event_context) in std::mem::take(&mut api.emitted) {
+            match event.downcast::<UserEvents>() {
+                Ok(event) => api.dispatch_event(&mut self.app, *event, event_context),
+                Err(_) => tracing::error!(target: "telera::app", "emitted event isn't of the app's event type"),
+            }
+        }
+
+        let mut ui_renderer = api.ui_renderer.take().ok_or(HarnessError::NoRenderer)?;
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: FORMAT,
+            width: self.size.0.max(1),
+            height: self.size.1.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: Vec::new(),
+        };
+        if ui_renderer.render_pipeline.is_none() {
+            ui_renderer.build_shaders(&api.ctx.device, &api.ctx.queue, &config, 1);
+        }
+
+        ui_renderer.dpi_scale = api.dpi_scale;
+        api.stage_ui_resources(&mut ui_renderer);
+        ui_renderer.resize((config.width as i32, config.height as i32), &api.ctx.queue);
+
+        let (width, height) = (config.width as f32 / api.dpi_scale, config.height as f32 / api.dpi_scale);
+        api.ui_layout.set_layout_dimensions(width, height);
+        set_layout_size(width, height);
+
+        let pointer = (api.mouse_poistion.0 / api.dpi_scale, api.mouse_poistion.1 / api.dpi_scale);
+        ui_renderer.pointer = pointer;
+        api.ui_layout.pointer_state(pointer.0, pointer.1, api.left_mouse_down);
+        api.ui_layout.update_scroll_containers(false, api.scroll_delta_distance.0, api.scroll_delta_distance.1, FRAME_TIME);
+        api.scroll_delta_distance = (0.0, 0.0);
+
+        api.ui_layout.begin_layout(ui_renderer);
+        api.hovered_element = None;
+        let events = self.binder.set_named_page(&page, &[], api, &mut self.app);
+        let (render_commands, mut ui_renderer) = api.ui_layout.end_layout();
+        api.record_layout(&page, (width, height));
+
+        let image = match draw {
+            true => Some(api.ctx.render_offscreen(&config, |render_pass, device, queue, config| {
+                ui_renderer.render_layout(render_commands, render_pass, device, queue, config);
+            })),
+            false => None,
+        };
+        api.ui_renderer = Some(ui_renderer);
+
+        let events = events.map_err(|_| HarnessError::UnknownPage(page))?;
+        for (event, event_context) in events {
+            api.dispatch_event(&mut self.app, event, event_context);
+        }
+        api.clear_frame_input();
+        image.transpose().map_err(|_| HarnessError::ReadBack)
+    }
+}
+
+/// how far a render may drift from its golden image before `check_golden_image` fails
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageTolerance {
+    /// 0 to 1, how different a pixel has to look to count as changed. the default lets antialiasing and dithering through
+    pub threshold: f32,
+    /// 0 to 1, the share of pixels that may change
+    pub max_changed: f32,
+}
+
+impl Default for ImageTolerance {
+    fn default() -> Self {
+        Self { threshold: 0.1, max_changed: 0.001 }
+    }
+}
+
+/// the pixels that look different between two images
+pub struct ImageDiff {
+    pub changed: usize,
+    pub total: usize,
+    /// the expected image faded, with the changed pixels in red
+    pub image: RgbaImage,
+}
+
+impl ImageDiff {
+    pub fn within(&self, tolerance: ImageTolerance) -> bool {
+        self.changed as f32 <= self.total as f32 * tolerance.max_changed
+    }
+}
+
+/// compares by how different the colors look rather than by their channels, like pixelmatch.
+/// images of different sizes differ everywhere
+pub fn compare_images(expected: &RgbaImage, actual: &RgbaImage, threshold: f32) -> ImageDiff {
+    let total = (expected.width() * expected.height()).max(actual.width() * actual.height()) as usize;
+    if expected.dimensions() != actual.dimensions() {
+        return ImageDiff { changed: total, total, image: actual.clone() }
+    }
+
+    // 35215 is the largest difference there is, between black and white
+    let max_delta = 35215.0 * threshold * threshold;
+    let mut changed = 0;
+    let mut image = RgbaImage::new(expected.width(), expected.height());
+    for ((expected, actual), diff) in expected.pixels().zip(actual.pixels()).zip(image.pixels_mut()) {
+        match color_delta(expected, actual) > max_delta {
+            true => {
+                changed += 1;
+                *diff = Rgba([255, 0, 0, 255]);
+            }
+            false => {
+                let (y, _, _) = yiq(expected);
+                let faded = (255.0 - (255.0 - y) * 0.1) as u8;
+                *diff = Rgba([faded, faded, faded, 255]);
+            }
+        }
+    }
+    ImageDiff { changed, total, image }
+}
+
+/// brightness and the two color axes of a pixel blended over white
+fn yiq(pixel: &Rgba<u8>) -> (f32, f32, f32) {
+    let alpha = pixel[3] as f32 / 255.0;
+    let [r, g, b] = [0, 1, 2].map(|channel| 255.0 + (pixel[channel] as f32 - 255.0) * alpha);
+    (
+        r * 0.29889531 + g * 0.58662247 + b * 0.11448223,
+        r * 0.59597799 - g * 0.27417610 - b * 0.32180189,
+        r * 0.21147017 - g * 0.52261711 + b * 0.31114694,
+    )
+}
+
+fn color_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f32 {
+    let (ya, ia, qa) = yiq(a);
+    let (yb, ib, qb) = yiq(b);
+    0.5053 * (ya - yb).powi(2) + 0.299 * (ia - ib).powi(2) + 0.1957 * (qa - qb).powi(2)
+}
+
+/// compares `image` with the png at `path`. when they differ too much the render and the diff are written next to it,
+/// as `name.actual.png` and `name.diff.png`. the png is written instead when it doesn't exist yet or `TELERA_UPDATE_SNAPSHOTS` is set
+pub fn check_golden_image(image: &RgbaImage, path: impl AsRef<Path>, tolerance: ImageTolerance) -> Result<(), HarnessError> {
+    let path = path.as_ref();
+    if std::env::var_os("TELERA_UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        return image.save(path).map_err(|error| HarnessError::WriteImage { path: path.to_path_buf(), error })
+    }
+
+    let expected = image::open(path)
+        .map_err(|error| HarnessError::ReadImage { path: path.to_path_buf(), error })?
+        .to_rgba8();
+    let diff = compare_images(&expected, image, tolerance.threshold);
+    if diff.within(tolerance) {
+        return Ok(())
+    }
+
+    let beside = |suffix: &str| path.with_extension(format!("{}.png", suffix));
+    let _ = image.save(beside("actual"));
+    let _ = diff.image.save(beside("diff"));
+    Err(HarnessError::ImageChanged { path: path.to_path_buf(), changed: diff.changed, total: diff.total, diff: beside("diff") })
+}
+
+/// Fails the test when `image` looks different from the png at `path`, relative to the tested crate's manifest.
+/// The png is written when it's missing, or when the `TELERA_UPDATE_SNAPSHOTS` environment variable is set.
+/// Takes an optional `ImageTolerance`, the default allows for antialiasing differences between gpus.
+#[macro_export]
+macro_rules! assert_golden_image {
+    ($image:expr, $path:expr) => {
+        $crate::assert_golden_image!($image, $path, $crate::ImageTolerance::default())
+    };
+    ($image:expr, $path:expr, $tolerance:expr) => {
+        if let Err(error) = $crate::check_golden_image(&$image, ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path), $tolerance) {
+            panic!("{}", error);
+        }
+    };
+}
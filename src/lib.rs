@@ -1,10 +1,9 @@
 use std::{
     collections::HashMap, 
     fmt::Debug, 
-    fs::read_to_string, 
     path::{Path, PathBuf}, 
     str::FromStr, 
-    time::Instant
+    time::{Duration, Instant}
 };
 pub use rkyv;
 use notify::{
@@ -29,6 +28,10 @@ use winit::{
         ControlFlow, 
         EventLoop, 
         EventLoopProxy
+    }, keyboard::{
+        Key,
+        ModifiersState,
+        NamedKey
     }
 };
 pub use winit::{
@@ -36,17 +39,52 @@ pub use winit::{
         Window,
         WindowId,
         WindowAttributes,
+        Theme,
     },
     dpi::LogicalSize
 };
 pub use image::DynamicImage;
+pub use wgpu;
 pub use symbol_table;
 pub use telera_macros::*;
 
+mod tasks;
+pub use tasks::{ApiProxy, Task};
+
+mod undo;
+pub use undo::{Command, UndoStack};
+
+mod allocations;
+pub use allocations::CountingAllocator;
+
+mod snapshot;
+pub use snapshot::{LayoutSnapshot, SnapshotDraw, SnapshotError};
+
+mod harness;
+pub use harness::{TestHarness, HarnessError, ImageTolerance, ImageDiff, compare_images, check_golden_image};
+
 mod graphics;
+#[cfg(feature = "egui")]
+use graphics::egui_layer::EguiLayer;
+#[cfg(feature = "egui")]
+pub use egui;
 pub use graphics::{
+    graphics_context::RenderHookStage,
+    skybox::Environment,
+    scene_renderer::RenderStats,
+    world_label::WorldLabel,
+    plot::PlotVertex,
+    texture::{TextureFilter, TextureOptions, TextureWrap},
+    post_process::{PostEffect, Tonemapper},
+    camera_controller::{
+        Camera,
+        CameraController,
+        ControllerMode,
+        Projection,
+    },
     model::{
         load_model_gltf,
+        BlendMode,
         Model,
         Transform,
         TransformMatrix,
@@ -56,52 +94,92 @@ pub use graphics::{
     }
 };
 use graphics::{
-    graphics_context::GraphicsContext,
+    graphics_context::{GraphicsContext, RenderStage},
+    post_process::PostProcessor,
     viewport::Viewport,
     viewport::BuildViewport,
-    scene_renderer::SceneRenderer,
+    scene_renderer::{SceneRenderer, DEFAULT_CAMERA},
+    scene_file::{SceneCamera, SceneFile, SceneModel},
+    compute::ComputeRunner,
+    frame_capture::CaptureRequest,
+    plot::{Plot, PlotKind},
+    recorder::Recorder,
     texture
 };
 const MULTI_SAMPLE_COUNT: u32 = 1;
+/// layout engine multiplies scroll deltas by this before applying them
+const SCROLL_DELTA_SCALE: f32 = 10.0;
+/// zoom limits and how much Ctrl+= and Ctrl+- change it
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 3.0;
+const UI_SCALE_STEP: f32 = 0.1;
+/// most `fixed_update`s run in one go when the loop falls behind
+const MAX_FIXED_CATCH_UP: u32 = 5;
 
 mod ui_toolkit;
 pub use ui_toolkit::{
     ui_renderer::UIImageDescriptor,
+    ui_shapes::{CustomBounds, CustomElementRenderer},
+    ui_renderer::UILayer,
+    ui_renderer::ScrollbarStyle,
+    ui_renderer::FontFace,
     layout_types::*,
     page_set::*,
     markdown::*,
+    layout_error::{LayoutError, LayoutDiagnostic},
+    builder::{Page, ElementBuilder, TextBuilder},
     treeview::TreeViewItem,
     treeview::TreeViewEvents,
+    treeview::TreeViewCheckbox,
+    treeview::TreeSelectionMode,
 };
 use ui_toolkit::{
+    checkbox::{BoolWrites, Binding},
+    treeview::TreeViewState,
+    textbox::EditorState,
+    menu_bar::MenuBarState,
     ui_renderer::UIRenderer,
+    ui_renderer::Scrollbar,
+    ui_renderer::TextLayout,
     ui_renderer::CustomLayoutSettings,
     ui_shapes::CustomElement,
     telera_layout::LayoutEngine,
+    markdown::{parse_markdown, process_layout_file_with_imports, MarkdownTrees},
 };
 
 #[allow(dead_code)]
 enum InternalEvents{
     Hi,
     RebuildLayout(PathBuf),
+    Shutdown,
+    /// sent by an `ApiProxy` on another thread
+    Redraw,
+    Emit(Box<dyn std::any::Any + Send>, Option<EventContext>),
 }
 
 #[derive(Clone)]
 pub struct EventContext{
     pub text: Option<String>,
     pub code: Option<u32>,
-    pub code2: Option<u32>
+    pub code2: Option<u32>,
+    pub value: Option<f32>
 }
 
 impl EventContext {
     pub fn new() -> Self {
-        EventContext { text: None, code: None, code2: None }
+        EventContext { text: None, code: None, code2: None, value: None }
     }
     pub fn from_code(code: u32) -> Self {
-        EventContext { text: None, code: Some(code), code2: None }
+        EventContext { text: None, code: Some(code), code2: None, value: None }
     }
     pub fn from_code2(code2: u32) -> Self {
-        EventContext { text: None, code: None, code2: Some(code2) }
+        EventContext { text: None, code: None, code2: Some(code2), value: None }
+    }
+    pub fn from_value(value: f32) -> Self {
+        EventContext { text: None, code: None, code2: None, value: Some(value) }
+    }
+    pub fn from_text(text: &str) -> Self {
+        EventContext { text: Some(text.to_string()), code: None, code2: None, value: None }
     }
     pub fn code(mut self, code: u32) -> Self {
         self.code = Some(code);
@@ -111,6 +189,59 @@ impl EventContext {
         self.code2 = Some(code2);
         self
     }
+    pub fn value(mut self, value: f32) -> Self {
+        self.value = Some(value);
+        self
+    }
+}
+
+/// where an element was laid out, in layout units from the top left of its viewport
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && y >= self.y && x <= self.x + self.width && y <= self.y + self.height
+    }
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.x + other.width && other.x < self.x + self.width
+        && self.y < other.y + other.height && other.y < self.y + self.height
+    }
+}
+
+/// what an event filter does with an event, see `API::add_event_filter`
+pub enum Filter<Event> {
+    /// on to the next filter, then the event's handler
+    Continue,
+    /// dropped, later filters and the handler never see it
+    Consume,
+    /// this event goes on instead
+    Replace(Event, Option<EventContext>),
+}
+
+type EventFilter<Event> = Box<dyn FnMut(&Event, Option<&EventContext>) -> Filter<Event>>;
+
+/// called with the name of the viewport being drawn
+type RenderHook = Box<dyn FnMut(&str, &mut wgpu::RenderPass<'static>, &wgpu::Device, &wgpu::Queue, &wgpu::SurfaceConfiguration)>;
+
+/// how long the last frame took on the cpu, from `api.frame_stats()`.
+/// the render times are for recording gpu commands, not for the gpu running them
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    /// the whole redraw, the others are parts of it
+    pub frame: Duration,
+    pub layout: Duration,
+    pub ui_render: Duration,
+    pub scene_render: Duration,
+    /// heap allocations on any thread while laying out, `None` unless the app installed `CountingAllocator`
+    pub layout_allocations: Option<usize>,
+    /// draw calls the ui took, text not counted
+    pub ui_draw_calls: u32,
 }
 
 pub trait EventHandler {
@@ -129,6 +260,22 @@ pub trait App{
     /// 
     /// This will be called at the beginning of each render loop
     fn update(&mut self, api: &mut API){}
+
+    /// called at the rate set with `api.set_fixed_rate`, whether or not any events come in.
+    /// `dt` is always the same, in seconds, so simulations step deterministically
+    fn fixed_update(&mut self, api: &mut API, dt: f32){}
+
+    /// called when the os switches between light and dark, `system.dark_mode` in layouts already follows it
+    fn on_theme_changed(&mut self, api: &mut API, theme: Theme){}
+
+    /// called once before the event loop exits, whether from closing the last window, `api.exit()` or ctrl-c
+    /// 
+    /// flush files, stop workers and persist settings here
+    fn shutdown(&mut self, api: &mut API){}
+
+    /// draws debug panels with egui over the ui of every viewport, while `api.show_debug_ui` is on
+    #[cfg(feature = "egui")]
+    fn debug_ui(&mut self, ui: &mut egui::Ui){}
 }
 
 pub struct API{
@@ -136,13 +283,50 @@ pub struct API{
 
     ctx: GraphicsContext,
     pub scene_renderer: SceneRenderer,
+    post_processor: PostProcessor,
+    /// ask for a float surface on the first window, later windows match whatever it got
+    hdr_output: bool,
+    compute: ComputeRunner,
     ui_renderer: Option<UIRenderer>,
     pub ui_layout: LayoutEngine<UIRenderer, UIImageDescriptor, CustomElement, CustomLayoutSettings>,
-    model_ids: HashMap<String, usize>,
-    models: Vec<Model>,
+    /// by the name they were loaded under
+    models: HashMap<String, Model>,
     
     viewport_lookup: bimap::BiMap<String, WindowId>,
     viewports: HashMap<WindowId, Viewport>,
+    /// viewport name to camera name, viewports without an entry use the default camera
+    viewport_cameras: HashMap<String, String>,
+    /// viewport name to the zoom the user picked, kept when its window is closed and opened again
+    ui_scales: HashMap<String, f32>,
+    textures: HashMap<String, texture::Texture>,
+    recorder: Option<Recorder>,
+    exit_requested: bool,
+    frame_stats: FrameStats,
+    /// the app's data version each viewport last drew
+    drawn_versions: HashMap<WindowId, u64>,
+    /// what each viewport's last layout drew, only kept while `record_layout_snapshots` is on
+    layout_snapshots: Option<HashMap<WindowId, LayoutSnapshot>>,
+    /// the frame stats overlay, toggled with F12
+    show_frame_stats: bool,
+    /// egui over each viewport's ui, made when the viewport is first drawn with it
+    #[cfg(feature = "egui")]
+    egui_layers: HashMap<WindowId, EguiLayer>,
+    #[cfg(feature = "egui")]
+    show_debug_ui: bool,
+    /// time between `fixed_update`s and when the next one is due, `None` when they're off
+    fixed_step: Option<(Duration, Instant)>,
+    /// events queued with `emit`, the app's event type is only known where they're dispatched
+    emitted: Vec<(Box<dyn std::any::Any>, Option<EventContext>)>,
+    /// `None` without an event loop, like in a `TestHarness`
+    app_events: Option<EventLoopProxy<InternalEvents>>,
+    /// `EventFilter`s of the app's event type, run in the order they were added
+    event_filters: Vec<Box<dyn std::any::Any>>,
+    /// drawn into every viewport's frame, in the order they were added
+    render_hooks: Vec<(String, RenderHookStage, RenderHook)>,
+    /// what layouts resolve while they're laid out, kept until the frame is drawn
+    frame_arena: bumpalo::Bump,
+    /// `Command`s of the app's type, boxed so `API` doesn't need it
+    undo_stack: UndoStack<Box<dyn std::any::Any>>,
 
     pub event_string: String,
 
@@ -152,7 +336,8 @@ pub struct API{
     left_mouse_clicked: bool,
     left_mouse_double_clicked: bool,
     left_mouse_clicked_timer: Option<Instant>,
-    _left_mouse_dbl_clicked_timer: Option<Instant>,
+    /// when the last click landed, a second one soon after is a double click
+    left_mouse_dbl_clicked_timer: Option<Instant>,
 
     right_mouse_pressed: bool,
     right_mouse_down: bool,
@@ -163,6 +348,57 @@ pub struct API{
     pub x_at_click: f32,
     pub y_at_click: f32,
     pub focus: u32,
+    /// keys pressed since the last redraw
+    keys_pressed: Vec<Key>,
+    bool_writes: BoolWrites,
+    numeric_writes: Vec<((symbol_table::GlobalSymbol, Option<(symbol_table::GlobalSymbol, usize)>), f32)>,
+    /// slider or drag-value being dragged and its value when the drag started
+    slider_drag: Option<(u32, f32)>,
+    /// drag-value being typed into and what's been typed
+    numeric_edit: Option<(u32, String)>,
+    /// the text editor with focus
+    text_editor: Option<(u32, EditorState)>,
+    text_writes: Vec<(Binding, String)>,
+    /// opacity of every open layout element, children start with their parent's
+    opacity: Vec<f32>,
+    /// id for the next element configured, the first element of a keyed list item
+    item_key: Option<String>,
+    /// id of the innermost element with one under the pointer, found while laying out
+    hovered_element: Option<String>,
+    /// layout engine ids of the elements with an `id` config, for floating elements attached to them
+    element_ids: HashMap<String, u32>,
+    /// layout files that failed to reload and the page each one holds, shown until it reloads or is dismissed
+    layout_errors: HashMap<PathBuf, (Option<String>, Vec<LayoutError>)>,
+    /// element with a tooltip under the pointer and when the pointer got there
+    tooltip_hover: Option<(u32, Instant)>,
+    /// when every viewport is redrawn next without any input, for tooltips waiting out their delay
+    redraw_at: Option<Instant>,
+    /// the open context menu and where it was opened, only one is open at a time
+    context_menu: Option<(u32, (f32, f32))>,
+    menu_bar: Option<MenuBarState>,
+    /// selection and renaming of every treeview drawn, by source and list item
+    treeviews: HashMap<Binding, TreeViewState>,
+    modifiers: ModifiersState,
+    /// clock for toolkit animations
+    started: Instant,
+    /// font ids by the names layouts use
+    font_ids: HashMap<String, u16>,
+    /// fonts added while the renderer was busy laying out
+    staged_fonts: Vec<(u16, FontFace)>,
+    staged_fallback_fonts: Vec<Vec<u8>>,
+    /// svgs by atlas name, rasterized again when they're drawn at a new dpi scale
+    #[cfg(feature = "svg")]
+    svgs: HashMap<String, ui_toolkit::svg::SvgImage>,
+    /// scrollbars each viewport drew last frame
+    scrollbars: HashMap<WindowId, Vec<Scrollbar>>,
+    /// scrollbar whose thumb is held and the pointer position it was last moved to
+    scrollbar_drag: Option<(usize, (f32, f32))>,
+    /// text editor lines each viewport drew last frame
+    text_layouts: HashMap<WindowId, Vec<TextLayout>>,
+    /// the ones of the viewport being laid out, for placing clicks in the text
+    editor_lines: Vec<TextLayout>,
+    /// each viewport's bounds and where its last layout put the elements with an `id` config, by viewport name
+    laid_out: HashMap<String, (Rect, HashMap<String, Rect>)>,
 
     pub dpi_scale: f32,
     pub mouse_poistion: (f32, f32),
@@ -171,13 +407,240 @@ pub struct API{
     scroll_delta_distance: (f32, f32),
 }
 
+struct HeldInput {
+    keys: Vec<Key>,
+    left: [bool; 5],
+    right: [bool; 4],
+}
+
 // private api functions
 impl API{
+    /// no window is made until the app asks for a viewport, `app_events` is `None` when there's no event loop at all
+    fn new(app_events: Option<EventLoopProxy<InternalEvents>>) -> Self {
+        let ctx = GraphicsContext::new();
+        let scene_renderer = SceneRenderer::new(&ctx.device);
+        let post_processor = PostProcessor::new(&ctx.device);
+        let ui_renderer = Some(UIRenderer::new(&ctx.device, &ctx.queue));
+
+        API {
+            staged_windows: Vec::new(),
+            ctx,
+            scene_renderer,
+            post_processor,
+            hdr_output: false,
+            compute: ComputeRunner::default(),
+            ui_renderer,
+            ui_layout: LayoutEngine::<UIRenderer, UIImageDescriptor, CustomElement, CustomLayoutSettings>::new((1.0, 1.0)),
+            models: HashMap::new(),
+            viewport_lookup: bimap::BiMap::new(),
+            viewports: HashMap::new(),
+            viewport_cameras: HashMap::new(),
+            ui_scales: HashMap::new(),
+            textures: HashMap::new(),
+            recorder: None,
+            exit_requested: false,
+            frame_stats: FrameStats::default(),
+            drawn_versions: HashMap::new(),
+            layout_snapshots: None,
+            show_frame_stats: false,
+            #[cfg(feature = "egui")]
+            egui_layers: HashMap::new(),
+            #[cfg(feature = "egui")]
+            show_debug_ui: true,
+            fixed_step: None,
+            emitted: Vec::new(),
+            event_filters: Vec::new(),
+            render_hooks: Vec::new(),
+            undo_stack: UndoStack::new(),
+            frame_arena: bumpalo::Bump::new(),
+            app_events,
+
+            event_string: "".to_string(),
+
+            left_mouse_pressed: false,
+            left_mouse_down: false,
+            left_mouse_released: false,
+            left_mouse_clicked: false,
+            left_mouse_double_clicked: false,
+            left_mouse_clicked_timer: None,
+            left_mouse_dbl_clicked_timer: None,
+
+            right_mouse_pressed: false,
+            right_mouse_down: false,
+            right_mouse_released: false,
+            right_mouse_clicked: false,
+            right_mouse_clicked_timer: None,
+
+            x_at_click: 0.0,
+            y_at_click: 0.0,
+            focus: 0,
+            keys_pressed: Vec::new(),
+            bool_writes: BoolWrites::default(),
+            numeric_writes: Vec::new(),
+            slider_drag: None,
+            numeric_edit: None,
+            text_editor: None,
+            text_writes: Vec::new(),
+            opacity: Vec::new(),
+            item_key: None,
+            hovered_element: None,
+            element_ids: HashMap::new(),
+            layout_errors: HashMap::new(),
+            tooltip_hover: None,
+            redraw_at: None,
+            context_menu: None,
+            menu_bar: None,
+            treeviews: HashMap::new(),
+            modifiers: ModifiersState::empty(),
+            started: Instant::now(),
+            font_ids: HashMap::new(),
+            staged_fonts: Vec::new(),
+            staged_fallback_fonts: Vec::new(),
+            #[cfg(feature = "svg")]
+            svgs: HashMap::new(),
+            scrollbars: HashMap::new(),
+            scrollbar_drag: None,
+            text_layouts: HashMap::new(),
+            editor_lines: Vec::new(),
+            laid_out: HashMap::new(),
+        
+            dpi_scale: 0.0,
+            mouse_poistion: (0.0,0.0),
+            mouse_delta: (0.0,0.0),
+            scroll_delta_time: Instant::now(),
+            scroll_delta_distance: (0.0, 0.0),
+        }
+    }
     fn request_redraw_viewport(&mut self, window_id: WindowId){
         if let Some(viewport) = self.viewports.get_mut(&window_id) {
             viewport.window.request_redraw();
         }
     }
+    fn request_redraw_all(&mut self) {
+        for viewport in self.viewports.values() {
+            viewport.window.request_redraw();
+        }
+    }
+    /// wakes the event loop at `at` to redraw every viewport, the earliest of several requests wins
+    fn request_redraw_at(&mut self, at: Instant) {
+        if self.redraw_at.is_none_or(|scheduled| at < scheduled) {
+            self.redraw_at = Some(at);
+        }
+    }
+    /// the center of a model's bounds in the viewport being drawn, in layout units
+    fn model_on_screen(&self, model: &str) -> Option<(f32, f32)> {
+        let (min, max) = self.models.get(model)?.bounds()?;
+        let center = cgmath::EuclideanSpace::midpoint(min, max);
+        let camera = self.scene_renderer.cameras.get(&self.scene_renderer.active_camera)?;
+        let (x, y) = camera.world_to_screen(center, self.scene_renderer.viewport_size)?;
+        Some((x / self.dpi_scale, y / self.dpi_scale))
+    }
+    /// fonts and svg rasters the app added since the last frame
+    fn stage_ui_resources(&mut self, ui_renderer: &mut UIRenderer) {
+        for data in self.staged_fallback_fonts.drain(..) {
+            ui_renderer.add_fallback_font(data);
+        }
+        for (font_id, face) in self.staged_fonts.drain(..) {
+            ui_renderer.set_font(font_id, face);
+        }
+        #[cfg(feature = "svg")]
+        for (name, svg) in self.svgs.iter_mut() {
+            if let Some(raster) = svg.raster_for(self.dpi_scale) {
+                ui_renderer.stage_atlas(name.clone(), raster);
+            }
+        }
+    }
+    fn named_key_pressed(&self, key: NamedKey) -> bool {
+        self.keys_pressed.contains(&Key::Named(key))
+    }
+    fn font_id_for(&mut self, name: &str) -> u16 {
+        // id 0 is the default font
+        let next = self.font_ids.len() as u16 + 1;
+        *self.font_ids.entry(name.to_string()).or_insert(next)
+    }
+    /// hides this frame's clicks and keys from layers under a dialog, `release_input` gives them back for the top layer
+    fn hold_input(&mut self) -> HeldInput {
+        HeldInput {
+            keys: std::mem::take(&mut self.keys_pressed),
+            left: [
+                std::mem::take(&mut self.left_mouse_pressed),
+                std::mem::take(&mut self.left_mouse_down),
+                std::mem::take(&mut self.left_mouse_released),
+                std::mem::take(&mut self.left_mouse_clicked),
+                std::mem::take(&mut self.left_mouse_double_clicked),
+            ],
+            right: [
+                std::mem::take(&mut self.right_mouse_pressed),
+                std::mem::take(&mut self.right_mouse_down),
+                std::mem::take(&mut self.right_mouse_released),
+                std::mem::take(&mut self.right_mouse_clicked),
+            ],
+        }
+    }
+    fn release_input(&mut self, held: HeldInput) {
+        self.keys_pressed = held.keys;
+        [
+            self.left_mouse_pressed,
+            self.left_mouse_down,
+            self.left_mouse_released,
+            self.left_mouse_clicked,
+            self.left_mouse_double_clicked,
+        ] = held.left;
+        [
+            self.right_mouse_pressed,
+            self.right_mouse_down,
+            self.right_mouse_released,
+            self.right_mouse_clicked,
+        ] = held.right;
+    }
+    /// thumb drags and track clicks on last frame's scrollbars, in layout units.
+    /// returns a point over the scrolled container and how far its content should move
+    fn scrollbar_input(&mut self, scrollbars: &[Scrollbar], pointer: (f32, f32)) -> Option<((f32, f32), f32, bool)> {
+        let inside = |(x, y, width, height): (f32, f32, f32, f32)| {
+            pointer.0 >= x && pointer.0 <= x + width && pointer.1 >= y && pointer.1 <= y + height
+        };
+
+        if let Some((index, last)) = self.scrollbar_drag {
+            let Some(bar) = scrollbars.get(index).filter(|_| self.left_mouse_down) else {
+                self.scrollbar_drag = None;
+                return None
+            };
+            self.scrollbar_drag = Some((index, pointer));
+            let moved = match bar.vertical {
+                true => pointer.1 - last.1,
+                false => pointer.0 - last.0,
+            };
+            let (x, y, width, height) = bar.track;
+            return Some(((x + width / 2.0, y + height / 2.0), moved * bar.content_per_unit, bar.vertical))
+        }
+
+        if !self.left_mouse_pressed {
+            return None
+        }
+        let (index, bar) = scrollbars.iter().enumerate().rev().find(|(_, bar)| inside(bar.track))?;
+        let (x, y, width, height) = bar.track;
+        let center = (x + width / 2.0, y + height / 2.0);
+
+        if inside(bar.thumb) {
+            self.scrollbar_drag = Some((index, pointer));
+            return Some((center, 0.0, bar.vertical))
+        }
+        // a click on the track pages toward the pointer
+        let before_thumb = match bar.vertical {
+            true => pointer.1 < bar.thumb.1,
+            false => pointer.0 < bar.thumb.0,
+        };
+        match before_thumb {
+            true => Some((center, -bar.page, bar.vertical)),
+            false => Some((center, bar.page, bar.vertical)),
+        }
+    }
+    fn add_plot(&mut self, name: &str, plot: Plot) {
+        if let Some(old_plot) = self.scene_renderer.plots.plots.insert(name.to_string(), plot) {
+            old_plot.destroy();
+        }
+        self.request_redraw_all();
+    }
     fn remove_viewport(&mut self, window_id: WindowId) {
         let viewport_title = if let Some(viewport) = self.viewports.get(&window_id) {
             viewport.window.title().clone()
@@ -186,6 +649,14 @@ impl API{
 
         self.viewport_lookup.remove_by_left(viewport_title.as_str());
         self.viewports.remove(&window_id);
+        self.drawn_versions.remove(&window_id);
+        self.text_layouts.remove(&window_id);
+        self.laid_out.remove(&viewport_title);
+        #[cfg(feature = "egui")]
+        self.egui_layers.remove(&window_id);
+        if let Some(snapshots) = &mut self.layout_snapshots {
+            snapshots.remove(&window_id);
+        }
     }
     fn resize_viewport(&mut self, window_id: WindowId, size: PhysicalSize<u32>) {
         if let Some(viewport) = self.viewports.get_mut(&window_id) {
@@ -199,10 +670,19 @@ impl API{
             
             if self.viewport_lookup.get_by_left(&name).is_some() { continue; }
             
-            let viewport = attr.build_viewport(event_loop, page, &self.ctx, MULTI_SAMPLE_COUNT);
+            // the shared pipelines are built for one format, so every window has to use the same one
+            let preferred_format = match self.viewports.values().next() {
+                Some(existing) => Some(existing.config.format),
+                None => self.hdr_output.then_some(wgpu::TextureFormat::Rgba16Float),
+            };
+            let viewport = attr.build_viewport(event_loop, page, &self.ctx, MULTI_SAMPLE_COUNT, preferred_format);
             
             viewport.window.set_title(&name);
             let window_id = viewport.window.id();
+            if self.viewports.is_empty()
+            && let Some(theme) = viewport.window.theme() {
+                set_system_dark_mode(theme == Theme::Dark);
+            }
             
             let ui_renderer = self.ui_renderer.as_mut().unwrap();
             match ui_renderer.render_pipeline {
@@ -221,28 +701,124 @@ impl API{
         }
         self.staged_windows.clear();
     }
+    /// runs `event` through the filters, then its handler unless a filter consumed it
+    fn dispatch_event<UserEvents, UserApp>(&mut self, user_application: &mut UserApp, mut event: UserEvents, mut context: Option<EventContext>)
+    where 
+        UserEvents: EventHandler<UserApplication = UserApp>+'static,
+    {
+        // filters added while these run go after them
+        let mut filters = std::mem::take(&mut self.event_filters);
+        let mut consumed = false;
+        for filter in filters.iter_mut() {
+            let Some(filter) = filter.downcast_mut::<EventFilter<UserEvents>>() else {
+                continue
+            };
+            match filter(&event, context.as_ref()) {
+                Filter::Continue => {}
+                Filter::Consume => {
+                    consumed = true;
+                    break
+                }
+                Filter::Replace(replacement, replacement_context) => {
+                    event = replacement;
+                    context = replacement_context;
+                }
+            }
+        }
+        filters.append(&mut self.event_filters);
+        self.event_filters = filters;
+
+        if !consumed {
+            event.dispatch(user_application, context, self);
+        }
+    }
+
+    /// undoes or redoes one command, commands pushed while it runs are dropped
+    fn step_undo<UserApp: 'static>(&mut self, app: &mut UserApp, redo: bool) -> bool {
+        let mut stack = std::mem::take(&mut self.undo_stack);
+        let command = match redo {
+            true => stack.redo(),
+            false => stack.undo(),
+        };
+        let done = match command.map(|command| command.downcast_mut::<Box<dyn Command<UserApp>>>()) {
+            Some(Some(command)) => {
+                match redo {
+                    true => command.redo(app, self),
+                    false => command.undo(app, self),
+                }
+                true
+            }
+            Some(None) => {
+                tracing::error!(target: "telera::app", "undo command isn't for the app's type");
+                false
+            }
+            None => false,
+        };
+        self.undo_stack = stack;
+        self.request_redraw_all();
+        done
+    }
+
     fn redraw_viewport<UserEvents, UserApp>(&mut self, window_id: WindowId, layout_binder: &mut Binder<UserEvents,UserApp>, user_application: &mut UserApp)
     where 
-        UserApp: ParserDataAccess<UserEvents>,
-        UserEvents: FromStr+Debug+Default+Clone+PartialEq+EventHandler<UserApplication = UserApp>,
+        UserApp: App+ParserDataAccess<UserEvents>+'static,
+        UserEvents: FromStr+Debug+Default+Clone+PartialEq+EventHandler<UserApplication = UserApp>+'static,
         <UserEvents as FromStr>::Err: Debug+Default
     {
+        let frame_started = Instant::now();
+        let _frame = tracing::debug_span!(target: "telera::render", "frame", window = ?window_id).entered();
 
         let ui_renderer = if let Some(viewport) = self.viewports.get_mut(&window_id) {
             let size: (f32,f32) = viewport.window.inner_size().into();
-            self.dpi_scale = viewport.window.scale_factor() as f32;
+            self.dpi_scale = viewport.window.scale_factor() as f32 * self.window_ui_scale(window_id);
 
             let mut ui_renderer = self.ui_renderer.take().unwrap();
+            // the glyphs for every monitor's scale are made up front, not when a window first lands on it
+            if ui_renderer.dpi_scale != self.dpi_scale || !ui_renderer.glyphs_warmed() {
+                ui_renderer.warm_glyphs(viewport.window.available_monitors().map(|monitor| monitor.scale_factor() as f32));
+                ui_renderer.warm_glyphs([self.dpi_scale]);
+            }
             ui_renderer.dpi_scale = self.dpi_scale;
+            self.stage_ui_resources(&mut ui_renderer);
+
+            self.scene_renderer.active_camera = self.viewport_lookup.get_by_right(&window_id)
+                .and_then(|viewport_name| self.viewport_cameras.get(viewport_name))
+                .cloned()
+                .unwrap_or(DEFAULT_CAMERA.to_string());
+
+            if size.1 > 0.0 {
+                self.scene_renderer.camera().aspect = size.0 / size.1;
+            }
+            self.scene_renderer.viewport_size = size;
             ui_renderer.resize((size.0 as i32, size.1 as i32), &self.ctx.queue);
             
             self.ui_layout.set_layout_dimensions(size.0/self.dpi_scale, size.1/self.dpi_scale);
+            set_layout_size(size.0/self.dpi_scale, size.1/self.dpi_scale);
 
-            self.ui_layout.pointer_state(
-                self.mouse_poistion.0/self.dpi_scale, 
-                self.mouse_poistion.1/self.dpi_scale, 
-                self.left_mouse_down
-            );
+            let pointer = (self.mouse_poistion.0/self.dpi_scale, self.mouse_poistion.1/self.dpi_scale);
+            let scrollbars = self.scrollbars.remove(&window_id).unwrap_or_default();
+            self.editor_lines = self.text_layouts.remove(&window_id).unwrap_or_default();
+            let scrollbar_input = self.scrollbar_input(&scrollbars, pointer);
+            ui_renderer.pointer = pointer;
+            ui_renderer.scrollbar_dragging = self.scrollbar_drag.is_some();
+
+            match scrollbar_input {
+                // the layout engine scrolls whatever is under the pointer, so point at the bar's container
+                Some((aim, content_delta, vertical)) => {
+                    self.ui_layout.pointer_state(aim.0, aim.1, false);
+                    // a click on a bar isn't a click on whatever is under it
+                    self.left_mouse_pressed = false;
+                    self.scroll_delta_distance = match vertical {
+                        true => (0.0, -content_delta / SCROLL_DELTA_SCALE),
+                        false => (-content_delta / SCROLL_DELTA_SCALE, 0.0),
+                    };
+                }
+                None => self.ui_layout.pointer_state(
+                    pointer.0, 
+                    pointer.1, 
+                    self.left_mouse_down
+                ),
+            }
             self.ui_layout.update_scroll_containers(
                 false, 
                 self.scroll_delta_distance.0, 
@@ -260,55 +836,222 @@ impl API{
 
         if let Some(ui_renderer) = ui_renderer {
 
+            for (name, page) in user_application.take_pages() {
+                layout_binder.insert_page(&name, page);
+            }
+
+            // text entry takes the keys while it has focus, the text editor keeps its own undo
+            let typing = self.text_editor.is_some()
+                || self.numeric_edit.is_some()
+                || self.treeviews.values().any(TreeViewState::is_renaming);
+            if !typing && (self.modifiers.control_key() || self.modifiers.super_key()) {
+                for key in self.keys_pressed.clone() {
+                    if let Key::Character(typed) = key {
+                        match typed.to_lowercase().as_str() {
+                            "z" if self.modifiers.shift_key() => { self.redo(user_application); }
+                            "z" => { self.undo(user_application); }
+                            "y" => { self.redo(user_application); }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            // events emitted while these are handled wait for the next frame
+            for (event, event_context) in std::mem::take(&mut self.emitted) {
+                match event.downcast::<UserEvents>() {
+                    Ok(event) => self.dispatch_event(user_application, *event, event_context),
+                    Err(_) => tracing::error!(target: "telera::app", "emitted event isn't of the app's event type"),
+                }
+            }
+
+            if self.named_key_pressed(NamedKey::F12) {
+                self.show_frame_stats = !self.show_frame_stats;
+            }
+
+            set_undo_state(self.undo_stack.can_undo(), self.undo_stack.can_redo());
+            let drawn_version = user_application.data_version();
+            let layout_started = Instant::now();
+            let layout_span = tracing::debug_span!(target: "telera::layout", "layout").entered();
+            let allocations_before = allocations::allocations();
             self.ui_layout.begin_layout(ui_renderer);
+            self.hovered_element = None;
             
             if let Ok(events) = layout_binder.set_page(
                 window_id,
                 self, 
                 user_application
             ) {
-                for (event, event_context) in events.iter() {
-                    event.dispatch(user_application, event_context.clone(), self);
+                for (event, event_context) in events {
+                    self.dispatch_event(user_application, event, event_context);
                 }
             }
             
             let (render_commands, mut ui_renderer) = self.ui_layout.end_layout();
+            let layout_time = layout_started.elapsed();
+            drop(layout_span);
+            if let Some(viewport_name) = self.viewport_lookup.get_by_right(&window_id).cloned() {
+                let (width, height) = self.scene_renderer.viewport_size;
+                self.record_layout(&viewport_name, (width / self.dpi_scale, height / self.dpi_scale));
+            }
+            let mut snapshot = self.layout_snapshots.is_some().then(|| LayoutSnapshot::from_commands(&render_commands));
+            let layout_allocations = allocations::allocations()
+                .zip(allocations_before)
+                .map(|(after, before)| after - before);
+            let mut ui_render_time = Duration::ZERO;
+            let mut scene_render_time = Duration::ZERO;
+
+            if let Some(camera) = self.scene_renderer.cameras.get(&self.scene_renderer.active_camera) {
+                self.scene_renderer.labels.queue(camera, self.scene_renderer.viewport_size, self.dpi_scale, &mut ui_renderer);
+            }
+
+            let recording = match &self.recorder {
+                Some(recorder) => self.viewport_lookup.get_by_left(&recorder.viewport) == Some(&window_id),
+                None => false
+            };
 
             if let Some(viewport) = self.viewports.get_mut(&window_id) {
+                if recording && let Some(recorder) = &mut self.recorder {
+                    match recorder.capture() {
+                        Ok(request) => viewport.capture_requests.push(request),
+                        Err(()) => {
+                            tracing::error!(target: "telera::render", "recording to {} stopped, its encoder failed", recorder.path.display());
+                            if let Some(recorder) = self.recorder.take() {
+                                recorder.finish();
+                            }
+                        }
+                    }
+                }
+                if !self.compute.passes.is_empty() {
+                    ComputeRunner::dispatch(&self.ctx.device, &self.ctx.queue, self.compute.passes.iter().map(|(_, job)| job));
+                }
+                self.scene_renderer.upload_instances(&mut self.models, &self.ctx.device, &self.ctx.queue);
+
+                #[cfg(feature = "egui")]
+                let egui_layer = match self.show_debug_ui {
+                    true => {
+                        let layer = self.egui_layers.entry(window_id).or_insert_with(|| {
+                            EguiLayer::new(&viewport.window, &self.ctx.device, viewport.config.format, MULTI_SAMPLE_COUNT)
+                        });
+                        let size = [viewport.config.width, viewport.config.height];
+                        let repaint = layer.prepare(&viewport.window, &self.ctx.device, &self.ctx.queue, size, |context| {
+                            egui::Window::new("Debug").show(context, |ui| user_application.debug_ui(ui));
+                        });
+                        if repaint {
+                            viewport.window.request_redraw();
+                        }
+                        Some(&*layer)
+                    }
+                    false => None,
+                };
+
+                let mut render_commands = Some(render_commands);
+                let viewport_name = self.viewport_lookup.get_by_right(&window_id).map(String::as_str).unwrap_or_default();
+                let _render = tracing::debug_span!(target: "telera::render", "render").entered();
                 self.ctx.render(
                     viewport,
                     MULTI_SAMPLE_COUNT,
-                    |render_pass, device, queue, config| {
-                        
-                        self.scene_renderer.render(&mut self.models, render_pass, &queue);
-                        
-                        ui_renderer.render_layout(render_commands, render_pass, &device, &queue, &config);
-                    
+                    &mut self.post_processor,
+                    |stage, render_pass, device, queue, config| {
+                        match stage {
+                            RenderStage::Scene => {
+                                let scene_started = Instant::now();
+                                let mut underlay_time = Duration::ZERO;
+                                self.scene_renderer.update_camera(&queue);
+                                self.scene_renderer.render_environment(render_pass, &queue);
+                                if ui_renderer.layer == UILayer::Underlay && let Some(render_commands) = render_commands.take() {
+                                    let ui_started = Instant::now();
+                                    ui_renderer.render_layout(render_commands, render_pass, &device, &queue, &config);
+                                    underlay_time = ui_started.elapsed();
+                                    ui_render_time += underlay_time;
+                                }
+                                self.scene_renderer.render(&mut self.models, render_pass, &queue);
+                                scene_render_time += scene_started.elapsed().saturating_sub(underlay_time);
+                                run_render_hooks(&mut self.render_hooks, RenderHookStage::AfterScene, viewport_name, render_pass, device, queue, config);
+                            }
+                            RenderStage::Interface => {
+                                run_render_hooks(&mut self.render_hooks, RenderHookStage::BeforeUI, viewport_name, render_pass, device, queue, config);
+                                if let Some(render_commands) = render_commands.take() {
+                                    let ui_started = Instant::now();
+                                    ui_renderer.render_layout(render_commands, render_pass, &device, &queue, &config);
+                                    ui_render_time += ui_started.elapsed();
+                                }
+                                #[cfg(feature = "egui")]
+                                if let Some(layer) = egui_layer {
+                                    layer.render(render_pass);
+                                }
+                            }
+                        }
                     }
                 ).unwrap();
+
+                // keep drawing until a projection switch has finished easing or the camera stops coasting
+                if self.scene_renderer.camera().is_transitioning() || self.scene_renderer.camera_controller.is_moving() {
+                    viewport.window.request_redraw();
+                }
+
+                // keep drawing so every tick of the recording gets a frame, and so captures still being read back get polled
+                if (recording && self.recorder.is_some()) || !viewport.pending_captures.is_empty() {
+                    viewport.window.request_redraw();
+                }
             }
 
+            let ui_draw_calls = ui_renderer.draw_calls;
+            if let Some(mut snapshot) = snapshot.take() {
+                snapshot.elements = self.element_ids.keys()
+                    .filter_map(|id| Some((id.clone(), self.layout_bounds(id)?)))
+                    .collect();
+                snapshot.elements.sort_by(|a, b| a.0.cmp(&b.0));
+                if let Some(snapshots) = &mut self.layout_snapshots {
+                    snapshots.insert(window_id, snapshot);
+                }
+            }
+            self.scrollbars.insert(window_id, std::mem::take(&mut ui_renderer.scrollbars));
+            self.text_layouts.insert(window_id, std::mem::take(&mut ui_renderer.text_layouts));
             self.ui_renderer = Some(ui_renderer);
 
-            self.left_mouse_pressed = false;
-            self.left_mouse_released = false;
-            self.left_mouse_clicked = false;
-            self.left_mouse_double_clicked = false;
-            if let Some(timer) = self.left_mouse_clicked_timer
-            && timer.elapsed().as_millis() > 400 {
-                self.left_mouse_clicked_timer = None;
+            match drawn_version {
+                Some(version) => self.drawn_versions.insert(window_id, version),
+                None => self.drawn_versions.remove(&window_id),
+            };
+            // events from this layout changed what it showed
+            if user_application.data_version() != drawn_version {
+                self.request_redraw_viewport(window_id);
             }
-            // if let Some(timer) = self.core.left_mouse_dbl_clicked_timer
-            // && timer.elapsed().as_millis() > 300 {
-            //     self.core.left_mouse_dbl_clicked_timer = None;
-            // }
-            self.right_mouse_pressed = false;
-            self.right_mouse_released = false;
-            self.right_mouse_clicked = false;
-            if let Some(timer) = self.right_mouse_clicked_timer
-            && timer.elapsed().as_millis() > 300 {
-                self.right_mouse_clicked_timer = None;
+
+            self.frame_stats = FrameStats {
+                frame: frame_started.elapsed(),
+                layout: layout_time,
+                ui_render: ui_render_time,
+                scene_render: scene_render_time,
+                layout_allocations,
+                ui_draw_calls,
+            };
+            // the overlay shows the frame before, keep drawing so it stays current
+            if self.show_frame_stats {
+                self.request_redraw_viewport(window_id);
             }
+
+            self.clear_frame_input();
+        }
+    }
+    /// presses, releases, clicks and keys only last the frame they happened in
+    fn clear_frame_input(&mut self) {
+        self.left_mouse_pressed = false;
+        self.left_mouse_released = false;
+        self.left_mouse_clicked = false;
+        self.left_mouse_double_clicked = false;
+        if let Some(timer) = self.left_mouse_clicked_timer
+        && timer.elapsed().as_millis() > 400 {
+            self.left_mouse_clicked_timer = None;
+        }
+        self.right_mouse_pressed = false;
+        self.right_mouse_released = false;
+        self.right_mouse_clicked = false;
+        self.keys_pressed.clear();
+        if let Some(timer) = self.right_mouse_clicked_timer
+        && timer.elapsed().as_millis() > 300 {
+            self.right_mouse_clicked_timer = None;
         }
     }
 }
@@ -323,11 +1066,237 @@ impl API{
         let new_window = Window::default_attributes().with_inner_size(LogicalSize::new(800, 600));
         self.staged_windows.push(("Main".to_string(), "Main".to_string(), new_window));
     }
+    /// Chooses whether the ui is drawn over or under the 3d scene.
+    /// 
+    /// Elements marked `depth-test` are hidden by scene geometry in front of them regardless of the layer.
+    pub fn set_ui_layer(&mut self, layer: UILayer) {
+        if let Some(ui_renderer) = &mut self.ui_renderer {
+            ui_renderer.layer = layer;
+        }
+    }
+    /// Rounds ui edges and border widths to device pixels, elements can opt out with `pixel-snap false`.
+    pub fn set_pixel_snapping(&mut self, enabled: bool) {
+        if let Some(ui_renderer) = &mut self.ui_renderer {
+            ui_renderer.pixel_snapping = enabled;
+        }
+    }
+    /// Labels of the nodes selected in the treeview drawn from `name`.
+    pub fn treeview_selection(&self, name: &str) -> &[String] {
+        match self.treeviews.get(&(symbol_table::GlobalSymbol::new(name), None)) {
+            Some(state) => state.selected(),
+            None => &[],
+        }
+    }
+    /// Styles the overlay scrollbars drawn over every scroll container whose content doesn't fit.
+    pub fn set_scrollbar_style(&mut self, style: ScrollbarStyle) {
+        if let Some(ui_renderer) = &mut self.ui_renderer {
+            ui_renderer.scrollbar_style = style;
+        }
+    }
+    /// Draws every `custom` layout element with `type` set to `name` by calling `renderer` inside the ui pass.
+    /// Registering the same name again replaces the previous renderer.
+    pub fn register_custom_element(&mut self, name: &str, renderer: Box<dyn CustomElementRenderer>) {
+        if let Some(ui_renderer) = &mut self.ui_renderer {
+            ui_renderer.custom_renderers.insert(name.to_string(), renderer);
+        }
+        self.request_redraw_all();
+    }
+    pub fn remove_custom_element(&mut self, name: &str) {
+        if let Some(ui_renderer) = &mut self.ui_renderer {
+            ui_renderer.custom_renderers.remove(name);
+        }
+        self.request_redraw_all();
+    }
+    /// Registers a font file under `name` for the `font` text config, returning its font id.
+    /// Adding a name again replaces its font and keeps the id.
+    pub fn add_font(&mut self, name: &str, data: Vec<u8>) -> u16 {
+        let font_id = self.font_id_for(name);
+        self.staged_fonts.push((font_id, FontFace::Data(data)));
+        self.request_redraw_all();
+        font_id
+    }
+    /// Same as `add_font`, reading the font from a file.
+    pub fn add_font_file(&mut self, name: &str, path: &Path) -> Result<u16, ()> {
+        let data = std::fs::read(path)
+            .map_err(|e| tracing::error!(target: "telera::render", "failed to read font {}: {}", path.display(), e))?;
+        Ok(self.add_font(name, data))
+    }
+    /// Registers installed font families under `name`, the first one installed is used.
+    /// Glyphs missing from a font are looked up in every other loaded font.
+    pub fn add_font_family(&mut self, name: &str, families: &[&str]) -> u16 {
+        let font_id = self.font_id_for(name);
+        self.staged_fonts.push((font_id, FontFace::Installed(families.iter().map(|family| family.to_string()).collect())));
+        self.request_redraw_all();
+        font_id
+    }
+    /// Loads a font that text falls back to for glyphs its own font lacks.
+    /// A color emoji font (CBDT, sbix or COLR) added this way draws emoji in color instead of boxes.
+    pub fn add_fallback_font(&mut self, data: Vec<u8>) {
+        self.staged_fallback_fonts.push(data);
+        self.request_redraw_all();
+    }
+    pub fn font_id(&self, name: &str) -> Option<u16> {
+        self.font_ids.get(name).copied()
+    }
+    /// The id of the innermost element under the pointer that has one, as of the last layout of the viewport being drawn.
+    pub fn hovered_element_id(&self) -> Option<String> {
+        self.hovered_element.clone()
+    }
+    /// Queues an event to be dispatched like one sent from a layout, at the start of the next frame.
+    /// `event` must be of the app's event type.
+    pub fn emit<Event: 'static>(&mut self, event: Event, context: Option<EventContext>) {
+        self.emitted.push((Box::new(event), context));
+        self.request_redraw_all();
+    }
+    /// Runs `filter` on every event before its handler, from layouts and `emit` alike.
+    /// Filters run in the order they were added, each one sees what the ones before it let through.
+    /// `Event` must be the app's event type, filters of any other type never run.
+    pub fn add_event_filter<Event: 'static>(&mut self, filter: impl FnMut(&Event, Option<&EventContext>) -> Filter<Event> + 'static) {
+        let filter: EventFilter<Event> = Box::new(filter);
+        self.event_filters.push(Box::new(filter));
+    }
+    /// Records a change the app just made so `undo` can take it back, anything undone before can't be redone after.
+    /// Layouts read `system.can_undo` and `system.can_redo`, to enable menu items for instance.
+    pub fn push_undo<UserApp: 'static>(&mut self, command: impl Command<UserApp>) {
+        let command: Box<dyn Command<UserApp>> = Box::new(command);
+        self.undo_stack.push(Box::new(command));
+        self.request_redraw_all();
+    }
+    /// Undoes the newest command that isn't undone yet, false when there's none.
+    /// Ctrl+Z does the same while no text box has focus.
+    pub fn undo<UserApp: 'static>(&mut self, app: &mut UserApp) -> bool {
+        self.step_undo(app, false)
+    }
+    /// Redoes the last command undone, false when there's none. Ctrl+Y and Ctrl+Shift+Z do the same.
+    pub fn redo<UserApp: 'static>(&mut self, app: &mut UserApp) -> bool {
+        self.step_undo(app, true)
+    }
+    pub fn can_undo(&self) -> bool {
+        self.undo_stack.can_undo()
+    }
+    pub fn can_redo(&self) -> bool {
+        self.undo_stack.can_redo()
+    }
+    /// Forgets every command, after loading a new document for instance.
+    pub fn clear_undo(&mut self) {
+        self.undo_stack.clear();
+        self.request_redraw_all();
+    }
+    /// How many commands are kept, the oldest are dropped past it. 0, the default, keeps them all.
+    pub fn set_undo_limit(&mut self, limit: usize) {
+        self.undo_stack.set_limit(limit);
+    }
+    /// A handle other threads can use to redraw the app or emit events to it.
+    pub fn proxy(&self) -> ApiProxy {
+        ApiProxy { events: self.app_events.clone() }
+    }
+    /// Runs `work` on a new thread. Once it returns, the result waits in the returned `Task`
+    /// and `done` is dispatched on the ui thread, where its handler can `take` the result.
+    pub fn spawn<Event: Send + 'static, T: Send + 'static>(&self, done: Event, work: impl FnOnce() -> T + Send + 'static) -> Task<T> {
+        Task::spawn(self.proxy(), done, work)
+    }
+    /// Same as `spawn` for a future, run on the tokio runtime started by `run_async`.
+    #[cfg(feature = "async")]
+    pub fn spawn_async<Event: Send + 'static, T: Send + 'static>(&self, done: Event, future: impl Future<Output = T> + Send + 'static) -> Task<T> {
+        Task::spawn_async(self.proxy(), done, future)
+    }
+    /// Shows a notification from the os, seen even when none of the app's windows have focus.
+    /// `icon` is an icon name or an image path, how it's shown is up to the platform. Failures are only logged.
+    #[cfg(feature = "notifications")]
+    pub fn native_notification(&self, title: &str, body: &str, icon: Option<&str>) {
+        let mut notification = notify_rust::Notification::new();
+        notification.summary(title).body(body);
+        if let Some(icon) = icon {
+            notification.icon(icon);
+        }
+        // showing waits on the notification service, which shouldn't hold up a frame
+        std::thread::spawn(move || {
+            if let Err(error) = notification.show() {
+                tracing::warn!(target: "telera::app", "couldn't show a notification: {}", error);
+            }
+        });
+    }
+    /// Where the element with the `id` config was placed by the last layout of `viewport`, scroll offsets included.
+    /// Keyed list items are found by their `list#key` id.
+    pub fn element_bounds(&self, viewport: &str, id: &str) -> Option<Rect> {
+        self.laid_out.get(viewport)?.1.get(id).copied()
+    }
+    /// Whether any of the element's bounds lie inside `viewport` as of its last layout.
+    /// Clipping by scroll containers isn't taken into account.
+    pub fn is_element_visible(&self, viewport: &str, id: &str) -> bool {
+        self.laid_out.get(viewport)
+            .is_some_and(|(bounds, elements)| elements.get(id).is_some_and(|element| element.intersects(bounds)))
+    }
+    /// where the layout that just ended put the element
+    fn layout_bounds(&self, id: &str) -> Option<Rect> {
+        let element_id = self.ui_layout.get_element_id(id);
+        let bounds = self.ui_layout.get_bounding_box(element_id)?;
+        Some(Rect { x: bounds.x, y: bounds.y, width: bounds.width, height: bounds.height })
+    }
+    /// keeps where the layout that just ended put the elements with an `id` config as `viewport`'s, `size` in layout units
+    fn record_layout(&mut self, viewport: &str, (width, height): (f32, f32)) {
+        let elements = self.element_ids.keys()
+            .filter_map(|id| Some((id.clone(), self.layout_bounds(id)?)))
+            .collect();
+        self.laid_out.insert(viewport.to_string(), (Rect { x: 0.0, y: 0.0, width, height }, elements));
+    }
+    /// Registers an svg as a ui image, drawn from the atlas `name` like images from `add_image`.
+    /// It's rasterized at each viewport's dpi scale, so it stays sharp across monitors.
+    #[cfg(feature = "svg")]
+    pub fn add_svg(&mut self, name: &str, source: &str) -> Result<(), ()> {
+        let svg = ui_toolkit::svg::SvgImage::parse(name, source)?;
+        self.svgs.insert(name.to_string(), svg);
+        self.request_redraw_all();
+        Ok(())
+    }
     pub fn add_image(&mut self, name: &str, image: DynamicImage) {
         if let Some(ui_renderer) = &mut self.ui_renderer {
             ui_renderer.stage_atlas(name.to_string(), image);
         }
     }
+    /// Shows a frame of raw rgba pixels, `width` by `height`, as the ui image `name`, for video or camera feeds.
+    /// Meant to be called every frame: the image's textures are kept and written in turns, they're only made again when the size changes.
+    pub fn update_image_from_rgba(&mut self, name: &str, rgba: &[u8], width: u32, height: u32) -> Result<(), ()> {
+        if width == 0 || height == 0 || rgba.len() != width as usize * height as usize * 4 {
+            tracing::error!(target: "telera::render", "a frame of {} has {} bytes, {} x {} rgba needs {}", name, rgba.len(), width, height, width as usize * height as usize * 4);
+            return Err(())
+        }
+        let ui_renderer = self.ui_renderer.as_mut().ok_or(())?;
+        ui_renderer.stream_image(&self.ctx.device, &self.ctx.queue, name, rgba, (width, height));
+        self.request_redraw_all();
+        Ok(())
+    }
+    /// Uploads an image as a texture that models can share with `set_model_texture`.
+    /// It's also registered as a ui image under the same name, sampled with `options`.
+    pub fn load_texture(&mut self, name: &str, image: DynamicImage, options: TextureOptions) -> Result<(), ()> {
+        let texture = texture::Texture::from_image_with_options(&self.ctx.device, &self.ctx.queue, &image, Some(name), &options)
+            .map_err(|e| tracing::error!(target: "telera::render", "failed to load texture {}: {}", name, e))?;
+
+        if let Some(ui_renderer) = &mut self.ui_renderer {
+            ui_renderer.atlas_map.insert(name.to_string(), texture.bind_group(&self.ctx.device));
+        }
+        self.textures.insert(name.to_string(), texture);
+        self.request_redraw_all();
+        Ok(())
+    }
+    /// Models already using the texture keep it until they're given another one.
+    pub fn remove_texture(&mut self, name: &str) -> Result<(), ()> {
+        self.textures.remove(name).ok_or(())?;
+        if let Some(ui_renderer) = &mut self.ui_renderer {
+            ui_renderer.atlas_map.remove(name);
+        }
+        Ok(())
+    }
+    /// Draws every material of the model with a texture from `load_texture`.
+    pub fn set_model_texture(&mut self, model_name: &str, texture_name: &str) -> Result<(), ()> {
+        let texture = self.textures.get(texture_name).ok_or(())?;
+        let model = self.models.get_mut(model_name).ok_or(())?;
+        for material in model.materials.iter_mut() {
+            material.set_texture(&self.ctx.device, texture);
+        }
+        self.request_redraw_all();
+        Ok(())
+    }
     pub fn set_viewport_title(&mut self, viewport: &str, title: &str) {
         if  let Some(window_id) = self.viewport_lookup.get_by_left(viewport) && 
             let Some (viewport) = self.viewports.get_mut(window_id) {
@@ -336,7 +1305,7 @@ impl API{
     }
     pub fn set_current_viewport_page(&mut self, page: &str) {
         // TODO !
-        println!("{:?}", page);
+        tracing::warn!(target: "telera::app", "set_current_viewport_page isn't implemented, {} wasn't opened", page);
     }
     pub fn set_viewport_page(&mut self, viewport: &str, page: &str){
         if  let Some(window_id) = self.viewport_lookup.get_by_left(viewport) &&
@@ -345,40 +1314,517 @@ impl API{
             window.window.request_redraw();
         }
     }
+    /// Draws `page` as a modal layer over the viewport's current page.
+    /// 
+    /// The page underneath stays visible but gets no pointer or keyboard input until the dialog is closed.
+    /// Events from the dialog are dispatched like any other page's. Dialogs stack, the newest one is on top.
+    pub fn open_dialog(&mut self, viewport: &str, page: &str) {
+        if  let Some(window_id) = self.viewport_lookup.get_by_left(viewport) &&
+            let Some(window) = self.viewports.get_mut(window_id) {
+            window.dialogs.push(page.to_string());
+            window.window.request_redraw();
+        }
+    }
+    /// Closes the topmost dialog in the viewport, returning its page name.
+    pub fn close_dialog(&mut self, viewport: &str) -> Option<String> {
+        let window_id = self.viewport_lookup.get_by_left(viewport)?;
+        let window = self.viewports.get_mut(window_id)?;
+        window.window.request_redraw();
+        window.dialogs.pop()
+    }
+    pub fn close_all_dialogs(&mut self, viewport: &str) {
+        if  let Some(window_id) = self.viewport_lookup.get_by_left(viewport) &&
+            let Some(window) = self.viewports.get_mut(window_id) {
+            window.dialogs.clear();
+            window.window.request_redraw();
+        }
+    }
+    pub fn dialog_open(&self, viewport: &str) -> bool {
+        self.viewport_lookup.get_by_left(viewport)
+            .and_then(|window_id| self.viewports.get(window_id))
+            .is_some_and(|window| !window.dialogs.is_empty())
+    }
+    /// Records every frame drawn in the viewport to `path`, encoding on a worker thread.
+    /// 
+    /// A `.gif` path writes an animated gif and a path without an extension is filled with numbered pngs.
+    /// Any other extension is encoded as video, which requires the `video_export` feature and `ffmpeg` on the path.
+    /// 
+    /// Frames are stamped on a fixed timestep of `1/fps` rather than wall-clock time,
+    /// so animations driven by `recording_time` come out smooth no matter how long each frame takes to render.
+    pub fn start_recording(&mut self, viewport: &str, path: PathBuf, fps: u32) -> Result<(), ()> {
+        if self.recorder.is_some() {
+            return Err(())
+        }
+        let Some(window_id) = self.viewport_lookup.get_by_left(viewport) else {
+            return Err(())
+        };
+        self.recorder = Some(Recorder::new(viewport, path, fps)?);
+        self.request_redraw_viewport(*window_id);
+        Ok(())
+    }
+    /// Whether the os prefers a dark color scheme, layouts read it as `system.dark_mode`.
+    pub fn dark_mode(&self) -> bool {
+        system_dark_mode()
+    }
+    /// Keeps what every viewport's layout drew, for comparing against saved snapshots in tests.
+    /// Off by default since it copies every draw of every frame.
+    pub fn record_layout_snapshots(&mut self, record: bool) {
+        match record {
+            true => { self.layout_snapshots.get_or_insert_default(); }
+            false => self.layout_snapshots = None,
+        }
+        self.request_redraw_all();
+    }
+    /// What the viewport's last layout drew, see `assert_layout_snapshot!`.
+    /// `None` until a frame was drawn with `record_layout_snapshots` on.
+    pub fn layout_snapshot(&self, viewport: &str) -> Option<&LayoutSnapshot> {
+        let window_id = self.viewport_lookup.get_by_left(viewport)?;
+        self.layout_snapshots.as_ref()?.get(window_id)
+    }
+    /// How long the last frame drawn took, in any viewport.
+    pub fn frame_stats(&self) -> FrameStats {
+        self.frame_stats
+    }
+    /// Shows the egui panels from `App::debug_ui` over every viewport, they're on to start with.
+    #[cfg(feature = "egui")]
+    pub fn show_debug_ui(&mut self, show: bool) {
+        self.show_debug_ui = show;
+        self.request_redraw_all();
+    }
+    /// Shows the frame stats in the corner of every viewport, F12 toggles it too.
+    pub fn show_frame_stats(&mut self, show: bool) {
+        self.show_frame_stats = show;
+        self.request_redraw_all();
+    }
+    /// Calls `App::fixed_update` `hz` times a second from now on, 0 stops it.
+    /// Every viewport is redrawn after the updates that were due.
+    pub fn set_fixed_rate(&mut self, hz: f32) {
+        self.fixed_step = match hz > 0.0 {
+            true => {
+                let step = Duration::from_secs_f32(1.0 / hz);
+                Some((step, Instant::now() + step))
+            }
+            false => None,
+        };
+    }
+    /// Ends the event loop once the current event is handled, `App::shutdown` runs before it stops.
+    pub fn exit(&mut self) {
+        self.exit_requested = true;
+    }
+    /// Waits for queued frames to be written and closes the file. Does nothing if no recording is running.
+    pub fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            // frames still being read back go in before the file is closed
+            if let Some(viewport) = self.viewport_lookup.get_by_left(&recorder.viewport).and_then(|window_id| self.viewports.get_mut(window_id)) {
+                self.ctx.read_captures(viewport, true);
+            }
+            recorder.finish();
+        }
+    }
+    /// Seconds into the current recording, advancing by exactly one frame per redraw.
+    pub fn recording_time(&self) -> Option<f32> {
+        self.recorder.as_ref().map(|recorder| recorder.timestamp())
+    }
+    /// The device and queue everything is drawn with, for creating buffers and textures shared with compute shaders
+    /// and the pipelines of render hooks.
+    pub fn gpu(&self) -> (&wgpu::Device, &wgpu::Queue) {
+        (&self.ctx.device, &self.ctx.queue)
+    }
+    /// Draws into every viewport's frame at `stage` until removed, hooks at the same stage run in the order they were added.
+    /// The hook gets the viewport's name, so it can skip the ones it doesn't draw in. Its pipelines have to match the pass:
+    /// the surface format from the config, `Depth32Float` depth and a sample count of 1.
+    /// Adding a hook with an existing label replaces it.
+    pub fn add_render_hook(
+        &mut self,
+        label: &str,
+        stage: RenderHookStage,
+        hook: impl FnMut(&str, &mut wgpu::RenderPass<'static>, &wgpu::Device, &wgpu::Queue, &wgpu::SurfaceConfiguration) + 'static,
+    ) {
+        let hook: RenderHook = Box::new(hook);
+        match self.render_hooks.iter_mut().find(|(existing, _, _)| existing == label) {
+            Some(existing) => *existing = (label.to_string(), stage, hook),
+            None => self.render_hooks.push((label.to_string(), stage, hook)),
+        }
+        self.request_redraw_all();
+    }
+    pub fn remove_render_hook(&mut self, label: &str) {
+        self.render_hooks.retain(|(hook, _, _)| hook != label);
+        self.request_redraw_all();
+    }
+    /// Compiles and dispatches a compute shader once, right away. The shader needs a single `@compute` entry point
+    /// and its bindings in group 0, numbered in the order of `resources`. Compiled shaders are kept per label.
+    pub fn run_compute(&mut self, label: &str, wgsl: &str, workgroups: (u32, u32, u32), resources: &[wgpu::BindingResource]) -> Result<(), ()> {
+        let job = self.compute.job(&self.ctx.device, label, wgsl, workgroups, resources)?;
+        ComputeRunner::dispatch(&self.ctx.device, &self.ctx.queue, std::iter::once(&job));
+        Ok(())
+    }
+    /// Like `run_compute`, but dispatched before every viewport redraw until removed, in the order passes were added.
+    /// Adding a pass with an existing label replaces it.
+    pub fn add_compute_pass(&mut self, label: &str, wgsl: &str, workgroups: (u32, u32, u32), resources: &[wgpu::BindingResource]) -> Result<(), ()> {
+        let job = self.compute.job(&self.ctx.device, label, wgsl, workgroups, resources)?;
+        match self.compute.passes.iter_mut().find(|(pass, _)| pass == label) {
+            Some((_, pass)) => *pass = job,
+            None => self.compute.passes.push((label.to_string(), job)),
+        }
+        Ok(())
+    }
+    pub fn remove_compute_pass(&mut self, label: &str) {
+        self.compute.passes.retain(|(pass, _)| pass != label);
+    }
+    /// Reads back the next frame drawn in the viewport, ui included. Once it's read the image waits in the returned `Task`
+    /// and `done` is dispatched, like `spawn`. Fails if the viewport doesn't exist.
+    pub fn capture_viewport<Event: Send + 'static>(&mut self, viewport: &str, done: Event) -> Result<Task<DynamicImage>, ()> {
+        let proxy = self.proxy();
+        let viewport = self.viewport_lookup.get_by_left(viewport).and_then(|window_id| self.viewports.get_mut(window_id)).ok_or(())?;
+        let (task, finish) = Task::new(proxy, done);
+        viewport.capture_requests.push(CaptureRequest::Color(Box::new(move |frame| finish(DynamicImage::ImageRgba8(frame)))));
+        viewport.window.request_redraw();
+        Ok(task)
+    }
+    /// Reads back the depth buffer of the next frame drawn in the viewport, 0 at the near plane and 1 at the far plane.
+    /// Same as `capture_viewport` otherwise, and also fails when the viewport is multisampled.
+    pub fn capture_viewport_depth<Event: Send + 'static>(&mut self, viewport: &str, done: Event) -> Result<Task<image::ImageBuffer<image::Luma<f32>, Vec<f32>>>, ()> {
+        let proxy = self.proxy();
+        let viewport = self.viewport_lookup.get_by_left(viewport).and_then(|window_id| self.viewports.get_mut(window_id)).ok_or(())?;
+        if viewport.depth_texture.texture.sample_count() != 1 {
+            return Err(())
+        }
+        let (task, finish) = Task::new(proxy, done);
+        viewport.capture_requests.push(CaptureRequest::Depth(Box::new(finish)));
+        viewport.window.request_redraw();
+        Ok(task)
+    }
+    /// The camera of the viewport drawn most recently.
+    pub fn scene_camera(&mut self) -> &mut Camera {
+        self.scene_renderer.camera()
+    }
+    pub fn camera_controller(&mut self) -> &mut CameraController {
+        &mut self.scene_renderer.camera_controller
+    }
+    pub fn set_camera_controller_mode(&mut self, mode: ControllerMode) {
+        self.scene_renderer.camera_controller.set_mode(mode);
+    }
+    /// `sensitivity` is radians per pixel dragged, `inertia` is the share of motion kept each frame after input stops.
+    pub fn set_camera_controller_feel(&mut self, sensitivity: f32, inertia: f32) {
+        let controller = &mut self.scene_renderer.camera_controller;
+        controller.sensitivity = sensitivity;
+        controller.inertia = inertia.clamp(0.0, 0.99);
+    }
+    /// Draws `environment` behind the scene, replacing any previous one.
+    pub fn set_environment(&mut self, environment: Environment) -> Result<(), ()> {
+        self.scene_renderer.set_environment(&self.ctx.device, &self.ctx.queue, &environment)?;
+        self.request_redraw_all();
+        Ok(())
+    }
+    pub fn clear_environment(&mut self) {
+        self.scene_renderer.clear_environment();
+        self.request_redraw_all();
+    }
+    pub fn set_environment_exposure(&mut self, exposure: f32) {
+        if let Some(skybox) = &mut self.scene_renderer.skybox {
+            skybox.exposure = exposure;
+        }
+    }
+    /// Draw call, triangle and culling counts from the last scene render.
+    pub fn render_stats(&self) -> RenderStats {
+        self.scene_renderer.stats
+    }
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.scene_renderer.frustum_culling = enabled;
+    }
+    /// Effects run over the scene in list order before the ui is drawn. Push, remove or reorder freely;
+    /// an empty list renders the scene straight to the window.
+    pub fn post_effects_mut(&mut self) -> &mut Vec<PostEffect> {
+        &mut self.post_processor.effects
+    }
+    /// Sets the tonemapping curve and exposure, replacing any tonemap effect already in the post chain.
+    /// The ui is drawn after the chain, so it keeps its srgb colors whatever the curve.
+    pub fn set_tonemapping(&mut self, operator: Tonemapper, exposure: f32) {
+        let effects = &mut self.post_processor.effects;
+        effects.retain(|effect| !matches!(effect, PostEffect::Tonemap { .. }));
+        effects.insert(0, PostEffect::Tonemap { operator, exposure });
+        self.request_redraw_all();
+    }
+    /// Removes tonemapping from the post chain.
+    pub fn clear_tonemapping(&mut self) {
+        self.post_processor.effects.retain(|effect| !matches!(effect, PostEffect::Tonemap { .. }));
+        self.request_redraw_all();
+    }
+    /// Requests an Rgba16Float surface so scene colors above 1.0 reach hdr monitors.
+    /// Only applies if set before the first window opens, falls back to srgb where the surface can't present it.
+    /// Pair with `Tonemapper::Linear` to keep highlights, the default aces curve clamps to 1.
+    pub fn set_hdr_output(&mut self, enabled: bool) {
+        self.hdr_output = enabled;
+    }
+    /// True when the windows ended up with a float surface.
+    pub fn hdr_output(&self) -> bool {
+        self.viewports.values().next().is_some_and(|viewport| viewport.config.format == wgpu::TextureFormat::Rgba16Float)
+    }
+    /// Toggles the ground grid on the y = 0 plane.
+    pub fn show_grid(&mut self, show: bool) {
+        self.scene_renderer.overlay.show_grid = show;
+        self.request_redraw_all();
+    }
+    /// Toggles the xyz orientation gizmo in the bottom left corner of each viewport.
+    pub fn show_axis_gizmo(&mut self, show: bool) {
+        self.scene_renderer.overlay.show_axis_gizmo = show;
+        self.request_redraw_all();
+    }
+    /// Adds a named camera with default settings, or returns the existing one.
+    pub fn create_camera(&mut self, name: &str) -> &mut Camera {
+        self.scene_renderer.cameras.entry(name.to_string()).or_default()
+    }
+    pub fn camera(&mut self, name: &str) -> Option<&mut Camera> {
+        self.scene_renderer.cameras.get_mut(name)
+    }
+    pub fn set_active_camera(&mut self, viewport: &str, camera: &str) -> Result<(), ()> {
+        if !self.scene_renderer.cameras.contains_key(camera) {
+            return Err(())
+        }
+        self.viewport_cameras.insert(viewport.to_string(), camera.to_string());
+        if let Some(window_id) = self.viewport_lookup.get_by_left(viewport).copied() {
+            self.request_redraw_viewport(window_id);
+        }
+        Ok(())
+    }
+    /// Zooms the viewport's whole interface, layout and text, on top of the monitor's dpi scale. Clamped to 0.5 to 3.
+    /// Users change it with Ctrl+= and Ctrl+-, Ctrl+0 resets it. It's kept for the viewport's name, save it in `shutdown` to keep it between runs.
+    pub fn set_ui_scale(&mut self, viewport: &str, factor: f32) {
+        let factor = factor.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        // steps of a tenth so zooming in and out lands back on the same scale
+        let factor = (factor * 10.0).round() / 10.0;
+        self.ui_scales.insert(viewport.to_string(), factor);
+        if let Some(window_id) = self.viewport_lookup.get_by_left(viewport).copied() {
+            self.request_redraw_viewport(window_id);
+        }
+    }
+    /// The viewport's zoom from `set_ui_scale`, 1 when it wasn't zoomed.
+    pub fn ui_scale(&self, viewport: &str) -> f32 {
+        self.ui_scales.get(viewport).copied().unwrap_or(1.0)
+    }
+    fn window_ui_scale(&self, window_id: WindowId) -> f32 {
+        self.viewport_lookup.get_by_right(&window_id).map_or(1.0, |viewport| self.ui_scale(viewport))
+    }
+    /// Projects a world space point into the viewport, in the logical pixels the layout uses,
+    /// so floating elements can follow things in the scene. None when the point is behind the camera.
+    pub fn world_to_screen(&self, viewport: &str, point: impl Into<cgmath::Point3<f32>>) -> Option<(f32, f32)> {
+        let window_id = self.viewport_lookup.get_by_left(viewport)?;
+        let window = &self.viewports.get(window_id)?.window;
+        let camera = self.viewport_cameras.get(viewport).map(String::as_str).unwrap_or(DEFAULT_CAMERA);
+        let camera = self.scene_renderer.cameras.get(camera)?;
+
+        let dpi_scale = window.scale_factor() as f32 * self.ui_scale(viewport);
+        let (x, y) = camera.world_to_screen(point.into(), window.inner_size().into())?;
+        Some((x / dpi_scale, y / dpi_scale))
+    }
+    /// Frames every shown model instance in the scene camera.
+    pub fn zoom_to_extents(&mut self) {
+        let bounds = self.models.values().filter_map(|model| model.bounds()).reduce(|(min_a, max_a), (min_b, max_b)| {
+            (min_a.zip(min_b, f32::min), max_a.zip(max_b, f32::max))
+        });
+        if let Some((min, max)) = bounds {
+            self.scene_renderer.camera().zoom_to_extents(min, max);
+        }
+    }
     pub fn load_gltf_model(&mut self, model_name: &str, filename: PathBuf, transfrom: Option<Transform>) -> BaseMesh{
-        self.model_ids.insert(model_name.to_string(), self.models.len());
         let model = load_model_gltf(filename, &self.ctx.device, &self.ctx.queue, transfrom).unwrap();
         let base = model.mesh.base.clone();
-        self.models.push(model);
+
+        // loading over an existing name replaces that model instead of leaving the old one orphaned
+        if let Some(old_model) = self.models.insert(model_name.to_string(), model) {
+            old_model.destroy();
+        }
 
         base
     }
-    pub fn transform_model(&mut self, model_name: &str) -> Result<&mut Transform, ()> {
-        if let Some(model_index) = self.model_ids.get(model_name) {
-            if let Some(model_reference) = self.models.get_mut(*model_index) {
-                model_reference.transform_dirty = true;
-                return Ok(&mut model_reference.transform)
+    /// Removes the model and all of its instances, freeing its gpu buffers and textures.
+    pub fn unload_model(&mut self, model_name: &str) -> Result<(), ()> {
+        let model = self.models.remove(model_name).ok_or(())?;
+        model.destroy();
+        self.request_redraw_all();
+        Ok(())
+    }
+    /// Bytes of gpu memory held by loaded models, per model name.
+    pub fn model_memory_usage(&self) -> HashMap<String, u64> {
+        self.models.iter().map(|(name, model)| (name.clone(), model.gpu_memory())).collect()
+    }
+    /// Total bytes of gpu memory held by loaded models.
+    pub fn gpu_memory_usage(&self) -> u64 {
+        self.models.values().map(|model| model.gpu_memory()).sum()
+    }
+    /// Writes the loaded models, their transforms and instances, and every camera to `path`.
+    /// Models are saved as references to their gltf files, which have to still be there when the scene is loaded.
+    pub fn save_scene(&self, path: impl AsRef<Path>) -> Result<(), ()> {
+        let mut models = self.models.iter().map(|(name, model)| SceneModel::new(name, model)).collect::<Vec<SceneModel>>();
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let scene = SceneFile {
+            models,
+            cameras: self.scene_renderer.cameras.iter().map(|(name, camera)| SceneCamera::new(name, camera)).collect(),
+            viewport_cameras: self.viewport_cameras.iter().map(|(viewport, camera)| (viewport.clone(), camera.clone())).collect(),
+        };
+
+        std::fs::write(path, scene.to_bytes()?).map_err(|e| tracing::error!(target: "telera::scene", "failed to write scene: {}", e))
+    }
+    /// Replaces every loaded model and camera with the ones saved in `path`.
+    pub fn load_scene(&mut self, path: impl AsRef<Path>) -> Result<(), ()> {
+        let bytes = std::fs::read(path).map_err(|e| tracing::error!(target: "telera::scene", "failed to read scene: {}", e))?;
+        let scene = SceneFile::from_bytes(&bytes)?;
+
+        let mut models = Vec::<(String, Model)>::new();
+        for saved in scene.models.iter() {
+            let transform = Transform::from(&saved.transform);
+            let Ok(mut model) = load_model_gltf(PathBuf::from(&saved.path), &self.ctx.device, &self.ctx.queue, Some(transform)) else {
+                tracing::error!(target: "telera::scene", "failed to load {} from {}", saved.name, saved.path);
+                for (_, model) in models {
+                    model.destroy();
+                }
+                return Err(())
+            };
+            for (instance_name, instance_transform) in saved.instances.iter() {
+                model.mesh.add_instance(instance_name.clone(), &self.ctx.device, Some(Transform::from(instance_transform)));
             }
+            model.wireframe = saved.wireframe;
+            models.push((saved.name.clone(), model));
+        }
+
+        for (_, model) in self.models.drain() {
+            model.destroy();
+        }
+        self.models.extend(models);
+
+        self.scene_renderer.cameras = scene.cameras.iter().map(|camera| (camera.name.clone(), camera.to_camera())).collect();
+        self.viewport_cameras = scene.viewport_cameras.into_iter().collect();
+        self.request_redraw_all();
+        Ok(())
+    }
+    pub fn transform_model(&mut self, model_name: &str) -> Result<&mut Transform, ()> {
+        if let Some(model_reference) = self.models.get_mut(model_name) {
+            model_reference.transform_dirty = true;
+            return Ok(&mut model_reference.transform)
         }
 
         Err(())
     }
-    pub fn add_instance(&mut self, model_name: &str, instance_name: &str, transfrom: Option<Transform>){
-        if let Some(model_index) = self.model_ids.get(model_name) {
-            if let Some(model) = self.models.get_mut(*model_index) {
-                model.mesh.add_instance(instance_name.to_string(), &self.ctx.device, transfrom);
-                //println!("hi {:?}", model.mesh.instances);
+    pub fn set_model_wireframe(&mut self, model_name: &str, wireframe: bool) -> Result<(), ()> {
+        if let Some(model) = self.models.get_mut(model_name) {
+            model.wireframe = wireframe;
+            return Ok(())
+        }
+        Err(())
+    }
+    /// Outlines a model, or one of its instances when `instance_name` is given.
+    pub fn set_selected(&mut self, model_name: &str, instance_name: Option<&str>, selected: bool) -> Result<(), ()> {
+        let Some(model) = self.models.get_mut(model_name) else {
+            return Err(())
+        };
+        match instance_name {
+            None => model.selected = selected,
+            Some(instance_name) => {
+                let Some(instance) = model.mesh.instance_lookup.get(instance_name).copied() else {
+                    return Err(())
+                };
+                match selected {
+                    true => model.selected_instances.insert(instance),
+                    false => model.selected_instances.remove(&instance),
+                };
+            }
+        }
+        self.request_redraw_all();
+        Ok(())
+    }
+    pub fn clear_selection(&mut self) {
+        for model in self.models.values_mut() {
+            model.selected = false;
+            model.selected_instances.clear();
+        }
+        self.request_redraw_all();
+    }
+    /// Color and pixel width of selection outlines.
+    pub fn set_selection_style(&mut self, color: [f32; 4], width: f32) {
+        self.scene_renderer.outline.color = color;
+        self.scene_renderer.outline.width = width;
+    }
+    /// Overrides the blend mode loaded from the model's gltf materials.
+    pub fn set_model_blend_mode(&mut self, model_name: &str, blend_mode: BlendMode) -> Result<(), ()> {
+        if let Some(model) = self.models.get_mut(model_name) {
+            for material in model.materials.iter_mut() {
+                material.set_blend_mode(blend_mode, &self.ctx.queue);
             }
+            return Ok(())
+        }
+        Err(())
+    }
+    /// Pins text to a point in the scene, drawn just behind the ui. Billboard labels are `size` logical pixels tall
+    /// at any distance, the rest are `size` world units tall. Returns an id for changing or removing the label.
+    pub fn add_world_label(&mut self, text: &str, position: impl Into<cgmath::Point3<f32>>, size: f32, billboard: bool) -> usize {
+        self.request_redraw_all();
+        self.scene_renderer.labels.add(WorldLabel {
+            text: text.to_string(),
+            position: position.into(),
+            size,
+            billboard,
+            color: [255, 255, 255, 255],
+        })
+    }
+    pub fn world_label_mut(&mut self, id: usize) -> Option<&mut WorldLabel> {
+        self.scene_renderer.labels.get_mut(id)
+    }
+    pub fn remove_world_label(&mut self, id: usize) -> Result<(), ()> {
+        self.request_redraw_all();
+        self.scene_renderer.labels.remove(id).map(|_| ()).ok_or(())
+    }
+    pub fn clear_world_labels(&mut self) {
+        self.scene_renderer.labels.clear();
+        self.request_redraw_all();
+    }
+    /// Draws a line through `points` that stays `width` pixels wide at any distance, replacing any plot with the same name.
+    /// Points can be plain positions or `(position, color)` pairs, colors blend along each segment.
+    pub fn add_polyline<V: Into<PlotVertex>>(&mut self, name: &str, points: impl IntoIterator<Item = V>, width: f32) {
+        let vertices = points.into_iter().map(Into::into).collect();
+        self.add_plot(name, Plot::new(&self.ctx.device, PlotKind::Polyline, vertices, width));
+    }
+    /// Draws a round dot at each point, 4 pixels across until changed with `set_plot_width`.
+    pub fn add_points<V: Into<PlotVertex>>(&mut self, name: &str, points: impl IntoIterator<Item = V>) {
+        let vertices = points.into_iter().map(Into::into).collect();
+        self.add_plot(name, Plot::new(&self.ctx.device, PlotKind::Points, vertices, 4.0));
+    }
+    /// Line width or point diameter in pixels.
+    pub fn set_plot_width(&mut self, name: &str, width: f32) -> Result<(), ()> {
+        let plot = self.scene_renderer.plots.plots.get_mut(name).ok_or(())?;
+        plot.set_width(width, &self.ctx.queue);
+        self.request_redraw_all();
+        Ok(())
+    }
+    pub fn remove_plot(&mut self, name: &str) -> Result<(), ()> {
+        let plot = self.scene_renderer.plots.plots.remove(name).ok_or(())?;
+        plot.destroy();
+        self.request_redraw_all();
+        Ok(())
+    }
+    /// Queues a line for the next redraw, call from `App::update` every time it should stay visible.
+    pub fn debug_line(&mut self, a: impl Into<cgmath::Point3<f32>>, b: impl Into<cgmath::Point3<f32>>, color: [f32; 3]) {
+        self.scene_renderer.debug_draw.line(a.into(), b.into(), color);
+    }
+    pub fn debug_aabb(&mut self, min: impl Into<cgmath::Point3<f32>>, max: impl Into<cgmath::Point3<f32>>, color: [f32; 3]) {
+        self.scene_renderer.debug_draw.aabb(min.into(), max.into(), color);
+    }
+    pub fn debug_sphere(&mut self, center: impl Into<cgmath::Point3<f32>>, radius: f32, color: [f32; 3]) {
+        self.scene_renderer.debug_draw.sphere(center.into(), radius, color);
+    }
+    pub fn add_instance(&mut self, model_name: &str, instance_name: &str, transfrom: Option<Transform>){
+        if let Some(model) = self.models.get_mut(model_name) {
+            model.mesh.add_instance(instance_name.to_string(), &self.ctx.device, transfrom);
+            //println!("hi {:?}", model.mesh.instances);
         }
     }
     pub fn transform_instance(&mut self, model_name: &str, instance_name: &str) -> Result<&mut Transform, ()> {
-        if  let Some(model_index) = self.model_ids.get(model_name) &&
-            let Some(model_reference) = self.models.get_mut(*model_index) &&
-            let Some(instance) = model_reference.mesh.instance_lookup.get(instance_name) &&
-            let Some(instance_reference) = model_reference.mesh.instances.get_mut(*instance)
+        if  let Some(model_reference) = self.models.get_mut(model_name) &&
+            let Some(instance) = model_reference.mesh.instance_lookup.get(instance_name).copied() &&
+            instance < model_reference.mesh.instances.len()
             {
-            model_reference.mesh.instances_dirty = true;
-            return Ok(instance_reference)
+            model_reference.mesh.instance_moved(instance);
+            return Ok(&mut model_reference.mesh.instances[instance])
         }
         Err(())
     }
@@ -386,9 +1832,9 @@ impl API{
 
 struct Application<UserApp, UserEvents>
 where 
-    UserEvents: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    UserEvents: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>+'static,
     <UserEvents as FromStr>::Err: Debug,
-    UserApp: App + ParserDataAccess<UserEvents>,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
 {
     layout_binder: Binder<UserEvents,UserApp>,
     core: Option<API>,
@@ -398,37 +1844,164 @@ where
     app_events: EventLoopProxy<InternalEvents>,
     #[allow(dead_code)]
     watcher: Option<ReadDirectoryChangesWatcher>,
+    /// what each layout file defined, so a change reloads only that file's definitions
+    layout_files: HashMap<PathBuf, LayoutFile>,
 }
 
-impl<UserEvents, UserApp> Application<UserApp, UserEvents>
+/// what one layout file put in the binder, keyed by its canonical path
+#[derive(Default)]
+struct LayoutFile {
+    /// empty for files of reusables alone
+    page: String,
+    reusables: Vec<String>,
+    /// canonical paths of the files it imports, directly or not
+    imports: Vec<PathBuf>,
+}
+
+fn run_render_hooks(
+    hooks: &mut [(String, RenderHookStage, RenderHook)],
+    stage: RenderHookStage,
+    viewport: &str,
+    render_pass: &mut wgpu::RenderPass<'static>,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    config: &wgpu::SurfaceConfiguration,
+) {
+    for (_, _, hook) in hooks.iter_mut().filter(|(_, hook_stage, _)| *hook_stage == stage) {
+        hook(viewport, render_pass, device, queue, config);
+    }
+}
+
+/// parses `path` and swaps what it defined last time for what it defines now.
+/// the old page and reusables are left alone when it fails to parse
+fn load_layout<UserEvents, UserApp>(binder: &mut Binder<UserEvents, UserApp>, layout_files: &mut HashMap<PathBuf, LayoutFile>, path: &Path) -> Result<(), Vec<LayoutError>>
 where 
     UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>,
     <UserEvents as FromStr>::Err: Debug+Default,
-    UserApp: App + ParserDataAccess<UserEvents>,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
 {
-    pub fn new(app_events: EventLoopProxy<InternalEvents>, user_application: UserApp, watcher: Option<ReadDirectoryChangesWatcher>) -> Self {
+    let _span = tracing::info_span!(target: "telera::parse", "load_layout", file = %path.display()).entered();
+    let parsed = process_layout_file_with_imports::<UserEvents>(path, &MarkdownTrees::new())?;
+    add_layout(binder, layout_files, path, parsed);
+    Ok(())
+}
 
-        let mut layout_binder = Binder::new();
+/// what `process_layout_file_with_imports` gives for one file
+type ParsedLayoutFile<UserEvents> = (String, Vec<Layout<UserEvents>>, HashMap<String, Vec<Layout<UserEvents>>>, Vec<PathBuf>);
 
-        let entries = std::fs::read_dir("src/layouts").unwrap_or_else(|e| {
-            eprintln!("Error reading directory: {}", e);
-            std::process::exit(1);
-        });
+/// every layout file in `directory`, their markdown parsed in parallel
+fn load_layout_directory<UserEvents, UserApp>(directory: &Path) -> Result<(Binder<UserEvents, UserApp>, HashMap<PathBuf, LayoutFile>), ()>
+where 
+    UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>,
+    <UserEvents as FromStr>::Err: Debug+Default,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
+{
+    let _span = tracing::info_span!(target: "telera::parse", "load_layouts", directory = %directory.display()).entered();
+    let mut layout_binder = Binder::new();
+    let mut layout_files = HashMap::new();
 
-        for dir in entries {
-            #[allow(for_loops_over_fallibles)]
-            for dir in dir {
-                let entry = dir.path();
-                if entry.is_file() 
-                && let Ok(file) = read_to_string(entry)
-                && let Ok((page_name, page_layout, reusables)) = process_layout::<UserEvents>(file) {   
-                    layout_binder.add_page(&page_name, page_layout);
-                    for (name, reusable) in reusables {
-                        layout_binder.add_reusable(&name, reusable);
-                    }
+    let entries = std::fs::read_dir(directory).map_err(|e| tracing::error!(target: "telera::parse", "failed to read {}: {}", directory.display(), e))?;
+    let files = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|entry| entry.is_file())
+        .collect::<Vec<PathBuf>>();
+
+    // a file that fails to parse is reported and skipped, the rest of the app still starts
+    let started = Instant::now();
+    let trees = parse_markdown_files(&files);
+    for file in &files {
+        match process_layout_file_with_imports::<UserEvents>(file, &trees) {
+            Ok(parsed) => add_layout(&mut layout_binder, &mut layout_files, file, parsed),
+            Err(errors) => {
+                for error in errors {
+                    tracing::error!(target: "telera::parse", "{}", error);
                 }
             }
         }
+    }
+    tracing::info!(target: "telera::parse", "loaded {} layout files in {:.1} ms", files.len(), started.elapsed().as_secs_f32() * 1000.0);
+
+    Ok((layout_binder, layout_files))
+}
+
+/// puts a parsed layout file's page and reusables in the binder, replacing what the file held before
+fn add_layout<UserEvents, UserApp>(binder: &mut Binder<UserEvents, UserApp>, layout_files: &mut HashMap<PathBuf, LayoutFile>, path: &Path, parsed: ParsedLayoutFile<UserEvents>)
+where 
+    UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>,
+    <UserEvents as FromStr>::Err: Debug+Default,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
+{
+    let file = path.canonicalize().unwrap_or(path.to_path_buf());
+    let (page_name, page_layout, reusables, imports) = parsed;
+
+    let old = layout_files.remove(&file).unwrap_or_default();
+    if !old.page.is_empty() && old.page != page_name {
+        binder.remove_page(&old.page);
+    }
+    for name in &old.reusables {
+        let defined_elsewhere = layout_files.values().any(|other| other.reusables.contains(name));
+        if !reusables.contains_key(name) && !defined_elsewhere {
+            binder.remove_reusable(name);
+        }
+    }
+
+    if !page_name.is_empty() {
+        binder.insert_page(&page_name, page_layout);
+    }
+    let names = reusables.keys().cloned().collect();
+    for (name, reusable) in reusables {
+        binder.reusable.insert(symbol_table::GlobalSymbol::new(name), reusable);
+    }
+    layout_files.insert(file, LayoutFile { page: page_name, reusables: names, imports });
+}
+
+/// reads the files and parses their markdown on as many threads as there are cores.
+/// turning the trees into layouts needs the app's events, so that's left to the calling thread
+fn parse_markdown_files(files: &[PathBuf]) -> MarkdownTrees {
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = files.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        // spans don't follow work onto other threads, so the workers' spans are parented explicitly
+        let parent = tracing::Span::current();
+        let workers = files.chunks(chunk_size)
+            .map(|chunk| {
+                let parent = parent.clone();
+                scope.spawn(move || chunk.iter()
+                    .filter_map(|file| {
+                        let _span = tracing::debug_span!(target: "telera::parse", parent: &parent, "parse", file = %file.display()).entered();
+                        // files that can't be read are reported when they're loaded
+                        let text = std::fs::read_to_string(file).ok()?;
+                        Some((file.canonicalize().unwrap_or(file.clone()), parse_markdown(&text)))
+                    })
+                    .collect::<Vec<_>>()
+                )
+            })
+            .collect::<Vec<_>>();
+        workers.into_iter().zip(files.chunks(chunk_size))
+            .flat_map(|(worker, chunk)| match worker.join() {
+                Ok(trees) => trees,
+                // a parser panic loses its thread's files, the others still load
+                Err(_) => chunk.iter()
+                    .map(|file| (
+                        file.canonicalize().unwrap_or(file.clone()),
+                        Err(LayoutError::new(None, "", "the parser panicked".to_string(), None))
+                    ))
+                    .collect(),
+            })
+            .collect()
+    })
+}
+
+impl<UserEvents, UserApp> Application<UserApp, UserEvents>
+where 
+    UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>+'static,
+    <UserEvents as FromStr>::Err: Debug+Default,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
+{
+    pub fn new(app_events: EventLoopProxy<InternalEvents>, user_application: UserApp, watcher: Option<ReadDirectoryChangesWatcher>) -> Self {
+
+        let (layout_binder, layout_files) = load_layout_directory(Path::new("src/layouts"))
+            .unwrap_or_else(|_| std::process::exit(1));
 
         Application {
             layout_binder,
@@ -436,6 +2009,7 @@ where
             app_events,
             user_application,
             watcher,
+            layout_files,
         }
     }
 
@@ -443,54 +2017,14 @@ where
 
 impl<UserEvents, UserApp> ApplicationHandler<InternalEvents> for Application<UserApp, UserEvents>
 where 
-    UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>,
+    UserEvents: FromStr+Clone+PartialEq+Debug+Default+EventHandler<UserApplication = UserApp>+'static,
     UserEvents: EventHandler<UserApplication = UserApp>, 
     <UserEvents as FromStr>::Err: Debug+Default,
-    UserApp: App + ParserDataAccess<UserEvents>,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
 {
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
         if self.core.is_none() {
-            let ctx = GraphicsContext::new();
-            let scene_renderer = SceneRenderer::new(&ctx.device);
-            let ui_renderer = Some(UIRenderer::new(&ctx.device, &ctx.queue));
-
-            let mut core =  API { 
-                staged_windows: Vec::new(), 
-                ctx,
-                scene_renderer,
-                ui_renderer,
-                ui_layout: LayoutEngine::<UIRenderer, UIImageDescriptor, CustomElement, CustomLayoutSettings>::new((1.0, 1.0)),
-                model_ids: HashMap::new(),
-                models: Vec::<Model>::new(),
-                viewport_lookup: bimap::BiMap::new(),
-                viewports: HashMap::new(),
-
-                event_string: "".to_string(),
-
-                left_mouse_pressed: false,
-                left_mouse_down: false,
-                left_mouse_released: false,
-                left_mouse_clicked: false,
-                left_mouse_double_clicked: false,
-                left_mouse_clicked_timer: None,
-                _left_mouse_dbl_clicked_timer: None,
-
-                right_mouse_pressed: false,
-                right_mouse_down: false,
-                right_mouse_released: false,
-                right_mouse_clicked: false,
-                right_mouse_clicked_timer: None,
-
-                x_at_click: 0.0,
-                y_at_click: 0.0,
-                focus: 0,
-                
-                dpi_scale: 0.0,
-                mouse_poistion: (0.0,0.0),
-                mouse_delta: (0.0,0.0),
-                scroll_delta_time: Instant::now(),
-                scroll_delta_distance: (0.0, 0.0),
-            };
+            let mut core = API::new(Some(self.app_events.clone()));
 
             self.user_application.initialize(&mut core);
             core.create_staged_viewports(event_loop);
@@ -503,11 +2037,42 @@ where
 
         if let Some(api) = &mut self.core {
             api.create_staged_viewports(event_loop);
+            // debug lines only live until the next update, which redraws them if it still wants them
+            api.scene_renderer.debug_draw.clear();
             self.user_application.update(api);
+            #[cfg(feature = "egui")]
+            if api.show_debug_ui
+            && let Some(layer) = api.egui_layers.get_mut(&window_id)
+            && let Some(viewport) = api.viewports.get(&window_id) {
+                let response = layer.on_window_event(&viewport.window, &event);
+                if response.repaint {
+                    viewport.window.request_redraw();
+                }
+                // input egui used doesn't reach the layouts or the camera
+                if response.consumed {
+                    return;
+                }
+            }
             api.scene_renderer.camera_controller.process_events(&event);
+            // input can change hover and widget state, other events only matter if the app's data changed
+            let input = matches!(event,
+                WindowEvent::CursorMoved { .. }
+                | WindowEvent::CursorLeft { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::KeyboardInput { .. }
+                | WindowEvent::ModifiersChanged(_)
+                | WindowEvent::Resized(_)
+                | WindowEvent::ScaleFactorChanged { .. }
+                | WindowEvent::Focused(_)
+            );
 
             match event {
                 WindowEvent::CloseRequested => {
+                    if let Some(recorder) = &api.recorder
+                    && api.viewport_lookup.get_by_left(&recorder.viewport) == Some(&window_id) {
+                        api.stop_recording();
+                    }
                     if api.viewports.len() < 2 {
                         event_loop.exit();
                     }
@@ -518,7 +2083,15 @@ where
                     api.resize_viewport(window_id, size);
                 }
                 WindowEvent::ScaleFactorChanged { scale_factor, inner_size_writer:_ } => {
-                    api.dpi_scale = scale_factor as f32;
+                    api.dpi_scale = scale_factor as f32 * api.window_ui_scale(window_id);
+                }
+                WindowEvent::ThemeChanged(theme) => {
+                    // every window reports the change, the app hears it once
+                    if (theme == Theme::Dark) != system_dark_mode() {
+                        set_system_dark_mode(theme == Theme::Dark);
+                        self.user_application.on_theme_changed(api, theme);
+                        api.request_redraw_all();
+                    }
                 }
                 WindowEvent::RedrawRequested => {
                     api.redraw_viewport(window_id, &mut self.layout_binder, &mut self.user_application);
@@ -533,10 +2106,6 @@ where
                                     if api.left_mouse_clicked_timer.is_none() {
                                         api.left_mouse_clicked_timer = Some(Instant::now());
                                     }
-                                    // else {
-                                    //     self.core.left_mouse_clicked_timer = None;
-                                    //     self.core.left_mouse_dbl_clicked_timer = Some(Instant::now());
-                                    // }
                                     api.x_at_click = api.mouse_poistion.0/api.dpi_scale;
                                     api.y_at_click = api.mouse_poistion.1/api.dpi_scale;
                                 }
@@ -545,12 +2114,11 @@ where
                                     && timer.elapsed().as_millis() < 400 {
                                         api.left_mouse_clicked = true;
                                         api.left_mouse_clicked_timer = None;
+                                        match api.left_mouse_dbl_clicked_timer.take() {
+                                            Some(last_click) if last_click.elapsed().as_millis() < 400 => api.left_mouse_double_clicked = true,
+                                            _ => api.left_mouse_dbl_clicked_timer = Some(Instant::now()),
+                                        }
                                     }
-                                    // if let Some(timer) = self.core.left_mouse_dbl_clicked_timer
-                                    // && timer.elapsed().as_millis() < 300 {
-                                    //     self.core.left_mouse_double_clicked = true;
-                                    //     self.core.left_mouse_dbl_clicked_timer = None;
-                                    // }
                                     api.left_mouse_down = false;
                                     api.left_mouse_released = true;
                                 }
@@ -589,6 +2157,28 @@ where
                     };
                     //viewport.window.request_redraw();
                 }
+                WindowEvent::ModifiersChanged(modifiers) => {
+                    api.modifiers = modifiers.state();
+                }
+                WindowEvent::KeyboardInput { device_id:_, event, is_synthetic:_ } => {
+                    if event.state == ElementState::Pressed {
+                        let zoom = match &event.logical_key {
+                            Key::Character(typed) if api.modifiers.control_key() || api.modifiers.super_key() => match typed.as_str() {
+                                "=" | "+" => Some(UI_SCALE_STEP),
+                                "-" => Some(-UI_SCALE_STEP),
+                                "0" => Some(0.0),
+                                _ => None,
+                            },
+                            _ => None,
+                        };
+                        // zoom shortcuts aren't typed into whatever has focus
+                        match (zoom, api.viewport_lookup.get_by_right(&window_id).cloned()) {
+                            (Some(0.0), Some(viewport)) => api.set_ui_scale(&viewport, 1.0),
+                            (Some(step), Some(viewport)) => api.set_ui_scale(&viewport, api.ui_scale(&viewport) + step),
+                            _ => api.keys_pressed.push(event.logical_key),
+                        }
+                    }
+                }
                 WindowEvent::CursorMoved { device_id:_, position } => {
                     api.mouse_delta.0 = position.x as f32 - api.mouse_poistion.0;
                     api.mouse_delta.1 = position.y as f32 - api.mouse_poistion.1;
@@ -596,18 +2186,110 @@ where
                 }
                 _ => {}
             }
-            api.request_redraw_viewport(window_id);
+            let changed = match self.user_application.data_version() {
+                Some(version) => api.drawn_versions.get(&window_id) != Some(&version),
+                None => true,
+            };
+            if input || changed {
+                api.request_redraw_viewport(window_id);
+            }
         }
     }
 
-    fn user_event(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop, event: InternalEvents) {
-        if let InternalEvents::RebuildLayout(path) = event {
-            let file = read_to_string(path).unwrap();
-            if let Ok((page_name, page_layout, reusables)) = process_layout::<UserEvents>(file) {
-                let _ = self.layout_binder.replace_page(&page_name, page_layout);
-                self.layout_binder.reusable.clear();
-                for (name, reusable) in reusables {
-                    self.layout_binder.add_reusable(&name, reusable);
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        if let Some(api) = &mut self.core
+        && let Some((step, mut next)) = api.fixed_step {
+            let now = Instant::now();
+            let mut steps = 0;
+            while next <= now {
+                // after a long stall the missed steps are skipped instead of run back to back
+                if steps == MAX_FIXED_CATCH_UP {
+                    next = now + step;
+                    break
+                }
+                self.user_application.fixed_update(api, step.as_secs_f32());
+                next += step;
+                steps += 1;
+            }
+            // the app may have changed or stopped the rate during its update
+            if let Some((current, _)) = api.fixed_step
+            && current == step {
+                api.fixed_step = Some((step, next));
+            }
+            if steps > 0 {
+                api.request_redraw_all();
+            }
+        }
+
+        if let Some(api) = &mut self.core
+        && let Some(at) = api.redraw_at
+        && at <= Instant::now() {
+            api.redraw_at = None;
+            api.request_redraw_all();
+        }
+
+        if let Some(api) = &self.core {
+            if api.exit_requested {
+                event_loop.exit();
+            }
+            let fixed_step = api.fixed_step.map(|(_, next)| next);
+            match fixed_step.into_iter().chain(api.redraw_at).min() {
+                Some(next) => event_loop.set_control_flow(ControlFlow::WaitUntil(next)),
+                None => event_loop.set_control_flow(ControlFlow::Wait),
+            }
+        }
+    }
+
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        if let Some(api) = &mut self.core {
+            self.user_application.shutdown(api);
+            api.stop_recording();
+        }
+    }
+
+    fn user_event(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, event: InternalEvents) {
+        if let InternalEvents::Shutdown = event {
+            event_loop.exit();
+        }
+        else if let InternalEvents::Redraw = event {
+            if let Some(api) = &mut self.core {
+                api.request_redraw_all();
+            }
+        }
+        else if let InternalEvents::Emit(event, context) = event {
+            if let Some(api) = &mut self.core {
+                api.emitted.push((event as Box<dyn std::any::Any>, context));
+                api.request_redraw_all();
+            }
+        }
+        else if let InternalEvents::RebuildLayout(path) = event {
+            let changed = path.canonicalize().unwrap_or(path.clone());
+            // files importing the changed one hold copies of its reusables, so they're reloaded with it
+            let mut files = vec![changed.clone()];
+            files.extend(self.layout_files.iter()
+                .filter(|(_, layout_file)| layout_file.imports.contains(&changed))
+                .map(|(file, _)| file.clone())
+            );
+
+            for file in files {
+                match load_layout(&mut self.layout_binder, &mut self.layout_files, &file) {
+                    Ok(()) => {
+                        if let Some(api) = &mut self.core
+                        && api.layout_errors.remove(&file).is_some() {
+                            api.request_redraw_all();
+                        }
+                    }
+                    Err(errors) => {
+                        for error in &errors {
+                            tracing::error!(target: "telera::parse", "{}", error);
+                        }
+                        if let Some(api) = &mut self.core {
+                            // files of reusables alone show their errors everywhere
+                            let page = self.layout_files.get(&file).map(|layout_file| layout_file.page.clone()).filter(|page| !page.is_empty());
+                            api.layout_errors.insert(file, (page, errors));
+                            api.request_redraw_all();
+                        }
+                    }
                 }
             }
         }
@@ -633,13 +2315,19 @@ fn watch_file(file: &str, sender: EventLoopProxy<InternalEvents>) -> Result<Read
 
 pub fn run<UserEvents, UserApp>(user_application: UserApp)
 where 
-    UserEvents: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    UserEvents: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>+'static,
     <UserEvents as FromStr>::Err: Debug+Default,
-    UserApp: App + ParserDataAccess<UserEvents>,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
 {
     if let Ok(event_loop) = EventLoop::<InternalEvents>::with_user_event().build() {
         event_loop.set_control_flow(ControlFlow::Wait);
         let file_watcher_proxy = event_loop.create_proxy();
+        let shutdown_proxy = event_loop.create_proxy();
+        if let Err(e) = ctrlc::set_handler(move || {
+            let _ = shutdown_proxy.send_event(InternalEvents::Shutdown);
+        }) {
+            tracing::warn!(target: "telera::app", "failed to install ctrl-c handler: {}", e);
+        }
         if let Ok(watcher) = watch_file("src/layouts", file_watcher_proxy) {
             let mut app = Application::new(
                 event_loop.create_proxy(), 
@@ -655,4 +2343,21 @@ where
     else {
         panic!("Event loop creation failed.");
     }
+}
+
+/// `run` with a tokio runtime beside the event loop. handlers can `tokio::spawn` or use `api.spawn_async`,
+/// futures run on the runtime's worker threads
+#[cfg(feature = "async")]
+pub fn run_async<UserEvents, UserApp>(user_application: UserApp)
+where 
+    UserEvents: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>+'static,
+    <UserEvents as FromStr>::Err: Debug+Default,
+    UserApp: App + ParserDataAccess<UserEvents> + 'static,
+{
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap_or_else(|e| panic!("Async runtime creation failed: {}", e));
+    let _runtime = runtime.enter();
+    run::<UserEvents, UserApp>(user_application);
 }
\ No newline at end of file
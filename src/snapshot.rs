@@ -0,0 +1,213 @@
+use std::{fmt, fs, io, path::{Path, PathBuf}};
+
+use telera_layout::RenderCommand;
+
+use crate::{
+    Rect,
+    ui_toolkit::{ui_renderer::{CustomLayoutSettings, UIImageDescriptor}, ui_shapes::CustomElement},
+};
+
+/// what a layout drew, from `api.layout_snapshot` once `api.record_layout_snapshots` is on.
+/// its text form is what `assert_layout_snapshot!` saves and compares, one line per element or draw
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LayoutSnapshot {
+    /// elements with an `id` config that were laid out, sorted by id
+    pub elements: Vec<(String, Rect)>,
+    /// in the order the layout drew them
+    pub draws: Vec<SnapshotDraw>,
+}
+
+/// why `LayoutSnapshot::check` failed
+#[derive(Debug)]
+pub enum SnapshotError {
+    Read { path: PathBuf, error: io::Error },
+    Write { path: PathBuf, error: io::Error },
+    /// the lines that differ, `-` saved and `+` current
+    Changed { path: PathBuf, diff: Vec<String> },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Read { path, error } => write!(f, "couldn't read snapshot {}: {}", path.display(), error),
+            SnapshotError::Write { path, error } => write!(f, "couldn't write snapshot {}: {}", path.display(), error),
+            SnapshotError::Changed { path, diff } => {
+                write!(f, "layout snapshot {} changed, - saved + current:", path.display())?;
+                for line in diff {
+                    write!(f, "\n{}", line)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// one draw of a layout, colors are 0 to 255 like in layout files
+#[derive(Clone, Debug, PartialEq)]
+pub enum SnapshotDraw {
+    Rectangle { bounds: Rect, color: [f32; 4] },
+    Border { bounds: Rect, color: [f32; 4], width: f32 },
+    Text { bounds: Rect, text: String, size: f32, color: [f32; 4] },
+    Image { bounds: Rect, atlas: String },
+    ClipStart { bounds: Rect },
+    ClipEnd,
+    Custom { bounds: Rect, color: [f32; 4] },
+}
+
+impl LayoutSnapshot {
+    pub(crate) fn from_commands(commands: &[RenderCommand<UIImageDescriptor, CustomElement, CustomLayoutSettings>]) -> Self {
+        let draws = commands.iter().filter_map(|command| Some(match command {
+            RenderCommand::Rectangle(r) => SnapshotDraw::Rectangle {
+                bounds: rect(r.bounding_box.x, r.bounding_box.y, r.bounding_box.width, r.bounding_box.height),
+                color: [r.color.r, r.color.g, r.color.b, r.color.a],
+            },
+            RenderCommand::Border(b) => SnapshotDraw::Border {
+                bounds: rect(b.bounding_box.x, b.bounding_box.y, b.bounding_box.width, b.bounding_box.height),
+                color: [b.color.r, b.color.g, b.color.b, b.color.a],
+                width: b.width.top as f32,
+            },
+            RenderCommand::Text(t) => SnapshotDraw::Text {
+                bounds: rect(t.bounding_box.x, t.bounding_box.y, t.bounding_box.width, t.bounding_box.height),
+                text: t.text.to_string(),
+                size: t.font_size as f32,
+                color: [t.color.r, t.color.g, t.color.b, t.color.a],
+            },
+            RenderCommand::Image(image) => SnapshotDraw::Image {
+                bounds: rect(image.bounding_box.x, image.bounding_box.y, image.bounding_box.width, image.bounding_box.height),
+                atlas: image.data.atlas.clone(),
+            },
+            RenderCommand::ScissorStart(b) => SnapshotDraw::ClipStart { bounds: rect(b.x, b.y, b.width, b.height) },
+            RenderCommand::ScissorEnd => SnapshotDraw::ClipEnd,
+            RenderCommand::Custom(shape) => SnapshotDraw::Custom {
+                bounds: rect(shape.bounding_box.x, shape.bounding_box.y, shape.bounding_box.width, shape.bounding_box.height),
+                color: [shape.background_color.r, shape.background_color.g, shape.background_color.b, shape.background_color.a],
+            },
+            RenderCommand::None => return None,
+        })).collect();
+
+        Self { elements: Vec::new(), draws }
+    }
+
+    /// compares the snapshot with the text form saved at `path`, the error holds a diff when they differ.
+    /// the file is written instead when it doesn't exist yet or `TELERA_UPDATE_SNAPSHOTS` is set
+    pub fn check(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        let current = self.to_string();
+
+        if std::env::var_os("TELERA_UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            return fs::write(path, current).map_err(|error| SnapshotError::Write { path: path.to_path_buf(), error })
+        }
+
+        let saved = fs::read_to_string(path).map_err(|error| SnapshotError::Read { path: path.to_path_buf(), error })?;
+        if saved.replace("\r\n", "\n") == current {
+            return Ok(())
+        }
+        Err(SnapshotError::Changed { path: path.to_path_buf(), diff: diff(&saved, &current) })
+    }
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+    Rect { x, y, width, height }
+}
+
+/// a tenth of a layout unit is plenty, finer than that is float noise between platforms
+struct Bounds<'a>(&'a Rect);
+
+impl fmt::Display for Bounds<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:.1}, {:.1}, {:.1} x {:.1})", self.0.x, self.0.y, self.0.width, self.0.height)
+    }
+}
+
+struct Rgba<'a>(&'a [f32; 4]);
+
+impl fmt::Display for Rgba<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b, a] = self.0.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+        write!(f, "#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+impl fmt::Display for LayoutSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (id, bounds) in &self.elements {
+            writeln!(f, "element {} {}", id, Bounds(bounds))?;
+        }
+        let mut depth = 0;
+        for draw in &self.draws {
+            if let SnapshotDraw::ClipEnd = draw {
+                depth -= 1;
+            }
+            write!(f, "{:indent$}", "", indent = depth.max(0) as usize * 2)?;
+            match draw {
+                SnapshotDraw::Rectangle { bounds, color } => writeln!(f, "rect {} {}", Bounds(bounds), Rgba(color))?,
+                SnapshotDraw::Border { bounds, color, width } => writeln!(f, "border {} {} {}", Bounds(bounds), Rgba(color), width)?,
+                SnapshotDraw::Text { bounds, text, size, color } => writeln!(f, "text {} {} {} {:?}", Bounds(bounds), size, Rgba(color), text)?,
+                SnapshotDraw::Image { bounds, atlas } => writeln!(f, "image {} {}", Bounds(bounds), atlas)?,
+                SnapshotDraw::ClipStart { bounds } => {
+                    writeln!(f, "clip {}", Bounds(bounds))?;
+                    depth += 1;
+                }
+                SnapshotDraw::ClipEnd => writeln!(f, "end clip")?,
+                SnapshotDraw::Custom { bounds, color } => writeln!(f, "custom {} {}", Bounds(bounds), Rgba(color))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// the lines that differ, from the longest run of lines both have in common
+fn diff(saved: &str, current: &str) -> Vec<String> {
+    let saved = saved.lines().collect::<Vec<&str>>();
+    let current = current.lines().collect::<Vec<&str>>();
+
+    // common[i][j] is how many lines saved[i..] and current[j..] have in common
+    let mut common = vec![vec![0u32; current.len() + 1]; saved.len() + 1];
+    for i in (0..saved.len()).rev() {
+        for j in (0..current.len()).rev() {
+            common[i][j] = match saved[i] == current[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < saved.len() || j < current.len() {
+        if i < saved.len() && j < current.len() && saved[i] == current[j] {
+            i += 1;
+            j += 1;
+        }
+        else if j < current.len() && (i == saved.len() || common[i][j + 1] >= common[i + 1][j]) {
+            lines.push(format!("{:>5} + {}", j + 1, current[j]));
+            j += 1;
+        }
+        else {
+            lines.push(format!("{:>5} - {}", i + 1, saved[i]));
+            i += 1;
+        }
+    }
+    lines
+}
+
+/// Fails the test when `snapshot` differs from the one saved at `path`, relative to the tested crate's manifest.
+/// The file is written when it's missing, or when the `TELERA_UPDATE_SNAPSHOTS` environment variable is set.
+/// ```ignore
+/// api.record_layout_snapshots(true);
+/// // after a frame was drawn
+/// assert_layout_snapshot!(api.layout_snapshot("Main").unwrap(), "tests/snapshots/main.snap");
+/// ```
+#[macro_export]
+macro_rules! assert_layout_snapshot {
+    ($snapshot:expr, $path:expr) => {
+        if let Err(error) = $snapshot.check(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path)) {
+            panic!("{}", error);
+        }
+    };
+}
@@ -0,0 +1,83 @@
+use std::{any::Any, sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::{self, Receiver}}};
+
+use winit::event_loop::EventLoopProxy;
+
+use crate::{EventContext, InternalEvents};
+
+/// reaches the app from other threads, get one with `api.proxy()`
+#[derive(Clone)]
+pub struct ApiProxy {
+    /// `None` without an event loop, what's sent is dropped
+    pub(crate) events: Option<EventLoopProxy<InternalEvents>>,
+}
+
+impl ApiProxy {
+    /// Redraws every viewport, for when a worker changed something the layouts show.
+    pub fn request_redraw(&self) {
+        self.send(InternalEvents::Redraw);
+    }
+    /// Same as `api.emit`, the event is dispatched on the ui thread at the start of the next frame.
+    pub fn emit<Event: Send + 'static>(&self, event: Event, context: Option<EventContext>) {
+        let event: Box<dyn Any + Send> = Box::new(event);
+        self.send(InternalEvents::Emit(event, context));
+    }
+    /// Same as `api.exit`, `App::shutdown` still runs before the event loop stops.
+    pub fn exit(&self) {
+        self.send(InternalEvents::Shutdown);
+    }
+
+    fn send(&self, event: InternalEvents) {
+        if let Some(events) = &self.events {
+            let _ = events.send_event(event);
+        }
+    }
+}
+
+/// work started with `api.spawn`, its result waits here once it's done
+pub struct Task<T> {
+    result: Receiver<T>,
+    finished: Arc<AtomicBool>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    pub(crate) fn spawn<Event: Send + 'static>(proxy: ApiProxy, done: Event, work: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (task, finish) = Self::new(proxy, done);
+        std::thread::spawn(move || finish(work()));
+        task
+    }
+
+    /// runs `future` on the runtime `run_async` started
+    #[cfg(feature = "async")]
+    pub(crate) fn spawn_async<Event: Send + 'static>(proxy: ApiProxy, done: Event, future: impl Future<Output = T> + Send + 'static) -> Self {
+        let (task, finish) = Self::new(proxy, done);
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(async move { finish(future.await) });
+            }
+            Err(_) => tracing::error!(target: "telera::app", "spawn_async needs the app to be started with run_async"),
+        }
+        task
+    }
+
+    /// the task and what hands it the result and sends `done`
+    pub(crate) fn new<Event: Send + 'static>(proxy: ApiProxy, done: Event) -> (Self, impl FnOnce(T) + Send + 'static) {
+        let (sender, result) = mpsc::channel();
+        let finished = Arc::new(AtomicBool::new(false));
+        let task = Self { result, finished: finished.clone() };
+        let finish = move |value: T| {
+            let _ = sender.send(value);
+            finished.store(true, Ordering::Release);
+            proxy.emit(done, None);
+        };
+        (task, finish)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+
+    /// the result once the work is done, it's only given out once
+    pub fn take(&self) -> Option<T> {
+        self.result.try_recv().ok()
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::Debug;
+
+use symbol_table::GlobalSymbol;
+use telera_layout::Color;
+
+use crate::{Config, DataSrc, Element, Layout, ui_toolkit::{expression::{binding_name, TextFormat}, markdown::collapse}};
+
+/// layout commands built in code, for pages made at runtime. hand `build()` to `Binder::add_page`,
+/// or return it from `ParserDataAccess::take_pages` once the app is running
+/// ```ignore
+/// let page = Page::new()
+///     .element(|e| e.grow().vertical().color(Color::rgb(30.0, 30.0, 30.0))
+///         .children(|page| page.text("hello {name}")))
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Page<Event: Clone+Debug+Default+PartialEq> {
+    commands: Vec<Layout<Event>>,
+}
+
+impl<Event: Clone+Debug+Default+PartialEq> Default for Page<Event> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Event: Clone+Debug+Default+PartialEq> Page<Event> {
+    pub fn new() -> Self {
+        Self { commands: Vec::new() }
+    }
+
+    pub fn element(mut self, build: impl FnOnce(ElementBuilder<Event>) -> ElementBuilder<Event>) -> Self {
+        let element = build(ElementBuilder::new());
+        self.commands.append(&mut element.build());
+        self
+    }
+
+    /// static text can have `{name}` holes like text in layout files
+    pub fn text(self, text: impl Into<DataSrc<String>>) -> Self {
+        self.styled_text(text, |text| text)
+    }
+
+    pub fn styled_text(mut self, text: impl Into<DataSrc<String>>, build: impl FnOnce(TextBuilder<Event>) -> TextBuilder<Event>) -> Self {
+        self.commands.push(Layout::Element(Element::TextElementOpened));
+        self.commands.push(Layout::Element(Element::TextConfigOpened));
+        self.commands.append(&mut build(TextBuilder { configs: Vec::new() }).configs);
+        self.commands.push(Layout::Element(Element::TextConfigClosed));
+        let text = match text.into() {
+            DataSrc::Static(text) => match TextFormat::parse(&text, None) {
+                Some(format) => Element::TextElementFormatted(format),
+                None => Element::TextElementClosed(DataSrc::Static(text)),
+            },
+            dynamic => Element::TextElementClosed(dynamic),
+        };
+        self.commands.push(Layout::Element(text));
+        self
+    }
+
+    /// a reusable from a layout file or another page builder registered with `Binder::add_reusable`
+    pub fn reusable(mut self, name: &str) -> Self {
+        self.commands.push(Layout::Element(Element::UseOpened));
+        self.commands.push(Layout::Element(Element::UseClosed(GlobalSymbol::new(name))));
+        self
+    }
+
+    /// `condition` can be an expression, like in layout files
+    pub fn when(mut self, condition: &str, build: impl FnOnce(Page<Event>) -> Page<Event>) -> Self {
+        self.commands.push(Layout::Element(Element::IfOpened { condition: binding_name(condition, None) }));
+        self.commands.append(&mut build(Page::new()).commands);
+        self.commands.push(Layout::Element(Element::IfClosed));
+        self
+    }
+
+    pub fn when_not(mut self, condition: &str, build: impl FnOnce(Page<Event>) -> Page<Event>) -> Self {
+        self.commands.push(Layout::Element(Element::IfNotOpened { condition: binding_name(condition, None) }));
+        self.commands.append(&mut build(Page::new()).commands);
+        self.commands.push(Layout::Element(Element::IfClosed));
+        self
+    }
+
+    /// `build` makes one item, laid out once for every item of the app's list `src`
+    pub fn list(mut self, src: &str, build: impl FnOnce(Page<Event>) -> Page<Event>) -> Self {
+        self.commands.push(Layout::Element(Element::ListOpened));
+        self.commands.append(&mut build(Page::new()).commands);
+        self.commands.push(Layout::Element(Element::ListClosed { src: GlobalSymbol::new(src), key: None }));
+        self
+    }
+
+    /// `build` is laid out `times` times with the count in the local `index`
+    pub fn repeat(mut self, times: impl Into<DataSrc<u16>>, build: impl FnOnce(Page<Event>) -> Page<Event>) -> Self {
+        self.commands.push(Layout::Element(Element::ListOpened));
+        self.commands.append(&mut build(Page::new()).commands);
+        self.commands.push(Layout::Element(Element::RepeatClosed { times: times.into(), index: GlobalSymbol::new("index") }));
+        self
+    }
+
+    /// any command, for what the builder has no method for
+    pub fn command(mut self, command: Layout<Event>) -> Self {
+        self.commands.push(command);
+        self
+    }
+
+    pub fn build(self) -> Vec<Layout<Event>> {
+        self.commands
+    }
+}
+
+/// the configs and children of one element
+#[derive(Clone, Debug)]
+pub struct ElementBuilder<Event: Clone+Debug+Default+PartialEq> {
+    configs: Vec<Layout<Event>>,
+    children: Page<Event>,
+}
+
+impl<Event: Clone+Debug+Default+PartialEq> ElementBuilder<Event> {
+    fn new() -> Self {
+        Self { configs: Vec::new(), children: Page::new() }
+    }
+
+    fn build(self) -> Vec<Layout<Event>> {
+        let mut commands = vec![
+            Layout::Element(Element::ElementOpened { id: None }),
+            Layout::Element(Element::ConfigOpened),
+        ];
+        commands.extend(self.configs);
+        commands.push(Layout::Element(Element::ConfigClosed));
+        commands.extend(self.children.commands);
+        commands.push(Layout::Element(Element::ElementClosed));
+        collapse(commands)
+    }
+
+    /// any element config, for what the builder has no method for
+    pub fn config(mut self, config: Config) -> Self {
+        self.configs.push(Layout::Config(config));
+        self
+    }
+
+    pub fn children(mut self, build: impl FnOnce(Page<Event>) -> Page<Event>) -> Self {
+        self.children = build(self.children);
+        self
+    }
+
+    pub fn id(self, id: &str) -> Self {
+        self.config(Config::Id(DataSrc::Static(id.to_string())))
+    }
+
+    pub fn grow(self) -> Self {
+        self.config(Config::GrowAll)
+    }
+
+    pub fn width_grow(self) -> Self {
+        self.config(Config::GrowX)
+    }
+
+    pub fn height_grow(self) -> Self {
+        self.config(Config::GrowY)
+    }
+
+    pub fn width_fit(self) -> Self {
+        self.config(Config::FitX)
+    }
+
+    pub fn height_fit(self) -> Self {
+        self.config(Config::FitY)
+    }
+
+    pub fn width_fixed(self, width: impl Into<DataSrc<f32>>) -> Self {
+        self.config(Config::FixedX(width.into()))
+    }
+
+    pub fn height_fixed(self, height: impl Into<DataSrc<f32>>) -> Self {
+        self.config(Config::FixedY(height.into()))
+    }
+
+    pub fn padding_all(self, padding: impl Into<DataSrc<u16>>) -> Self {
+        self.config(Config::PaddingAll(padding.into()))
+    }
+
+    pub fn child_gap(self, gap: impl Into<DataSrc<u16>>) -> Self {
+        self.config(Config::ChildGap(gap.into()))
+    }
+
+    pub fn vertical(self) -> Self {
+        self.config(Config::Vertical)
+    }
+
+    pub fn color(self, color: impl Into<DataSrc<Color>>) -> Self {
+        self.config(Config::Color(color.into()))
+    }
+
+    pub fn radius_all(self, radius: impl Into<DataSrc<f32>>) -> Self {
+        self.config(Config::RadiusAll(radius.into()))
+    }
+
+    pub fn border(self, width: impl Into<DataSrc<u16>>, color: impl Into<DataSrc<Color>>) -> Self {
+        self.config(Config::BorderAll(width.into())).config(Config::BorderColor(color.into()))
+    }
+
+    pub fn opacity(self, opacity: impl Into<DataSrc<f32>>) -> Self {
+        self.config(Config::Opacity(opacity.into()))
+    }
+
+    pub fn tooltip(self, text: impl Into<DataSrc<String>>) -> Self {
+        self.config(Config::Tooltip(text.into()))
+    }
+
+    /// sends `event` when the element is clicked
+    pub fn on_click(mut self, event: impl Into<DataSrc<Event>>) -> Self {
+        self.configs.push(Layout::Element(Element::LeftClickedOpened { event: Some(event.into()) }));
+        self.configs.push(Layout::Element(Element::LeftClickedClosed));
+        self
+    }
+
+    /// configs applied while the pointer is over the element
+    pub fn hovered(mut self, build: impl FnOnce(ElementBuilder<Event>) -> ElementBuilder<Event>) -> Self {
+        self.configs.push(Layout::Element(Element::HoverOpened { event: None }));
+        self.configs.append(&mut build(ElementBuilder::new()).configs);
+        self.configs.push(Layout::Element(Element::HoverClosed));
+        self
+    }
+}
+
+/// the configs of one text element
+#[derive(Clone, Debug)]
+pub struct TextBuilder<Event: Clone+Debug+Default+PartialEq> {
+    configs: Vec<Layout<Event>>,
+}
+
+impl<Event: Clone+Debug+Default+PartialEq> TextBuilder<Event> {
+    /// any text config, for what the builder has no method for
+    pub fn config(mut self, config: Config) -> Self {
+        self.configs.push(Layout::Config(config));
+        self
+    }
+
+    pub fn font_size(self, size: impl Into<DataSrc<u16>>) -> Self {
+        self.config(Config::FontSize(size.into()))
+    }
+
+    pub fn color(self, color: impl Into<DataSrc<Color>>) -> Self {
+        self.config(Config::FontColor(color.into()))
+    }
+
+    pub fn line_height(self, height: impl Into<DataSrc<u16>>) -> Self {
+        self.config(Config::LineHeight(height.into()))
+    }
+
+    pub fn font(self, font: impl Into<DataSrc<String>>) -> Self {
+        self.config(Config::Font(font.into()))
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::Debug;
+
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+use winit::keyboard::NamedKey;
+
+use crate::{API, DataSrc, EventContext};
+
+const NORMAL: Color = Color { r: 140.0, g: 140.0, b: 140.0, a: 255.0 };
+const HOVER: Color = Color { r: 120.0, g: 120.0, b: 120.0, a: 255.0 };
+const PRESSED: Color = Color { r: 95.0, g: 95.0, b: 95.0, a: 255.0 };
+const DISABLED: Color = Color { r: 190.0, g: 190.0, b: 190.0, a: 255.0 };
+const DISABLED_TEXT: Color = Color { r: 120.0, g: 120.0, b: 120.0, a: 255.0 };
+
+/// `tk` button as parsed from a layout, state colors fall back to `color` and then to the default palette
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ButtonConfig<Event: Clone+Debug+PartialEq+Default> {
+    pub label: DataSrc<String>,
+    pub emit: Option<DataSrc<Event>>,
+    pub disabled: DataSrc<bool>,
+    pub color: Option<DataSrc<Color>>,
+    pub hover_color: Option<DataSrc<Color>>,
+    pub pressed_color: Option<DataSrc<Color>>,
+    pub disabled_color: Option<DataSrc<Color>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonColors {
+    pub normal: Color,
+    pub hover: Color,
+    pub pressed: Color,
+    pub disabled: Color,
+}
+
+impl Default for ButtonColors {
+    fn default() -> Self {
+        Self {
+            normal: NORMAL,
+            hover: HOVER,
+            pressed: PRESSED,
+            disabled: DISABLED,
+        }
+    }
+}
+
+/// a button with its data resolved for this frame
+pub struct Button<'frame, Event> {
+    pub label: &'frame str,
+    pub event: Option<Event>,
+    pub disabled: bool,
+    pub colors: ButtonColors,
+}
+
+/// `config` and `text_config` already hold whatever the layout set, the button only adds its state visuals
+pub fn button<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    text_config: &mut TextConfig,
+    button: Button<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    api.ui_layout.open_element();
+
+    let hovered = !button.disabled && api.ui_layout.hovered();
+    let color = match (button.disabled, hovered, api.left_mouse_down) {
+        (true, _, _) => button.colors.disabled,
+        (false, true, true) => button.colors.pressed,
+        (false, true, false) => button.colors.hover,
+        (false, false, _) => button.colors.normal,
+    };
+    config.color(color)
+        .align_children_x_center()
+        .align_children_y_center()
+        .parse();
+
+    let id = api.ui_layout.configure_element(config);
+
+    let clicked = hovered && api.left_mouse_clicked;
+    if clicked {
+        api.focus = id;
+    }
+    let activated = !button.disabled
+        && api.focus == id
+        && (api.named_key_pressed(NamedKey::Enter) || api.named_key_pressed(NamedKey::Space));
+
+    if (clicked || activated) && let Some(event) = button.event {
+        events.push((event, None));
+    }
+
+    if button.disabled {
+        text_config.color(DISABLED_TEXT).parse();
+    }
+    api.ui_layout.add_text_element(button.label, text_config, false);
+
+    api.ui_layout.close_element();
+
+    events
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use symbol_table::GlobalSymbol;
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+use winit::keyboard::NamedKey;
+
+use crate::{API, DataSrc, EventContext};
+
+const BOX: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+const BORDER: Color = Color { r: 110.0, g: 110.0, b: 110.0, a: 255.0 };
+const BORDER_HOVER: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const MARK: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const DISABLED: Color = Color { r: 190.0, g: 190.0, b: 190.0, a: 255.0 };
+const DISABLED_TEXT: Color = Color { r: 120.0, g: 120.0, b: 120.0, a: 255.0 };
+
+/// `tk` checkbox or radio as parsed from a layout, `checked` is read with `get_bool` and written with `set_bool`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ToggleConfig<Event: Clone+Debug+PartialEq+Default> {
+    pub label: DataSrc<String>,
+    pub checked: DataSrc<bool>,
+    pub emit: Option<DataSrc<Event>>,
+    pub disabled: DataSrc<bool>,
+    /// radios only
+    pub group: String,
+}
+
+/// a checkbox or radio with its data resolved for this frame
+pub struct Toggle<'frame, Event> {
+    pub label: &'frame str,
+    pub checked: bool,
+    pub event: Option<Event>,
+    pub disabled: bool,
+    pub radio: bool,
+}
+
+pub(crate) type Binding = (GlobalSymbol, Option<(GlobalSymbol, usize)>);
+
+/// bool writes from widgets, held until the layout pass lets go of the app
+#[derive(Default)]
+pub struct BoolWrites {
+    writes: Vec<(Binding, bool)>,
+    radio_groups: HashMap<String, Vec<Binding>>,
+    selected: Vec<(String, Binding)>,
+}
+
+impl BoolWrites {
+    pub fn set(&mut self, binding: Binding, value: bool) {
+        self.writes.push((binding, value));
+    }
+
+    pub fn join_group(&mut self, group: &str, binding: Binding) {
+        self.radio_groups.entry(group.to_string()).or_default().push(binding);
+    }
+
+    pub fn select(&mut self, group: &str, binding: Binding) {
+        self.selected.push((group.to_string(), binding));
+    }
+
+    /// selecting a radio clears every other member of its group seen this frame
+    pub fn drain(&mut self) -> Vec<(Binding, bool)> {
+        let mut writes = std::mem::take(&mut self.writes);
+        for (group, selected) in self.selected.drain(..) {
+            for member in self.radio_groups.get(&group).into_iter().flatten() {
+                writes.push((*member, *member == selected));
+            }
+        }
+        self.radio_groups.clear();
+        writes
+    }
+}
+
+/// returns true when the user flipped it this frame, the change event carries the new state as its code
+pub fn toggle<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    text_config: &mut TextConfig,
+    toggle: Toggle<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> (Vec::<(Event, Option<EventContext>)>, bool)
+{
+    api.ui_layout.open_element();
+
+    let hovered = !toggle.disabled && api.ui_layout.hovered();
+    config.child_gap(6).align_children_y_center().parse();
+
+    let id = api.ui_layout.configure_element(config);
+
+    let clicked = hovered && api.left_mouse_clicked;
+    if clicked {
+        api.focus = id;
+    }
+    let activated = clicked || (!toggle.disabled && api.focus == id && api.named_key_pressed(NamedKey::Space));
+    // a radio can only be turned on by the user, its group turns it off
+    let changed = activated && !(toggle.radio && toggle.checked);
+    let new_state = match toggle.radio {
+        true => true,
+        false => !toggle.checked,
+    };
+
+    if changed && let Some(event) = toggle.event {
+        events.push((event, Some(EventContext::from_code(new_state as u32))));
+    }
+
+    let shown_state = match changed {
+        true => new_state,
+        false => toggle.checked,
+    };
+    let (box_radius, mark_radius) = match toggle.radio {
+        true => (8.0, 4.0),
+        false => (3.0, 1.0),
+    };
+    let border = match (toggle.disabled, hovered) {
+        (true, _) => DISABLED,
+        (false, true) => BORDER_HOVER,
+        (false, false) => BORDER,
+    };
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new()
+        .x_fixed(16.0)
+        .y_fixed(16.0)
+        .padding_all(4)
+        .color(BOX)
+        .border_all(2)
+        .border_color(border)
+        .radius_all(box_radius)
+        .end()
+    );
+        if shown_state {
+            api.ui_layout.open_element();
+            api.ui_layout.configure_element(&ElementConfiguration::new()
+                .x_grow()
+                .y_grow()
+                .color(match toggle.disabled {
+                    true => DISABLED,
+                    false => MARK,
+                })
+                .radius_all(mark_radius)
+                .end()
+            );
+            api.ui_layout.close_element();
+        }
+    api.ui_layout.close_element();
+
+    if toggle.disabled {
+        text_config.color(DISABLED_TEXT).parse();
+    }
+    api.ui_layout.add_text_element(toggle.label, text_config, false);
+
+    api.ui_layout.close_element();
+
+    (events, changed)
+}
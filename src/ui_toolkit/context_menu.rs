@@ -0,0 +1,134 @@
+use symbol_table::GlobalSymbol;
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+use winit::keyboard::NamedKey;
+
+use crate::{API, EventContext};
+
+const BACKGROUND: Color = Color { r: 250.0, g: 250.0, b: 250.0, a: 255.0 };
+const BORDER: Color = Color { r: 180.0, g: 180.0, b: 180.0, a: 255.0 };
+const HIGHLIGHT: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const TEXT: Color = Color { r: 30.0, g: 30.0, b: 30.0, a: 255.0 };
+const HIGHLIGHT_TEXT: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+/// above dialogs so a menu opened inside one isn't hidden by its scrim
+const Z_INDEX: i16 = i16::MAX - 1;
+
+/// `tk` context-menu as parsed from a layout, every item of the `src` list supplies a `label` text and an `emit` event
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContextMenuConfig {
+    pub src: GlobalSymbol,
+    pub label: GlobalSymbol,
+    pub emit: GlobalSymbol,
+}
+
+impl Default for ContextMenuConfig {
+    fn default() -> Self {
+        Self {
+            src: GlobalSymbol::new(""),
+            label: GlobalSymbol::new("label"),
+            emit: GlobalSymbol::new("event"),
+        }
+    }
+}
+
+/// one row of an open menu, items without an event are drawn but can't be chosen
+pub struct ContextMenuItem<'frame, Event> {
+    pub label: &'frame str,
+    pub event: Option<Event>,
+}
+
+/// opens on a right click over the parent element, `items` only has to be filled while `context_menu_open` is true
+pub fn context_menu<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    items: Vec<ContextMenuItem<Event>>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    // still the parent here, the menu's own element isn't open yet
+    let open_here = api.ui_layout.hovered() && api.right_mouse_clicked;
+
+    api.ui_layout.open_element();
+
+    let position = match api.context_menu {
+        Some((_, position)) => position,
+        None => (0.0, 0.0),
+    };
+    config.floating()
+        .floating_attach_to_root()
+        .floating_offset(position.0, position.1)
+        .floating_z_index(Z_INDEX)
+        .parse();
+
+    let id = api.ui_layout.configure_element(config);
+    let menu_hovered = api.ui_layout.hovered();
+
+    if open_here {
+        api.context_menu = Some((id, (api.mouse_poistion.0 / api.dpi_scale, api.mouse_poistion.1 / api.dpi_scale)));
+        api.ui_layout.close_element();
+        return events
+    }
+
+    if !api.context_menu.is_some_and(|(menu, _)| menu == id) {
+        api.ui_layout.close_element();
+        return events
+    }
+
+    let outside_click = !menu_hovered && (api.left_mouse_pressed || api.right_mouse_pressed);
+    if outside_click || api.named_key_pressed(NamedKey::Escape) {
+        api.context_menu = None;
+        api.ui_layout.close_element();
+        return events
+    }
+
+    // the frame is its own element so a closed menu draws nothing
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new()
+        .x_grow()
+        .direction(true)
+        .padding_all(4)
+        .color(BACKGROUND)
+        .border_all(1)
+        .border_color(BORDER)
+        .radius_all(4.0)
+        .end()
+    );
+
+    let mut chosen = false;
+    for item in items {
+        api.ui_layout.open_element();
+        let hovered = item.event.is_some() && api.ui_layout.hovered();
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .x_grow()
+            .padding_all(6)
+            .color(match hovered {
+                true => HIGHLIGHT,
+                false => BACKGROUND,
+            })
+            .end()
+        );
+
+        if hovered && api.left_mouse_clicked
+        && let Some(event) = item.event {
+            events.push((event, None));
+            chosen = true;
+        }
+
+        let mut text_config = TextConfig::default();
+        text_config.font_size(14).color(match hovered {
+            true => HIGHLIGHT_TEXT,
+            false => TEXT,
+        }).parse();
+        api.ui_layout.add_text_element(item.label, &text_config, false);
+        api.ui_layout.close_element();
+    }
+
+    api.ui_layout.close_element();
+
+    if chosen {
+        api.context_menu = None;
+    }
+
+    api.ui_layout.close_element();
+
+    events
+}
@@ -0,0 +1,188 @@
+use std::fmt::Debug;
+
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::{API, DataSrc, EventContext};
+
+const BOX: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+const BORDER: Color = Color { r: 110.0, g: 110.0, b: 110.0, a: 255.0 };
+const BORDER_ACTIVE: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const DISABLED_TEXT: Color = Color { r: 120.0, g: 120.0, b: 120.0, a: 255.0 };
+/// pixels of drag it takes to cover an unbounded value's step
+const PIXELS_PER_STEP: f32 = 4.0;
+
+/// `tk` drag-value as parsed from a layout, `value` is read with `get_numeric` and written with `set_numeric`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DragValueConfig<Event: Clone+Debug+PartialEq+Default> {
+    pub value: DataSrc<f32>,
+    pub min: Option<DataSrc<f32>>,
+    pub max: Option<DataSrc<f32>>,
+    /// 0 slides freely
+    pub step: DataSrc<f32>,
+    pub decimals: DataSrc<u16>,
+    pub suffix: DataSrc<String>,
+    pub emit: Option<DataSrc<Event>>,
+    pub disabled: DataSrc<bool>,
+}
+
+impl<Event: Clone+Debug+PartialEq+Default> Default for DragValueConfig<Event> {
+    fn default() -> Self {
+        Self {
+            value: DataSrc::Static(0.0),
+            min: None,
+            max: None,
+            step: DataSrc::Static(0.0),
+            decimals: DataSrc::Static(2),
+            suffix: DataSrc::Static(String::new()),
+            emit: None,
+            disabled: DataSrc::Static(false),
+        }
+    }
+}
+
+/// a drag-value with its data resolved for this frame
+pub struct DragValue<'frame, Event> {
+    pub value: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub step: f32,
+    pub decimals: u16,
+    pub suffix: &'frame str,
+    pub event: Option<Event>,
+    pub disabled: bool,
+}
+
+impl<Event> DragValue<'_, Event> {
+    fn limit(&self, value: f32) -> f32 {
+        let value = match self.step > 0.0 {
+            true => (value / self.step).round() * self.step,
+            false => value,
+        };
+        let value = match self.min {
+            Some(min) => value.max(min),
+            None => value,
+        };
+        match self.max {
+            Some(max) => value.min(max),
+            None => value,
+        }
+    }
+
+    /// value change per pixel dragged, bounded values cross their range in 200 pixels
+    fn speed(&self) -> f32 {
+        match (self.min, self.max, self.step > 0.0) {
+            (Some(min), Some(max), _) => (max - min) / 200.0,
+            (_, _, true) => self.step / PIXELS_PER_STEP,
+            (_, _, false) => 10f32.powi(-(self.decimals as i32)),
+        }
+    }
+}
+
+/// drag sideways to change the value, click without dragging to type one in.
+/// returns the new value when the user changed it this frame, the change event carries it in `EventContext::value`
+pub fn drag_value<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    text_config: &mut TextConfig,
+    drag: DragValue<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> (Vec::<(Event, Option<EventContext>)>, Option<f32>)
+{
+    api.ui_layout.open_element();
+
+    let hovered = !drag.disabled && api.ui_layout.hovered();
+    let editing = api.numeric_edit.as_ref().map(|(edit_id, _)| *edit_id);
+    config.padding_all(4)
+        .color(BOX)
+        .border_all(1)
+        .border_color(match hovered {
+            true => BORDER_ACTIVE,
+            false => BORDER,
+        })
+        .radius_all(3.0)
+        .align_children_y_center()
+        .parse();
+    let id = api.ui_layout.configure_element(config);
+    let dragging = api.slider_drag.is_some_and(|(drag_id, _)| drag_id == id);
+
+    let mut new_value = None;
+
+    if hovered && api.left_mouse_pressed && editing != Some(id) {
+        api.focus = id;
+        api.slider_drag = Some((id, drag.value));
+    }
+
+    if dragging {
+        let moved = api.mouse_poistion.0 / api.dpi_scale - api.x_at_click;
+        let start_value = api.slider_drag.map(|(_, start)| start).unwrap_or(drag.value);
+        if !api.left_mouse_down {
+            api.slider_drag = None;
+            // a click that never moved opens the value for typing
+            if moved.abs() < 2.0 {
+                api.numeric_edit = Some((id, format!("{:.*}", drag.decimals as usize, drag.value)));
+            }
+        }
+        else {
+            new_value = Some(drag.limit(start_value + moved * drag.speed()));
+        }
+    }
+    else if let Some((edit_id, buffer)) = api.numeric_edit.as_mut()
+    && *edit_id == id {
+        let mut commit = false;
+        let mut cancel = false;
+        for key in &api.keys_pressed {
+            match key {
+                Key::Character(text) => buffer.extend(text.chars().filter(|character| character.is_ascii_digit() || matches!(character, '.' | '-' | 'e'))),
+                Key::Named(NamedKey::Backspace) => { buffer.pop(); }
+                Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Tab) => commit = true,
+                Key::Named(NamedKey::Escape) => cancel = true,
+                _ => {}
+            }
+        }
+        // clicking somewhere else commits too, like leaving a text field
+        if api.left_mouse_pressed && !hovered {
+            commit = true;
+        }
+        if commit {
+            new_value = buffer.trim().parse::<f32>().ok().map(|typed| drag.limit(typed));
+        }
+        if commit || cancel {
+            api.numeric_edit = None;
+        }
+    }
+    else if !drag.disabled && api.focus == id {
+        let step = match drag.step > 0.0 {
+            true => drag.step,
+            false => drag.speed() * PIXELS_PER_STEP,
+        };
+        if api.named_key_pressed(NamedKey::ArrowUp) || api.named_key_pressed(NamedKey::ArrowRight) {
+            new_value = Some(drag.limit(drag.value + step));
+        }
+        else if api.named_key_pressed(NamedKey::ArrowDown) || api.named_key_pressed(NamedKey::ArrowLeft) {
+            new_value = Some(drag.limit(drag.value - step));
+        }
+        else if api.named_key_pressed(NamedKey::Enter) {
+            api.numeric_edit = Some((id, format!("{:.*}", drag.decimals as usize, drag.value)));
+        }
+    }
+    let new_value = new_value.filter(|value| *value != drag.value);
+
+    if let Some(value) = new_value
+    && let Some(event) = drag.event.clone() {
+        events.push((event, Some(EventContext::from_value(value))));
+    }
+
+    let text = match api.numeric_edit.as_ref() {
+        Some((edit_id, buffer)) if *edit_id == id => format!("{}|", buffer),
+        _ => format!("{:.*}{}", drag.decimals as usize, new_value.unwrap_or(drag.value), drag.suffix),
+    };
+    if drag.disabled {
+        text_config.color(DISABLED_TEXT).parse();
+    }
+    api.ui_layout.add_text_element(&text, text_config, false);
+
+    api.ui_layout.close_element();
+
+    (events, new_value)
+}
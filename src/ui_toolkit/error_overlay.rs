@@ -0,0 +1,69 @@
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+
+use crate::API;
+
+const BACKGROUND: Color = Color { r: 150.0, g: 20.0, b: 20.0, a: 240.0 };
+const TEXT: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+const DISMISS_HOVER: Color = Color { r: 190.0, g: 50.0, b: 50.0, a: 255.0 };
+/// under tooltips, over everything else
+const Z_INDEX: i16 = i16::MAX - 1;
+
+/// the errors of layout files that failed to reload, across the top of the viewport showing `page`.
+/// files that don't belong to one page show theirs in every viewport
+pub fn error_overlay(api: &mut API, page: &str) {
+    let shown = |error_page: &Option<String>| error_page.as_ref().is_none_or(|error_page| error_page == page);
+    let lines = api.layout_errors.values()
+        .filter(|(error_page, _)| shown(error_page))
+        .flat_map(|(_, errors)| errors.iter().map(|error| error.to_string()))
+        .collect::<Vec<String>>();
+    if lines.is_empty() {
+        return
+    }
+
+    let mut text_config = TextConfig::default();
+    text_config.font_size(14).color(TEXT).parse();
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new()
+        .floating()
+        .floating_attach_to_root()
+        .floating_z_index(Z_INDEX)
+        .x_grow()
+        .padding_all(12)
+        .child_gap(6)
+        .direction(true)
+        .color(BACKGROUND)
+        .end()
+    );
+        api.ui_layout.open_element();
+        api.ui_layout.configure_element(&ElementConfiguration::new().x_grow().align_children_y_center().end());
+            api.ui_layout.open_element();
+            api.ui_layout.configure_element(&ElementConfiguration::new().x_grow().end());
+            api.ui_layout.add_text_element("the layout failed to reload", &text_config, false);
+            api.ui_layout.close_element();
+
+            api.ui_layout.open_element();
+            let hovered = api.ui_layout.hovered();
+            api.ui_layout.configure_element(&ElementConfiguration::new()
+                .padding_all(4)
+                .radius_all(3.0)
+                .color(match hovered {
+                    true => DISMISS_HOVER,
+                    false => BACKGROUND,
+                })
+                .end()
+            );
+            api.ui_layout.add_text_element("\u{2715}", &text_config, false);
+            api.ui_layout.close_element();
+        api.ui_layout.close_element();
+
+        for line in &lines {
+            api.ui_layout.add_text_element(line, &text_config, false);
+        }
+    api.ui_layout.close_element();
+
+    if hovered && api.left_mouse_clicked {
+        api.layout_errors.retain(|_, (error_page, _)| !shown(error_page));
+        api.request_redraw_all();
+    }
+}
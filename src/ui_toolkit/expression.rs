@@ -0,0 +1,423 @@
+use std::{collections::HashMap, sync::{LazyLock, RwLock}};
+
+use markdown::unist::Position;
+use symbol_table::GlobalSymbol;
+
+use crate::ui_toolkit::layout_error::report;
+
+/// expressions found while parsing layouts, by their text. bindings keep the text as their name
+static EXPRESSIONS: LazyLock<RwLock<HashMap<GlobalSymbol, Expression>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// what an expression or one of its names evaluates to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Number(f32),
+    Bool(bool),
+}
+
+impl Value {
+    /// true is 1
+    pub fn number(self) -> f32 {
+        match self {
+            Value::Number(number) => number,
+            Value::Bool(true) => 1.0,
+            Value::Bool(false) => 0.0,
+        }
+    }
+
+    /// anything but 0 is true
+    pub fn truthy(self) -> bool {
+        match self {
+            Value::Number(number) => number != 0.0,
+            Value::Bool(value) => value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+}
+
+impl Operator {
+    /// higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            Operator::Or => 1,
+            Operator::And => 2,
+            Operator::Equal | Operator::NotEqual => 3,
+            Operator::Greater | Operator::GreaterOrEqual | Operator::Less | Operator::LessOrEqual => 4,
+            Operator::Add | Operator::Subtract => 5,
+            Operator::Multiply | Operator::Divide | Operator::Remainder => 6,
+        }
+    }
+
+    fn apply(&self, left: Value, right: Value) -> Value {
+        match self {
+            Operator::Add => Value::Number(left.number() + right.number()),
+            Operator::Subtract => Value::Number(left.number() - right.number()),
+            Operator::Multiply => Value::Number(left.number() * right.number()),
+            Operator::Divide => Value::Number(left.number() / right.number()),
+            Operator::Remainder => Value::Number(left.number() % right.number()),
+            Operator::Greater => Value::Bool(left.number() > right.number()),
+            Operator::GreaterOrEqual => Value::Bool(left.number() >= right.number()),
+            Operator::Less => Value::Bool(left.number() < right.number()),
+            Operator::LessOrEqual => Value::Bool(left.number() <= right.number()),
+            Operator::Equal => Value::Bool(left.number() == right.number()),
+            Operator::NotEqual => Value::Bool(left.number() != right.number()),
+            Operator::And => Value::Bool(left.truthy() && right.truthy()),
+            Operator::Or => Value::Bool(left.truthy() || right.truthy()),
+        }
+    }
+}
+
+/// arithmetic, comparisons and `&&` `||` `!` over numbers, bools and app values.
+/// a `-` between two letters is part of a name, so subtraction needs spaces around it
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Number(f32),
+    Bool(bool),
+    Name(GlobalSymbol),
+    Not(Box<Expression>),
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f32),
+    Name(String),
+    Operator(Operator),
+    Not,
+    Open,
+    Close,
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let tokens = tokenize(text)?;
+        let mut position = 0;
+        let expression = parse_binary(&tokens, &mut position, 0)?;
+        match tokens.get(position) {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected {:?} in \"{}\"", token, text)),
+        }
+    }
+
+    /// `lookup` gives the value of each name
+    pub fn evaluate(&self, lookup: &impl Fn(&GlobalSymbol) -> Value) -> Value {
+        match self {
+            Expression::Number(number) => Value::Number(*number),
+            Expression::Bool(value) => Value::Bool(*value),
+            Expression::Name(name) => lookup(name),
+            Expression::Not(inner) => Value::Bool(!inner.evaluate(lookup).truthy()),
+            Expression::Negate(inner) => Value::Number(-inner.evaluate(lookup).number()),
+            Expression::Binary(left, Operator::And, right) => Value::Bool(left.evaluate(lookup).truthy() && right.evaluate(lookup).truthy()),
+            Expression::Binary(left, Operator::Or, right) => Value::Bool(left.evaluate(lookup).truthy() || right.evaluate(lookup).truthy()),
+            Expression::Binary(left, operator, right) => operator.apply(left.evaluate(lookup), right.evaluate(lookup)),
+        }
+    }
+}
+
+/// the name a binding keeps for `text`. anything more than a plain name is parsed and remembered as an expression,
+/// one that doesn't parse is an error in the layout at `position`. bindings made in code have no position
+pub fn binding_name(text: &str, position: Option<&Position>) -> GlobalSymbol {
+    let text = text.trim();
+    let name = GlobalSymbol::new(text);
+    // names with characters expressions don't use are left alone
+    if !tokenize(text).is_ok_and(|tokens| tokens.len() > 1) {
+        return name
+    }
+    match Expression::parse(text) {
+        Ok(expression) => {
+            EXPRESSIONS.write().unwrap().insert(name, expression);
+        }
+        Err(error) => match position {
+            Some(position) => report(Some(position), "expression", format!("failed to parse expression {}", error), None),
+            None => tracing::error!(target: "telera::parse", "failed to parse expression {}", error),
+        },
+    }
+    name
+}
+
+/// the expression a binding was named after, if it was one
+pub fn registered(name: &GlobalSymbol) -> Option<Expression> {
+    EXPRESSIONS.read().unwrap().get(name).cloned()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatPiece {
+    Literal(String),
+    /// a binding name, which can be an expression, and how many decimals a number gets
+    Value{name: GlobalSymbol, decimals: Option<usize>},
+}
+
+/// text with `{name}` and `{name:.2}` holes filled in from the app each frame. `{{` and `}}` are plain braces
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextFormat {
+    pieces: Vec<FormatPiece>,
+}
+
+impl TextFormat {
+    /// `None` when there's nothing to fill in, expressions in holes that don't parse are reported at `position`
+    pub fn parse(text: &str, position: Option<&Position>) -> Option<Self> {
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut characters = text.chars().peekable();
+
+        while let Some(character) = characters.next() {
+            match character {
+                '{' if characters.peek() == Some(&'{') => {
+                    characters.next();
+                    literal.push('{');
+                }
+                '}' if characters.peek() == Some(&'}') => {
+                    characters.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let hole = characters.by_ref().take_while(|character| *character != '}').collect::<String>();
+                    let (name, decimals) = match hole.rsplit_once(":.") {
+                        Some((name, decimals)) if decimals.trim().parse::<usize>().is_ok() => (name, decimals.trim().parse().ok()),
+                        _ => (hole.as_str(), None),
+                    };
+                    if !literal.is_empty() {
+                        pieces.push(FormatPiece::Literal(std::mem::take(&mut literal)));
+                    }
+                    pieces.push(FormatPiece::Value { name: binding_name(name, position), decimals });
+                }
+                character => literal.push(character),
+            }
+        }
+
+        if !pieces.iter().any(|piece| matches!(piece, FormatPiece::Value { .. })) {
+            return None
+        }
+        if !literal.is_empty() {
+            pieces.push(FormatPiece::Literal(literal));
+        }
+        Some(Self { pieces })
+    }
+
+    /// `value` writes the text for each hole
+    pub fn write<W: std::fmt::Write>(&self, text: &mut W, value: impl Fn(&mut W, &GlobalSymbol, Option<usize>)) {
+        for piece in &self.pieces {
+            match piece {
+                FormatPiece::Literal(literal) => {
+                    let _ = text.write_str(literal);
+                }
+                FormatPiece::Value { name, decimals } => value(text, name, *decimals),
+            }
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let characters = text.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while let Some(&character) = characters.get(index) {
+        let next = characters.get(index + 1).copied();
+        let (token, length) = match (character, next) {
+            (' ' | '\t' | '\n', _) => {
+                index += 1;
+                continue
+            }
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            ('&', Some('&')) => (Token::Operator(Operator::And), 2),
+            ('|', Some('|')) => (Token::Operator(Operator::Or), 2),
+            ('>', Some('=')) => (Token::Operator(Operator::GreaterOrEqual), 2),
+            ('<', Some('=')) => (Token::Operator(Operator::LessOrEqual), 2),
+            ('=', Some('=')) => (Token::Operator(Operator::Equal), 2),
+            ('!', Some('=')) => (Token::Operator(Operator::NotEqual), 2),
+            ('>', _) => (Token::Operator(Operator::Greater), 1),
+            ('<', _) => (Token::Operator(Operator::Less), 1),
+            ('!', _) => (Token::Not, 1),
+            ('+', _) => (Token::Operator(Operator::Add), 1),
+            ('-', _) => (Token::Operator(Operator::Subtract), 1),
+            ('*', _) => (Token::Operator(Operator::Multiply), 1),
+            ('/', _) => (Token::Operator(Operator::Divide), 1),
+            ('%', _) => (Token::Operator(Operator::Remainder), 1),
+            (digit, _) if digit.is_ascii_digit() || digit == '.' => {
+                let length = characters[index..].iter()
+                    .take_while(|character| character.is_ascii_digit() || **character == '.')
+                    .count();
+                let number = characters[index..index + length].iter().collect::<String>();
+                let number = number.parse::<f32>().map_err(|_| format!("bad number {} in \"{}\"", number, text))?;
+                (Token::Number(number), length)
+            }
+            (letter, _) if letter.is_alphabetic() || letter == '_' => {
+                let mut length = 1;
+                while let Some(&character) = characters.get(index + length) {
+                    let joins = characters.get(index + length + 1).is_some_and(|after| after.is_alphanumeric() || *after == '_');
+                    match character {
+                        character if character.is_alphanumeric() || character == '_' || character == '.' => length += 1,
+                        '-' if joins => length += 1,
+                        _ => break,
+                    }
+                }
+                let name = characters[index..index + length].iter().collect::<String>();
+                (Token::Name(name), length)
+            }
+            (other, _) => return Err(format!("unexpected '{}' in \"{}\"", other, text)),
+        };
+        tokens.push(token);
+        index += length;
+    }
+
+    Ok(tokens)
+}
+
+/// precedence climbing, operators at `minimum` or tighter are taken
+fn parse_binary(tokens: &[Token], position: &mut usize, minimum: u8) -> Result<Expression, String> {
+    let mut left = parse_unary(tokens, position)?;
+    while let Some(Token::Operator(operator)) = tokens.get(*position)
+    && operator.precedence() >= minimum {
+        let operator = *operator;
+        *position += 1;
+        let right = parse_binary(tokens, position, operator.precedence() + 1)?;
+        left = Expression::Binary(Box::new(left), operator, Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_unary(tokens: &[Token], position: &mut usize) -> Result<Expression, String> {
+    let token = tokens.get(*position).ok_or("expression ends early")?;
+    *position += 1;
+    match token {
+        Token::Number(number) => Ok(Expression::Number(*number)),
+        Token::Name(name) => match name.as_str() {
+            "true" => Ok(Expression::Bool(true)),
+            "false" => Ok(Expression::Bool(false)),
+            _ => Ok(Expression::Name(GlobalSymbol::new(name))),
+        },
+        Token::Not => Ok(Expression::Not(Box::new(parse_unary(tokens, position)?))),
+        Token::Operator(Operator::Subtract) => Ok(Expression::Negate(Box::new(parse_unary(tokens, position)?))),
+        Token::Open => {
+            let inner = parse_binary(tokens, position, 0)?;
+            match tokens.get(*position) {
+                Some(Token::Close) => {
+                    *position += 1;
+                    Ok(inner)
+                }
+                _ => Err("missing )".to_string()),
+            }
+        }
+        other => Err(format!("unexpected {:?}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_toolkit::layout_error::take_errors;
+
+    /// `a` is 2 and `b` is 3, any other name is 0
+    fn evaluate(text: &str) -> Value {
+        let a = GlobalSymbol::new("a");
+        let b = GlobalSymbol::new("b");
+        Expression::parse(text).unwrap().evaluate(&|name| match name {
+            name if *name == a => Value::Number(2.0),
+            name if *name == b => Value::Number(3.0),
+            _ => Value::Number(0.0),
+        })
+    }
+
+    fn name(text: &str) -> Token {
+        Token::Name(text.to_string())
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(evaluate("1 + 2 * 3"), Value::Number(7.0));
+        assert_eq!(evaluate("10 - 4 - 3"), Value::Number(3.0));
+        assert_eq!(evaluate("7 % 4 * 2"), Value::Number(6.0));
+        assert_eq!(evaluate("a + 1 > b"), Value::Bool(false));
+        assert_eq!(evaluate("1 < 2 == 2 < 3"), Value::Bool(true));
+        assert_eq!(evaluate("false && true || true"), Value::Bool(true));
+        assert_eq!(evaluate("true || true && false"), Value::Bool(true));
+        assert_eq!(
+            Expression::parse("a + b * 2").unwrap(),
+            Expression::Binary(
+                Box::new(Expression::Name(GlobalSymbol::new("a"))),
+                Operator::Add,
+                Box::new(Expression::Binary(
+                    Box::new(Expression::Name(GlobalSymbol::new("b"))),
+                    Operator::Multiply,
+                    Box::new(Expression::Number(2.0)),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn unary() {
+        assert_eq!(evaluate("!true"), Value::Bool(false));
+        assert_eq!(evaluate("!0"), Value::Bool(true));
+        assert_eq!(evaluate("!!a"), Value::Bool(true));
+        assert_eq!(evaluate("-a"), Value::Number(-2.0));
+        assert_eq!(evaluate("-a * b"), Value::Number(-6.0));
+        assert_eq!(evaluate("- -4"), Value::Number(4.0));
+        assert_eq!(evaluate("b - -1"), Value::Number(4.0));
+        assert_eq!(evaluate("!a > b"), Value::Bool(false));
+    }
+
+    #[test]
+    fn parentheses() {
+        assert_eq!(evaluate("(1 + 2) * 3"), Value::Number(9.0));
+        assert_eq!(evaluate("((a))"), Value::Number(2.0));
+        assert_eq!(evaluate("-(a + b)"), Value::Number(-5.0));
+        assert_eq!(evaluate("!(a > b) && (b > a)"), Value::Bool(true));
+    }
+
+    #[test]
+    fn hyphen_between_letters_is_part_of_the_name() {
+        assert_eq!(tokenize("a-b"), Ok(vec![name("a-b")]));
+        assert_eq!(tokenize("width-10"), Ok(vec![name("width-10")]));
+        assert_eq!(tokenize("a - b"), Ok(vec![name("a"), Token::Operator(Operator::Subtract), name("b")]));
+        assert_eq!(tokenize("width - 10"), Ok(vec![name("width"), Token::Operator(Operator::Subtract), Token::Number(10.0)]));
+        assert_eq!(tokenize("a -b"), Ok(vec![name("a"), Token::Operator(Operator::Subtract), name("b")]));
+        assert_eq!(tokenize("a- b"), Ok(vec![name("a"), Token::Operator(Operator::Subtract), name("b")]));
+        assert_eq!(tokenize("item.size-x"), Ok(vec![name("item.size-x")]));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Expression::parse("1 +").is_err());
+        assert!(Expression::parse("(1 + 2").is_err());
+        assert!(Expression::parse("1 2").is_err());
+        assert!(Expression::parse("a )").is_err());
+        assert!(Expression::parse("* 2").is_err());
+        assert!(Expression::parse("").is_err());
+        assert!(Expression::parse("1.2.3").is_err());
+        assert!(Expression::parse("a $ b").is_err());
+    }
+
+    #[test]
+    fn bindings() {
+        take_errors();
+        assert!(registered(&binding_name("plain_name", None)).is_none());
+        assert!(registered(&binding_name("a-b", None)).is_none());
+        assert!(registered(&binding_name(" count + 1 ", None)).is_some());
+
+        let position = Position::new(3, 5, 20, 3, 12, 27);
+        binding_name("count +", Some(&position));
+        let errors = take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (3, 5));
+    }
+}
@@ -0,0 +1,93 @@
+use std::{cell::RefCell, fmt::Display, path::{Path, PathBuf}};
+
+use markdown::unist::Position;
+
+thread_local! {
+    /// errors found by the layout file being parsed on this thread
+    static ERRORS: RefCell<Vec<LayoutError>> = const { RefCell::new(Vec::new()) };
+}
+
+/// something wrong in a layout file and where it is
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutError {
+    /// `None` for layouts parsed from a string
+    pub file: Option<PathBuf>,
+    /// bytes from the start of the file
+    pub offset: usize,
+    /// from 1, 0 when the error isn't about one place
+    pub line: usize,
+    pub column: usize,
+    /// the element or config the error is about
+    pub tag: String,
+    pub message: String,
+    pub hint: Option<String>,
+    /// the errors of an imported file that failed to load
+    pub nested: Vec<LayoutError>,
+}
+
+/// what `validate_layouts` finds, the same errors loading the layouts reports
+pub type LayoutDiagnostic = LayoutError;
+
+impl LayoutError {
+    pub fn new(position: Option<&Position>, tag: &str, message: String, hint: Option<String>) -> Self {
+        let (offset, line, column) = match position {
+            Some(position) => (position.start.offset, position.start.line, position.start.column),
+            None => (0, 0, 0),
+        };
+        Self { file: None, offset, line, column, tag: tag.to_string(), message, hint, nested: Vec::new() }
+    }
+
+    pub fn in_file(mut self, file: &Path) -> Self {
+        self.file = Some(file.to_path_buf());
+        self
+    }
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}: {}", file.display(), self.line, self.column, self.message)?,
+            None => write!(f, "{}:{}: {}", self.line, self.column, self.message)?,
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, ", {}", hint)?;
+        }
+        for error in &self.nested {
+            write!(f, "\n    {}", error.to_string().replace('\n', "\n    "))?;
+        }
+        Ok(())
+    }
+}
+
+/// notes an error for the layout being parsed, the parse fails once it's done
+pub(crate) fn report(position: Option<&Position>, tag: &str, message: String, hint: Option<String>) {
+    ERRORS.with_borrow_mut(|errors| errors.push(LayoutError::new(position, tag, message, hint)));
+}
+
+/// the errors reported since the last call
+pub(crate) fn take_errors() -> Vec<LayoutError> {
+    ERRORS.with_borrow_mut(std::mem::take)
+}
+
+/// the known name `name` is most likely a typo of
+pub(crate) fn did_you_mean(name: &str, known: &[&str]) -> Option<String> {
+    known.iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| format!("did you mean `{}`?", candidate))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, a_character) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_character) in b.iter().enumerate() {
+            let substitution = previous[j] + (a_character != *b_character) as usize;
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
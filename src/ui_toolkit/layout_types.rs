@@ -3,7 +3,7 @@ use strum_macros::Display;
 use symbol_table::GlobalSymbol;
 use telera_layout::Color;
 
-use crate::{EventHandler, TreeViewItem, UIImageDescriptor, CustomElement};
+use crate::{ui_toolkit::expression::{binding_name, TextFormat}, EventHandler, TreeViewItem, TreeSelectionMode, UIImageDescriptor, CustomElement, ui_toolkit::{button::ButtonConfig, checkbox::ToggleConfig, slider::SliderConfig, context_menu::ContextMenuConfig, menu_bar::MenuBarConfig, progress::{ProgressConfig, SpinnerConfig}, drag_value::DragValueConfig, markdown_view::MarkdownConfig, textbox::TextEditorConfig}};
 
 #[derive(Clone, Debug, Display, PartialEq)]
 pub enum Layout<Event>
@@ -25,6 +25,8 @@ where
 
     TextElementOpened,
     TextElementClosed(DataSrc<String>),
+    /// text with `{name}` holes, closes a text element like `TextElementClosed`
+    TextElementFormatted(TextFormat),
 
     ConfigOpened,
     ConfigClosed,
@@ -32,18 +34,35 @@ where
     TextConfigOpened,
     TextConfigClosed,
     
+    /// the first case whose `is` matches the value of `src` is laid out, `DefaultOpened` when none do
+    MatchOpened{src: GlobalSymbol},
+    MatchClosed,
+    CaseOpened{is: String},
+    DefaultOpened,
+    CaseClosed,
+
     ListOpened,
-    ListClosed(GlobalSymbol),
+    /// with a `key` each item's first element is named after that field of the item,
+    /// so the state of its elements follows the item when items move
+    ListClosed{src: GlobalSymbol, key: Option<GlobalSymbol>},
+    /// only the items in view are laid out, every item has to be `item_height` tall
+    VirtualListClosed{src: GlobalSymbol, item_height: f32, key: Option<GlobalSymbol>},
+    /// opened with `ListOpened`, the items are laid out `times` times with the count in the local `index`
+    RepeatClosed{times: DataSrc<u16>, index: GlobalSymbol},
 
     UseOpened,
     UseClosed(GlobalSymbol),
 
     TreeViewOpened,
-    TreeViewClosed(GlobalSymbol),
+    TreeViewClosed{src: GlobalSymbol, selection: TreeSelectionMode},
 
     TextBoxOpened,
     TextBoxClosed(DataSrc<String>),
 
+    /// the configs in between size and place the widget, the widget sets its own state visuals
+    ToolkitOpened,
+    ToolkitClosed(Widget<Event>),
+
     CircleOpened{id: Option<DataSrc<String>>},
     CircleClosed,
 
@@ -51,6 +70,7 @@ where
     LineClosed,
 
     // if not
+    /// the condition is a bool or an expression like `count > 0 && !loading`
     IfOpened{condition: GlobalSymbol},
     IfNotOpened{condition: GlobalSymbol},
     IfClosed,
@@ -106,6 +126,28 @@ where
     RightClickedClosed,
 }
 
+/// prebuilt widgets from `tk` layout elements
+#[derive(Clone, Debug, Display, PartialEq)]
+pub enum Widget<Event>
+where
+    Event: Clone+Debug+PartialEq+Default
+{
+    Button(ButtonConfig<Event>),
+    Checkbox(ToggleConfig<Event>),
+    /// only one radio with the same `group` is on at a time
+    Radio(ToggleConfig<Event>),
+    Slider(SliderConfig<Event>),
+    DragValue(DragValueConfig<Event>),
+    /// opens at the pointer on a right click over the element it's in
+    ContextMenu(ContextMenuConfig),
+    MenuBar(MenuBarConfig<Event>),
+    Progress(ProgressConfig),
+    Spinner(SpinnerConfig),
+    /// lays out user provided markdown text
+    Markdown(MarkdownConfig<Event>),
+    TextEditor(TextEditorConfig<Event>),
+}
+
 #[derive(Clone, Debug, Display, PartialEq)]
 pub enum Config{
     Id(DataSrc<String>),
@@ -169,10 +211,17 @@ pub enum Config{
 
     Image{name: GlobalSymbol},
 
+    DepthTest(DataSrc<bool>),
+    PixelSnap(DataSrc<bool>),
+
     Floating,
     FloatingOffset{x:DataSrc<f32>,y:DataSrc<f32>},
+    /// offset that tracks the center of a model on screen, by model name
+    FloatingAnchor(DataSrc<String>),
     FloatingDimensions{width:DataSrc<f32>,height:DataSrc<f32>},
     FloatingZIndex{z:DataSrc<i16>},
+    /// draw order of a regular element among its neighbours, `layer` holds the resolved value for the renderer
+    ZIndex{z:DataSrc<i16>, layer:CustomElement},
     FloatingAttatchToParentAtTopLeft,
     FloatingAttatchToParentAtCenterLeft,
     FloatingAttatchToParentAtBottomLeft,
@@ -197,9 +246,29 @@ pub enum Config{
 
     CustomElement(CustomElement),
 
+    /// 0 to 1, fades the element and everything in it
+    Opacity(DataSrc<f32>),
+    /// a hidden element still takes up its space
+    Visible(DataSrc<bool>),
+    /// a collapsed element is left out of the layout, parsing turns it into an `if-not` around the element
+    Collapsed(DataSrc<bool>),
+
+    Tooltip(DataSrc<String>),
+    /// milliseconds of hovering before the tooltip shows
+    TooltipDelay(DataSrc<f32>),
+
+    /// `EventContext::code` of the event the hovered or clicked block around it sends
+    EventCode(DataSrc<f32>),
+    /// `EventContext::text` of the event the hovered or clicked block around it sends
+    EventText(DataSrc<String>),
+
     Use{name: GlobalSymbol},
 
     FontId(DataSrc<u16>),
+    /// a font registered with `add_font` or `add_font_family`
+    Font(DataSrc<String>),
+    /// right aligned text whose lines read right to left even when they start with a left to right word
+    Rtl(DataSrc<bool>),
     AlignRight,
     AlignLeft,
     AlignCenter,
@@ -240,17 +309,91 @@ impl<T:Default> Default for DataSrc<T> {
     }
 }
 
+impl<T:Default> From<T> for DataSrc<T> {
+    fn from(value: T) -> Self {
+        DataSrc::Static(value)
+    }
+}
+
+impl From<&str> for DataSrc<String> {
+    fn from(value: &str) -> Self {
+        DataSrc::Static(value.to_string())
+    }
+}
+
+impl<T:Default> DataSrc<T> {
+    /// a value read from the app every frame, `name` can be an expression like in layout files
+    pub fn binding(name: &str) -> Self {
+        DataSrc::Dynamic(binding_name(name, None))
+    }
+}
+
+/// the lists an item sits in, outermost first, with the item's index in each.
+/// an item of `orders.items` in the fourth order gives `[("orders", 3), ("items", 2)]`
+pub fn list_indices(list_data: &Option<(GlobalSymbol, usize)>) -> Vec<(&'static str, usize)> {
+    let Some((path, index)) = list_data else {
+        return Vec::new()
+    };
+    let mut indices = Vec::new();
+    for part in path.as_str().split(']') {
+        let part = part.strip_prefix('.').unwrap_or(part);
+        match part.split_once('[') {
+            Some((name, outer)) => indices.push((name, outer.parse().unwrap_or(0))),
+            None => indices.push((part, *index)),
+        }
+    }
+    indices
+}
+
+/// fieldless enums bound through `#[variant]` fields of the `ParserDataAccess` derive, `#[derive(LayoutEnum)]` implements it
+pub trait LayoutEnum {
+    /// for text and `match` cases
+    fn variant_name(&self) -> &'static String;
+    /// the variant's position in the enum, from 0
+    fn variant_index(&self) -> usize;
+}
+
+/// the index in the list `field` of the item `list_data` is in, and the `list_data` to ask that item with.
+/// for `#[list]` fields of the `ParserDataAccess` derive
+pub fn list_item_index(field: &str, list_data: &Option<(GlobalSymbol, usize)>) -> Option<(usize, Option<(GlobalSymbol, usize)>)> {
+    let (path, index) = list_data.as_ref()?;
+    if path.as_str() == field {
+        return Some((*index, None))
+    }
+    // an item of a list inside one of `field`'s items, like `orders[3].items`
+    let (outer, inner) = path.as_str().strip_prefix(field)?.strip_prefix('[')?.split_once(']')?;
+    let inner = inner.strip_prefix('.')?;
+    Some((outer.parse().ok()?, Some((GlobalSymbol::new(inner), *index))))
+}
+
 #[allow(unused_variables)]
 pub trait ParserDataAccess<Event: FromStr+Clone+PartialEq+Debug+EventHandler>{
+    /// a number that changes whenever anything layouts read changes. with it, viewports are only
+    /// laid out again after input or a new version, without it every event redraws
+    fn data_version(&self) -> Option<u64> {
+        None
+    }
+    /// pages built in code with `Page`, taken before every redraw and added or swapped in by name
+    fn take_pages(&mut self) -> Vec<(String, Vec<Layout<Event>>)> {
+        Vec::new()
+    }
+    /// lists nested in another list's items are asked with that item's `list_data`,
+    /// their own items get a `list_data` naming the whole path, see `list_indices`
     fn get_list_length(&self, name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>) -> Option<usize> {
         None
     }
     fn get_bool(&self, name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>) -> Option<bool>{
         None
     }
+    /// written by widgets bound to `name`, like checkboxes, once the layout pass is done
+    fn set_bool(&mut self, name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>, value: bool) {}
     fn get_numeric(&self, name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>) -> Option<f32>{
         None
     }
+    /// written by widgets bound to `name`, like sliders, once the layout pass is done
+    fn set_numeric(&mut self, name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>, value: f32) {}
+    /// written by widgets bound to `name`, like text editors, once the layout pass is done
+    fn set_text(&mut self, name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>, value: String) {}
     fn get_text<'render_pass, 'application>(&'application self, name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>) -> Option<&'render_pass String> where 'application: 'render_pass{
         None
     }
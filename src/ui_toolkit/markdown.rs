@@ -1,10 +1,40 @@
-use std::{collections::HashMap, fmt::Debug, str::FromStr};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, fmt::Debug, path::{Path, PathBuf}, str::FromStr};
 
-use markdown::mdast::{List, Node, Paragraph};
+use markdown::{mdast::{List, Node, Paragraph}, unist::Position};
 use symbol_table::GlobalSymbol;
-use crate::{Config, CustomElement, DataSrc, Declaration, Element, Layout, ui_toolkit::ui_shapes::LineConfig};
+use crate::{LayoutError, LayoutDiagnostic, Config, CustomElement, DataSrc, Declaration, Element, Layout, Widget, ui_toolkit::{button::ButtonConfig, checkbox::ToggleConfig, slider::SliderConfig, context_menu::ContextMenuConfig, menu_bar::{parse_mnemonic, MenuBarConfig, MenuEntry}, progress::{ProgressConfig, SpinnerConfig}, drag_value::DragValueConfig, markdown_view::MarkdownConfig, textbox::TextEditorConfig, treeview::TreeSelectionMode, ui_shapes::LineConfig, expression::{binding_name, TextFormat}, layout_error::{report, take_errors, did_you_mean}}};
 use telera_layout::Color;
 
+const DEFAULT_ITEM_HEIGHT: f32 = 24.0;
+/// for suggestions when a layout names an element that doesn't exist
+const ELEMENTS: &[&str] = &[
+    "declarations", "config", "element", "circle", "line", "custom", "tk", "grow", "text", "use", "list", "virtual-list",
+    "repeat", "if", "if-not", "match", "case", "default", "treeview", "textbox",
+];
+/// for suggestions when a layout names a config that doesn't exist
+const CONFIGS: &[&str] = &[
+    "grow", "width-grow", "height-grow", "width-fit", "height-fit", "width-fixed", "height-fixed", "width-percent",
+    "height-percent", "padding-all", "padding-top", "padding-right", "padding-bottom", "padding-left", "child-gap",
+    "vertical", "align-children-x", "align-children-y", "color", "width", "type", "radius-all", "radius-top-left",
+    "radius-top-right", "radius-bottom-left", "radius-bottom-right", "border-color", "border-all", "border-top",
+    "border-left", "border-bottom", "border-right", "border-in-between", "scroll", "image", "depth-test", "pixel-snap",
+    "opacity", "visible", "collapsed", "tooltip", "tooltip-delay", "floating", "use", "hovered", "unhovered", "hover",
+    "focused", "unfocused", "focus", "left-pressed", "left-down", "left-released", "left-clicked", "left-dbl-clicked",
+    "left-tpl-clicked", "right-pressed", "right-down", "right-released", "right-clicked", "pointer", "font-id", "font",
+    "rtl", "font-size", "align", "line-height", "letter-spacing", "font-color", "offset", "anchor", "attatch-parent",
+    "attach-self", "attach-to", "attach-root", "z-index", "event-code", "event-text"
+];
+/// the types a reusable's parameters can have
+const PARAMETER_TYPES: &[&str] = &["bool", "numeric", "text", "color", "event", "image"];
+
+/// layout files' markdown parsed ahead of time, by canonical path
+pub(crate) type MarkdownTrees = HashMap<PathBuf, Result<Node, LayoutError>>;
+
+thread_local! {
+    /// where the `use` elements parsed on this thread are, in the order their commands were made
+    static USES: RefCell<Vec<Option<Position>>> = const { RefCell::new(Vec::new()) };
+}
+
 #[derive(Debug)]
 enum ParsingMode {
     None,
@@ -13,18 +43,364 @@ enum ParsingMode {
     ReusableConfig,
 }
 
-pub fn process_layout<Event: Clone+Debug+Default+PartialEq+FromStr>(file: String) -> Result<(String, Vec<Layout<Event>>, HashMap::<String, Vec<Layout<Event>>>), String> 
+/// a reusable's declared parameter, a use that leaves it out gets `default`
+#[derive(Clone, Debug)]
+struct Parameter<Event: Clone+Debug+Default+PartialEq> {
+    name: GlobalSymbol,
+    kind: String,
+    default: Option<DataSrc<Declaration<Event>>>,
+}
+
+/// one layout file before its imports are read
+struct ParsedLayout<Event: Clone+Debug+Default+PartialEq> {
+    page_name: String,
+    body: Vec<Layout<Event>>,
+    reusables: HashMap<String, Vec<Layout<Event>>>,
+    /// files, the namespaces their reusables go under and where they're imported
+    imports: Vec<(String, String, Option<Position>)>,
+    /// every file read for the imports, directly or through another import
+    imported_files: Vec<PathBuf>,
+    /// of the reusables that declare them
+    parameters: HashMap<String, Vec<Parameter<Event>>>,
+    /// where the body's `use` elements are, in order
+    body_uses: Vec<Option<Position>>,
+    /// where each reusable's `use` elements are, in order
+    reusable_uses: HashMap<String, Vec<Option<Position>>>,
+}
+
+pub fn process_layout<Event: Clone+Debug+Default+PartialEq+FromStr>(file: String) -> Result<(String, Vec<Layout<Event>>, HashMap::<String, Vec<Layout<Event>>>), Vec<LayoutError>> 
+where <Event as FromStr>::Err: Debug+Default
+{
+    let tree = parse_markdown(&file).map_err(|error| vec![error])?;
+    let mut layout = parse_layout(&tree)?;
+    let errors = check_layout_uses(&mut layout);
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+    Ok((layout.page_name, layout.body, layout.reusables))
+}
+
+/// like `process_layout`, with the reusables of every file it imports added as `namespace.name`.
+/// `` `import` widgets.md `` names them after the file, `` `import` widgets.md as w `` after `w`
+pub fn process_layout_file<Event: Clone+Debug+Default+PartialEq+FromStr>(path: &Path) -> Result<(String, Vec<Layout<Event>>, HashMap::<String, Vec<Layout<Event>>>), Vec<LayoutError>> 
+where <Event as FromStr>::Err: Debug+Default
+{
+    let layout = load_layout_file::<Event>(path, &mut Vec::new(), &MarkdownTrees::new())?;
+    Ok((layout.page_name, layout.body, layout.reusables))
+}
+
+/// `process_layout_file` and every file it read for its imports, so it can be reloaded when one of them changes.
+/// files in `trees` aren't read again
+pub(crate) fn process_layout_file_with_imports<Event: Clone+Debug+Default+PartialEq+FromStr>(path: &Path, trees: &MarkdownTrees) -> Result<(String, Vec<Layout<Event>>, HashMap::<String, Vec<Layout<Event>>>, Vec<PathBuf>), Vec<LayoutError>> 
+where <Event as FromStr>::Err: Debug+Default
+{
+    let layout = load_layout_file::<Event>(path, &mut Vec::new(), trees)?;
+    Ok((layout.page_name, layout.body, layout.reusables, layout.imported_files))
+}
+
+/// parses every layout file in `dir` without opening a window, to check layouts in ci or before a commit.
+/// finds what loading them would report, and uses of reusables no file defines
+pub fn validate_layouts<Event: Clone+Debug+Default+PartialEq+FromStr>(dir: impl AsRef<Path>) -> Vec<LayoutDiagnostic>
+where <Event as FromStr>::Err: Debug+Default
+{
+    let dir = dir.as_ref();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return vec![LayoutError::new(None, "", format!("failed to read the layout directory: {}", e), None).in_file(dir)],
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut layouts = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue
+        }
+        match process_layout_file::<Event>(&path) {
+            Ok((_, body, reusables)) => layouts.push((path, body, reusables)),
+            Err(errors) => diagnostics.extend(errors),
+        }
+    }
+
+    let defined = layouts.iter()
+        .flat_map(|(_, _, reusables)| reusables.keys().map(|name| name.as_str()))
+        .collect::<HashSet<&str>>();
+    let known = defined.iter().copied().collect::<Vec<&str>>();
+    for (path, body, reusables) in &layouts {
+        for command in std::iter::once(body).chain(reusables.values()).flatten() {
+            if let Layout::Element(Element::UseClosed(name)) | Layout::Config(Config::Use { name }) = command
+            && !defined.contains(name.as_str()) {
+                diagnostics.push(LayoutError::new(
+                    None,
+                    name.as_str(),
+                    format!("no layout defines the reusable `{}`", name),
+                    did_you_mean(name.as_str(), &known)
+                ).in_file(path));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// `importing` holds the files further up the chain of imports, a file importing one of them fails to load.
+/// files in `trees` aren't read again
+fn load_layout_file<Event: Clone+Debug+Default+PartialEq+FromStr>(path: &Path, importing: &mut Vec<PathBuf>, trees: &MarkdownTrees) -> Result<ParsedLayout<Event>, Vec<LayoutError>> 
+where <Event as FromStr>::Err: Debug+Default
+{
+    let canonical = path.canonicalize().unwrap_or(path.to_path_buf());
+    let read;
+    let tree = match trees.get(&canonical) {
+        Some(Ok(tree)) => tree,
+        Some(Err(error)) => return Err(vec![error.clone().in_file(path)]),
+        None => {
+            let file = std::fs::read_to_string(path)
+                .map_err(|e| vec![LayoutError::new(None, "", format!("failed to read the file: {}", e), None).in_file(path)])?;
+            read = parse_markdown(&file).map_err(|error| vec![error.in_file(path)])?;
+            &read
+        }
+    };
+    let mut layout = parse_layout(tree)
+        .map_err(|errors| errors.into_iter().map(|error| error.in_file(path)).collect::<Vec<LayoutError>>())?;
+
+    let mut errors = Vec::new();
+    let mut imported_reusables = HashMap::<String, Vec<Layout<Event>>>::new();
+    importing.push(canonical);
+    for (import, namespace, position) in std::mem::take(&mut layout.imports) {
+        let import_path = path.parent().unwrap_or(Path::new("")).join(&import);
+        if importing.contains(&import_path.canonicalize().unwrap_or(import_path.clone())) {
+            errors.push(LayoutError::new(
+                position.as_ref(),
+                "import",
+                format!("`{}` imports this file back", import),
+                Some("move the reusables both files need into a third file".to_string())
+            ));
+            continue
+        }
+        layout.imported_files.push(import_path.canonicalize().unwrap_or(import_path.clone()));
+        match load_layout_file::<Event>(&import_path, importing, trees) {
+            Ok(imported) => {
+                layout.imported_files.extend(imported.imported_files);
+                let names = imported.reusables.keys().cloned().collect::<Vec<String>>();
+                for (name, mut reusable) in imported.reusables {
+                    namespace_uses(&mut reusable, &names, &namespace);
+                    imported_reusables.insert(format!("{}.{}", namespace, name), reusable);
+                }
+                for (name, parameters) in imported.parameters {
+                    layout.parameters.insert(format!("{}.{}", namespace, name), parameters);
+                }
+            }
+            Err(nested) => errors.push(LayoutError {
+                nested,
+                ..LayoutError::new(position.as_ref(), "import", format!("failed to import `{}`", import), None)
+            }),
+        }
+    }
+    importing.pop();
+
+    // imported reusables were checked when their own file was loaded
+    errors.extend(check_layout_uses(&mut layout));
+    if !errors.is_empty() {
+        return Err(errors.into_iter().map(|error| error.in_file(path)).collect())
+    }
+    layout.reusables.extend(imported_reusables);
+
+    Ok(layout)
+}
+
+/// points the uses of an imported file's own reusables at their namespaced names
+fn namespace_uses<Event: Clone+Debug+Default+PartialEq>(commands: &mut [Layout<Event>], names: &[String], namespace: &str) {
+    for command in commands {
+        let name = match command {
+            Layout::Element(Element::UseClosed(name)) | Layout::Config(Config::Use { name }) => name,
+            _ => continue,
+        };
+        if names.iter().any(|reusable| reusable == name.as_str()) {
+            *name = GlobalSymbol::new(format!("{}.{}", namespace, name));
+        }
+    }
+}
+
+/// `Card(title: text = Untitled, accent: color)` is the reusable `Card` with two parameters, the title defaults to `Untitled`.
+/// `position` is the heading's, for reporting parameters that can't be read
+fn reusable_signature<Event: Clone+Debug+Default+PartialEq+FromStr>(heading: &str, position: Option<&Position>) -> (String, Option<Vec<Parameter<Event>>>)
+where <Event as FromStr>::Err: Debug+Default
+{
+    let Some((name, parameters)) = heading.split_once('(') else {
+        return (heading.trim().to_string(), None)
+    };
+    let name = name.trim().to_string();
+    let parameters = parameters.trim_end().trim_end_matches(')')
+        .split(',')
+        .filter(|parameter| !parameter.trim().is_empty())
+        .filter_map(|parameter| {
+            let (parameter, default) = match parameter.split_once('=') {
+                Some((parameter, default)) => (parameter, Some(default.trim())),
+                None => (parameter, None),
+            };
+            let Some((parameter_name, kind)) = parameter.split_once(':') else {
+                report(
+                    position,
+                    &name,
+                    format!("parameter `{}` of `{}` has no type", parameter.trim(), name),
+                    Some(format!("declare it as `{}: text`", parameter.trim()))
+                );
+                return None
+            };
+            let parameter_name = parameter_name.trim();
+            let kind = kind.trim().to_string();
+            if !PARAMETER_TYPES.contains(&kind.as_str()) {
+                report(
+                    position,
+                    &name,
+                    format!("`{}` isn't a parameter type, for `{}` of `{}`", kind, parameter_name, name),
+                    did_you_mean(&kind, PARAMETER_TYPES)
+                );
+                return None
+            }
+            let default = match default {
+                Some(default) => match static_declaration::<Event>(&kind, default) {
+                    Some(default) => Some(default),
+                    None => {
+                        report(
+                            position,
+                            &name,
+                            format!("`{}` isn't a {} for the default of `{}` in `{}`", default, kind, parameter_name, name),
+                            None
+                        );
+                        None
+                    }
+                },
+                None => None,
+            };
+            Some(Parameter { name: GlobalSymbol::new(parameter_name), kind, default })
+        })
+        .collect();
+    (name, Some(parameters))
+}
+
+/// the kind a `set-` declaration of `value` would have
+fn declaration_kind<Event: Clone+Debug+Default+PartialEq>(value: &DataSrc<Declaration<Event>>) -> Option<&'static str> {
+    match value {
+        DataSrc::Dynamic(_) => None,
+        DataSrc::Static(Declaration::Bool(_)) => Some("bool"),
+        DataSrc::Static(Declaration::Numeric(_)) => Some("numeric"),
+        DataSrc::Static(Declaration::Text(_)) => Some("text"),
+        DataSrc::Static(Declaration::Color(_)) => Some("color"),
+        DataSrc::Static(Declaration::Event(_)) => Some("event"),
+        DataSrc::Static(Declaration::Image(_)) => Some("image"),
+    }
+}
+
+/// checks the uses in the body and every reusable of `layout` against the parameters they declare
+fn check_layout_uses<Event: Clone+Debug+Default+PartialEq>(layout: &mut ParsedLayout<Event>) -> Vec<LayoutError> {
+    let mut errors = check_uses(&mut layout.body, &layout.parameters, &layout.body_uses);
+    for (name, reusable) in layout.reusables.iter_mut() {
+        let positions = layout.reusable_uses.get(name).map(Vec::as_slice).unwrap_or_default();
+        errors.extend(check_uses(reusable, &layout.parameters, positions));
+    }
+    errors
+}
+
+/// checks every use of a reusable with declared parameters against them and adds the defaults it leaves out.
+/// `positions` are where the uses in `commands` are, in order
+fn check_uses<Event: Clone+Debug+Default+PartialEq>(commands: &mut Vec<Layout<Event>>, parameters: &HashMap<String, Vec<Parameter<Event>>>, positions: &[Option<Position>]) -> Vec<LayoutError> {
+    let mut errors = Vec::new();
+    let mut index = 0;
+    let mut uses = 0;
+    let mut given = Vec::<(GlobalSymbol, Option<&'static str>)>::new();
+    while let Some(command) = commands.get(index) {
+        match command {
+            Layout::Element(Element::UseOpened) => given.clear(),
+            Layout::Declaration { name, value } => given.push((*name, declaration_kind(value))),
+            Layout::Element(Element::UseClosed(src)) => {
+                let position = positions.get(uses).cloned().flatten();
+                uses += 1;
+                if let Some(declared) = parameters.get(src.as_str()) {
+                    let declared_names = declared.iter().map(|parameter| parameter.name.as_str()).collect::<Vec<&str>>();
+                    for (name, kind) in &given {
+                        match declared.iter().find(|parameter| parameter.name == *name) {
+                            None => errors.push(LayoutError::new(
+                                position.as_ref(),
+                                "use",
+                                format!("`{}` has no parameter `{}`", src, name),
+                                did_you_mean(name.as_str(), &declared_names)
+                            )),
+                            Some(parameter) => if let Some(kind) = kind
+                            && *kind != parameter.kind {
+                                errors.push(LayoutError::new(
+                                    position.as_ref(),
+                                    "use",
+                                    format!("`{}` of `{}` is a {}, not a {}", name, src, parameter.kind, kind),
+                                    Some(format!("give it with `set-{}`", parameter.kind))
+                                ));
+                            }
+                        }
+                    }
+                    let src = *src;
+                    for parameter in declared {
+                        if given.iter().any(|(name, _)| *name == parameter.name) {
+                            continue
+                        }
+                        match &parameter.default {
+                            Some(default) => {
+                                commands.insert(index, Layout::Declaration { name: parameter.name, value: default.clone() });
+                                index += 1;
+                            }
+                            None => errors.push(LayoutError::new(
+                                position.as_ref(),
+                                "use",
+                                format!("`{}` needs the parameter `{}`", src, parameter.name),
+                                Some(format!("give it with `set-{}` *{}*", parameter.kind, parameter.name))
+                            )),
+                        }
+                    }
+                }
+                given.clear();
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    errors
+}
+
+/// the positions of the `use` elements parsed since the last call
+fn take_uses() -> Vec<Option<Position>> {
+    USES.with_borrow_mut(std::mem::take)
+}
+
+/// the markdown tree of a layout file, the part of parsing that doesn't depend on the app's events
+pub(crate) fn parse_markdown(file: &str) -> Result<Node, LayoutError> {
+    markdown::to_mdast(file, &markdown::ParseOptions::default()).map_err(|message| {
+        let position = match message.place.as_deref() {
+            Some(markdown::message::Place::Position(position)) => Some(position.clone()),
+            Some(markdown::message::Place::Point(point)) => Some(markdown::unist::Position { start: point.clone(), end: point.clone() }),
+            None => None,
+        };
+        LayoutError::new(position.as_ref(), "", message.reason, None)
+    })
+}
+
+/// the page, its reusables and the files it imports with their namespaces
+fn parse_layout<Event: Clone+Debug+Default+PartialEq+FromStr>(tree: &Node) -> Result<ParsedLayout<Event>, Vec<LayoutError>> 
 where <Event as FromStr>::Err: Debug+Default
 {
+    // errors left over from a parse that panicked
+    take_errors();
+    take_uses();
+    let mut imports = Vec::<(String, String, Option<Position>)>::new();
+    let mut parameters = HashMap::<String, Vec<Parameter<Event>>>::new();
     let mut parsing_mode = ParsingMode::None;
     let mut page_name = "".to_string();
     let mut body = Vec::<Layout<Event>>::new();
     let mut open_reuseable_name = "".to_string();
     let mut _open_variable_name = "".to_string();
     let mut reusables = HashMap::<String, Vec<Layout<Event>>>::new();
+    let mut body_uses = Vec::<Option<Position>>::new();
+    let mut reusable_uses = HashMap::<String, Vec<Option<Position>>>::new();
 
-    if let Ok(m) = markdown::to_mdast(&file, &markdown::ParseOptions::default())
-    && let Some(nodes) = m.children() {
+    if let Some(nodes) = tree.children() {
 
         for node in nodes {
             match node {
@@ -42,12 +418,29 @@ where <Event as FromStr>::Err: Debug+Default
                             },
                             3 => {
                                 parsing_mode = ParsingMode::ReusableElements;
-                                open_reuseable_name = declaration.value.trim().to_string();
+                                let (name, declared) = reusable_signature::<Event>(&declaration.value, h.position.as_ref());
+                                if let Some(declared) = declared {
+                                    parameters.insert(name.clone(), declared);
+                                }
+                                open_reuseable_name = name;
                             }
                             _ => parsing_mode = ParsingMode::None,
                         }
                     }
                 }
+                Node::Paragraph(paragraph) => {
+                    if let Some(Node::InlineCode(directive)) = paragraph.children.get(0)
+                    && directive.value == "import"
+                    && let Some(Node::Text(import)) = paragraph.children.get(1) {
+                        let mut import = import.value.split_whitespace();
+                        let file = import.next().unwrap_or_default().to_string();
+                        let namespace = match (import.next(), import.next()) {
+                            (Some("as"), Some(namespace)) => namespace.to_string(),
+                            _ => Path::new(&file).file_stem().unwrap_or_default().to_string_lossy().to_string(),
+                        };
+                        imports.push((file, namespace, paragraph.position.clone()));
+                    }
+                }
                 Node::List(list) => {
                     match parsing_mode {
                         ParsingMode::ReusableConfig => {
@@ -60,8 +453,9 @@ where <Event as FromStr>::Err: Debug+Default
                             for node in &list.children{
                                 let element = process_element(node);
                                 reusables.insert(open_reuseable_name.clone(), element);
+                                reusable_uses.insert(open_reuseable_name.clone(), take_uses());
                             }
-                            
+
                         }
                         ParsingMode::Body => {
                             body.push(Layout::Element(Element::Pointer(winit::window::CursorIcon::Default)));
@@ -69,6 +463,7 @@ where <Event as FromStr>::Err: Debug+Default
                                 let mut element = process_element(node);
                                 body.append(&mut element);
                             }
+                            body_uses.append(&mut take_uses());
                         }
                         ParsingMode::None => {}
                     }
@@ -76,10 +471,14 @@ where <Event as FromStr>::Err: Debug+Default
                 _ => {}
             }
         }
-        Ok((page_name, body, reusables))
+        let errors = take_errors();
+        if !errors.is_empty() {
+            return Err(errors)
+        }
+        Ok(ParsedLayout { page_name, body, reusables, imports, imported_files: Vec::new(), parameters, body_uses, reusable_uses })
     }
     else {
-        Err(":(".to_string())
+        Err(vec![LayoutError::new(None, "", "the layout is empty".to_string(), None)])
     }
 }
 
@@ -179,6 +578,68 @@ where <Event as FromStr>::Err: Debug+Default
                 layout_commands.push(Layout::Element(Element::ConfigClosed));
                 layout_commands.push(Layout::Element(Element::LineClosed));
             }
+            "custom" => {
+                layout_commands.push(Layout::Element(Element::ElementOpened { id: None }));
+                layout_commands.push(Layout::Element(Element::ConfigOpened));
+                if let Some(element_name) = element_declaration.children.get(1)
+                && let Node::Text(element_name) = element_name {
+                    layout_commands.push(Layout::Config(Config::Id(DataSrc::Static(element_name.value.trim().to_string()))));
+                }
+                let mut custom_element = CustomElement::Registered(String::new());
+                if let Some(config) = element.children.get(1)
+                && let Node::List(configs) = config
+                && let Some(configs) = configs.children.get(0)
+                && let Node::ListItem(configs) = configs
+                && let Some(configs) = configs.children.get(1)
+                && let Node::List(config_commands) = configs {
+                    let mut layout_config_commands = process_configs(&config_commands, &mut Some(&mut custom_element));
+                    layout_commands.append(&mut layout_config_commands);
+                }
+                layout_commands.push(Layout::Config(Config::CustomElement(custom_element)));
+                layout_commands.push(Layout::Element(Element::ConfigClosed));
+                layout_commands.push(Layout::Element(Element::ElementClosed));
+            }
+            "tk" => {
+                if let Some(widget_type) = element_declaration.children.get(1)
+                && let Node::Text(widget_type) = widget_type {
+                    let mut configs = List {
+                        children: Vec::new(),
+                        position: None,
+                        ordered: false,
+                        start: None,
+                        spread: false,
+                    };
+                    if let Some(config) = element.children.get(1)
+                    && let Node::List(config) = config
+                    && let Some(config) = config.children.get(0)
+                    && let Node::ListItem(config) = config
+                    && let Some(config) = config.children.get(1)
+                    && let Node::List(config) = config {
+                        configs = config.clone();
+                    }
+
+                    let widget = match widget_type.value.trim() {
+                        "button" => Some(Widget::Button(process_button(&mut configs))),
+                        "checkbox" => Some(Widget::Checkbox(process_toggle(&mut configs))),
+                        "radio" => Some(Widget::Radio(process_toggle(&mut configs))),
+                        "slider" => Some(Widget::Slider(process_slider(&mut configs))),
+                        "context-menu" => Some(Widget::ContextMenu(process_context_menu(&mut configs))),
+                        "drag-value" => Some(Widget::DragValue(process_drag_value(&mut configs))),
+                        "progress" => Some(Widget::Progress(process_progress(&mut configs))),
+                        "spinner" => Some(Widget::Spinner(process_spinner(&mut configs))),
+                        "markdown" => Some(Widget::Markdown(process_markdown(&mut configs))),
+                        "text-editor" => Some(Widget::TextEditor(process_text_editor(&mut configs))),
+                        "menu-bar" => Some(Widget::MenuBar(MenuBarConfig { menus: process_menu(&mut configs) })),
+                        _ => None,
+                    };
+
+                    if let Some(widget) = widget {
+                        layout_commands.push(Layout::Element(Element::ToolkitOpened));
+                        layout_commands.append(&mut process_configs(&configs, &mut None));
+                        layout_commands.push(Layout::Element(Element::ToolkitClosed(widget)));
+                    }
+                }
+            }
             "grow" => {
                 layout_commands.push(Layout::Element(Element::ElementOpened { id: None }));
                 layout_commands.push(Layout::Element(Element::ConfigOpened));
@@ -219,9 +680,12 @@ where <Event as FromStr>::Err: Debug+Default
                             }
                         }
                         Node::Text(static_text) => {
-                            layout_commands.push(Layout::Element(Element::TextElementClosed(
-                                DataSrc::Static(static_text.value.trim().to_string())
-                            )));
+                            match TextFormat::parse(static_text.value.trim(), static_text.position.as_ref()) {
+                                Some(format) => layout_commands.push(Layout::Element(Element::TextElementFormatted(format))),
+                                None => layout_commands.push(Layout::Element(Element::TextElementClosed(
+                                    DataSrc::Static(static_text.value.trim().to_string())
+                                ))),
+                            }
                         }
                         _ => {}
                     }
@@ -234,6 +698,7 @@ where <Event as FromStr>::Err: Debug+Default
                 && let Some(input_variables) = element.children.get(1)
                 && let Node::List(input_variables) = input_variables {
                     let src = GlobalSymbol::new(reusable_name.value.trim().to_string());
+                    USES.with_borrow_mut(|uses| uses.push(element.position.clone()));
                     layout_commands.push(Layout::Element(Element::UseOpened));
                     for input_variable in &input_variables.children {
                         if let Some((name, declaration)) = process_variable(input_variable) {
@@ -245,7 +710,7 @@ where <Event as FromStr>::Err: Debug+Default
                 }
                 
             }
-            "list" => {
+            "list" | "virtual-list" => {
                 if let Some(list_src) = element_declaration.children.get(1)
                 && let Node::Text(list_src) = list_src
                 && let Some(list_content) = element.children.get(1)
@@ -261,7 +726,7 @@ where <Event as FromStr>::Err: Debug+Default
                         for declaration in &declarations.children {
                             if let Some((name, declaration)) = process_variable(declaration) {
                                 let src = GlobalSymbol::new(name);
-                                layout_commands.push(Layout::Declaration { name: src, value: declaration });
+                                formatted_list.push(Layout::Declaration { name: src, value: declaration });
                             }
                         }
                     }
@@ -271,12 +736,49 @@ where <Event as FromStr>::Err: Debug+Default
                         formatted_list.append(&mut list_item);
                     }
 
-                    let src = GlobalSymbol::new(list_src.value.trim().to_string());
-                    formatted_list.push(Layout::Element(Element::ListClosed(src)));
+                    // the source comes first, then a virtual list's item height and `key=field` in any order
+                    let mut list_src = list_src.value.split_whitespace();
+                    let src = GlobalSymbol::new(list_src.next().unwrap_or_default().to_string());
+                    let mut item_height = DEFAULT_ITEM_HEIGHT;
+                    let mut key = None;
+                    for option in list_src {
+                        match option.strip_prefix("key=") {
+                            Some(field) => key = Some(GlobalSymbol::new(field)),
+                            None => item_height = option.parse().unwrap_or(DEFAULT_ITEM_HEIGHT),
+                        }
+                    }
+                    match element_type.value.as_str() {
+                        "virtual-list" => formatted_list.push(Layout::Element(Element::VirtualListClosed { src, item_height, key })),
+                        _ => formatted_list.push(Layout::Element(Element::ListClosed { src, key })),
+                    }
 
                     layout_commands.append(&mut formatted_list);
                 }
             }
+            "repeat" => {
+                if let Some(repeat) = element_declaration.children.get(1)
+                && let Node::Text(repeat) = repeat
+                && let Some(repeated) = element.children.get(1)
+                && let Node::List(repeated) = repeated {
+
+                    layout_commands.push(Layout::Element(Element::ListOpened));
+                    for repeated_element in &repeated.children {
+                        let mut repeated_element = process_element::<Event>(&repeated_element);
+                        layout_commands.append(&mut repeated_element);
+                    }
+
+                    // the count comes first, then the name the index goes by
+                    let position = repeat.position.as_ref();
+                    let mut repeat = repeat.value.split_whitespace();
+                    let times = repeat.next().unwrap_or_default();
+                    let times = match times.parse::<u16>() {
+                        Ok(times) => DataSrc::Static(times),
+                        Err(_) => DataSrc::Dynamic(binding_name(times, position)),
+                    };
+                    let index = GlobalSymbol::new(repeat.next().unwrap_or("index"));
+                    layout_commands.push(Layout::Element(Element::RepeatClosed { times, index }));
+                }
+            }
             "if" => {
                 if let Some(conditional) = element_declaration.children.get(1)
                 && let Node::Text(conditional) = conditional
@@ -284,7 +786,7 @@ where <Event as FromStr>::Err: Debug+Default
                 && let Node::List(conditional_elements) = conditional_elements {
 
                     let mut formatted_element = Vec::<Layout<Event>>::new();
-                    let src = GlobalSymbol::new(conditional.value.trim().to_string());
+                    let src = binding_name(&conditional.value, conditional.position.as_ref());
                     formatted_element.push(Layout::Element(Element::IfOpened { 
                         condition: src
                     }));
@@ -306,7 +808,7 @@ where <Event as FromStr>::Err: Debug+Default
                 && let Node::List(conditional_elements) = conditional_elements {
 
                     let mut formatted_element = Vec::<Layout<Event>>::new();
-                    let src = GlobalSymbol::new(conditional.value.trim().to_string());
+                    let src = binding_name(&conditional.value, conditional.position.as_ref());
                     formatted_element.push(Layout::Element(Element::IfNotOpened { 
                         condition: src
                     }));
@@ -321,12 +823,50 @@ where <Event as FromStr>::Err: Debug+Default
                     layout_commands.append(&mut formatted_element);
                 }
             }
+            "match" => {
+                if let Some(src) = element_declaration.children.get(1)
+                && let Node::Text(src) = src
+                && let Some(cases) = element.children.get(1)
+                && let Node::List(cases) = cases {
+
+                    layout_commands.push(Layout::Element(Element::MatchOpened { src: binding_name(&src.value, src.position.as_ref()) }));
+                    for case in &cases.children {
+                        let mut case = process_element::<Event>(&case);
+                        layout_commands.append(&mut case);
+                    }
+                    layout_commands.push(Layout::Element(Element::MatchClosed));
+                }
+            }
+            "case" | "default" => {
+                let case = match element_declaration.children.get(1) {
+                    Some(Node::Text(value)) if element_type.value == "case" => Element::CaseOpened { is: value.value.trim().to_string() },
+                    _ => Element::DefaultOpened,
+                };
+                layout_commands.push(Layout::Element(case));
+
+                if let Some(case_elements) = element.children.get(1)
+                && let Node::List(case_elements) = case_elements {
+                    for case_element in &case_elements.children {
+                        let mut case_element = process_element::<Event>(&case_element);
+                        layout_commands.append(&mut case_element);
+                    }
+                }
+
+                layout_commands.push(Layout::Element(Element::CaseClosed));
+            }
             "treeview" => {
                 if let Some(reusable_name) = element_declaration.children.get(1)
                 && let Node::Text(reusable_name) = reusable_name {
                     layout_commands.push(Layout::Element(Element::TreeViewOpened));
-                    let src = GlobalSymbol::new(reusable_name.value.trim().to_string());
-                    layout_commands.push(Layout::Element(Element::TreeViewClosed(src)));
+                    // the selection mode follows the source
+                    let mut reusable_name = reusable_name.value.split_whitespace();
+                    let src = GlobalSymbol::new(reusable_name.next().unwrap_or_default().to_string());
+                    let selection = match reusable_name.next() {
+                        Some("single") => TreeSelectionMode::Single,
+                        Some("multi") => TreeSelectionMode::Multi,
+                        _ => TreeSelectionMode::None,
+                    };
+                    layout_commands.push(Layout::Element(Element::TreeViewClosed{src, selection}));
                 }
             }
             "textbox" => {
@@ -343,11 +883,40 @@ where <Event as FromStr>::Err: Debug+Default
                     _ => {}
                 }
             }
-            _ => {}
+            // the configs of elements that have them are read with the element
+            "config" => {}
+            other => report(
+                element_type.position.as_ref(),
+                other,
+                format!("unknown element `{}`", other),
+                did_you_mean(other, ELEMENTS)
+            ),
         }
     }
 
-    layout_commands
+    collapse(layout_commands)
+}
+
+/// wraps an element configured `collapsed` in an `if-not` on its flag, a collapsed static element is dropped
+pub(crate) fn collapse<Event: Clone+Debug+Default+PartialEq>(mut layout_commands: Vec<Layout<Event>>) -> Vec<Layout<Event>> {
+    let own_configs = layout_commands.iter()
+        .position(|command| matches!(command, Layout::Element(Element::ConfigClosed)))
+        .unwrap_or(0);
+    let Some(position) = layout_commands[..own_configs].iter()
+        .position(|command| matches!(command, Layout::Config(Config::Collapsed(_))))
+    else {
+        return layout_commands
+    };
+
+    match layout_commands.remove(position) {
+        Layout::Config(Config::Collapsed(DataSrc::Static(true))) => Vec::new(),
+        Layout::Config(Config::Collapsed(DataSrc::Dynamic(condition))) => {
+            layout_commands.insert(0, Layout::Element(Element::IfNotOpened { condition }));
+            layout_commands.push(Layout::Element(Element::IfClosed));
+            layout_commands
+        }
+        _ => layout_commands,
+    }
 }
 
 #[derive(Debug)]
@@ -413,8 +982,8 @@ fn parameter_check<T: FromStr>(parameters: &Paragraph, bound_a: &str, bound_b: &
     && let Some(bound_value_b) = bound_value_b.children.get(0)
     && let Node::Text(bound_value_b) = bound_value_b
     {
-        let bound_value_a = GlobalSymbol::new(bound_value_a.value.trim().to_string());
-        let bound_value_b = GlobalSymbol::new(bound_value_b.value.trim().to_string());
+        let bound_value_a = binding_name(&bound_value_a.value, bound_value_a.position.as_ref());
+        let bound_value_b = binding_name(&bound_value_b.value, bound_value_b.position.as_ref());
         if bound_range_a.value.as_str() == bound_a {
             AvailableParameters::TwoDynamic(bound_value_a, bound_value_b)
         }
@@ -440,7 +1009,7 @@ fn parameter_check<T: FromStr>(parameters: &Paragraph, bound_a: &str, bound_b: &
     && let Some(bound_value_b) = parameters.children.get(7)
     && let Node::Text(bound_value_b) = bound_value_b
     && let Ok(bound_value_b) = T::from_str(bound_value_b.value.trim()) {
-        let bound_value_a = GlobalSymbol::new(bound_value_a.value.trim().to_string());
+        let bound_value_a = binding_name(&bound_value_a.value, bound_value_a.position.as_ref());
         if bound_range_a.value.as_str() == bound_a {
             AvailableParameters::ADynamicBStatic(bound_value_a, bound_value_b)
         }
@@ -466,7 +1035,7 @@ fn parameter_check<T: FromStr>(parameters: &Paragraph, bound_a: &str, bound_b: &
     && let Node::Emphasis(bound_value_b) = bound_value_b
     && let Some(bound_value_b) = bound_value_b.children.get(0)
     && let Node::Text(bound_value_b) = bound_value_b {
-        let bound_value_b = GlobalSymbol::new(bound_value_b.value.trim().to_string());
+        let bound_value_b = binding_name(&bound_value_b.value, bound_value_b.position.as_ref());
         if bound_range_a.value.as_str() == bound_a {
             AvailableParameters::ADynamicBStatic(bound_value_b, bound_value_a)
         }
@@ -500,7 +1069,7 @@ fn parameter_check<T: FromStr>(parameters: &Paragraph, bound_a: &str, bound_b: &
     && let Node::Emphasis(bound_value_a) = bound_value_a
     && let Some(bound_value_a) = bound_value_a.children.get(0)
     && let Node::Text(bound_value_a) = bound_value_a {
-        let bound_value_a = GlobalSymbol::new(bound_value_a.value.trim().to_string());
+        let bound_value_a = binding_name(&bound_value_a.value, bound_value_a.position.as_ref());
         if bound_range_a.value.as_str() == bound_a {
             AvailableParameters::ADynamic(bound_value_a)
         }
@@ -513,7 +1082,7 @@ fn parameter_check<T: FromStr>(parameters: &Paragraph, bound_a: &str, bound_b: &
     && let Node::Emphasis(parameter) = parameter
     && let Some(parameter) = parameter.children.get(0)
     && let Node::Text(parameter) = parameter {
-        let parameter = GlobalSymbol::new(parameter.value.trim().to_string());
+        let parameter = binding_name(&parameter.value, parameter.position.as_ref());
         AvailableParameters::SingleDynamic(parameter)
     }
     else
@@ -522,6 +1091,20 @@ fn parameter_check<T: FromStr>(parameters: &Paragraph, bound_a: &str, bound_b: &
     && let Ok(parameter) = T::from_str(parameter.value.trim()) {
         AvailableParameters::SingleStatic(parameter)
     }
+    //  CASE: a single value that isn't one
+    else
+    if parameters.children.len() == 2
+    && let Some(Node::InlineCode(key)) = parameters.children.get(0)
+    && let Some(Node::Text(parameter)) = parameters.children.get(1)
+    && !parameter.value.trim().is_empty() {
+        report(
+            parameter.position.as_ref(),
+            &key.value,
+            format!("`{}` isn't a value `{}` takes", parameter.value.trim(), key.value),
+            None
+        );
+        AvailableParameters::None
+    }
     //  CASE: no parameters
     else {
         AvailableParameters::None
@@ -547,82 +1130,478 @@ fn process_variable<Event: Clone+Debug+Default+PartialEq+FromStr>(declaration: &
             "get-event" |
             "get-image" |
             "get-color" => {
-                let value = GlobalSymbol::new(variable_value.value.trim().to_string());
+                let value = binding_name(&variable_value.value, variable_value.position.as_ref());
                 Some((
                     variable_name.value.trim().to_string(),
                     DataSrc::<Declaration<Event>>::Dynamic(value)
                 ))
             }
-            "set-bool" => {
-                if let Ok(variable_value) = bool::from_str(&variable_value.value.trim()) {
-                    Some((
-                        variable_name.value.trim().to_string(),
-                        DataSrc::<Declaration<Event>>::Static(
-                            Declaration::Bool(variable_value)
-                        )
-                    ))
-                }
-                else {
-                    None
+            "set-bool" |
+            "set-numeric" |
+            "set-text" |
+            "set-event" |
+            "set-color" => {
+                let kind = variable_type.value.trim_start_matches("set-");
+                let value = static_declaration(kind, &variable_value.value);
+                if value.is_none() {
+                    report(
+                        variable_value.position.as_ref(),
+                        &variable_type.value,
+                        format!("`{}` isn't a {}", variable_value.value.trim(), kind),
+                        None
+                    );
                 }
+                value.map(|value| (variable_name.value.trim().to_string(), value))
             }
-            "set-numeric" => {
-                if let Ok(variable_value) = f32::from_str(&variable_value.value.trim()) {
-                    Some((
-                        variable_name.value.trim().to_string(),
-                        DataSrc::<Declaration<Event>>::Static(
-                            Declaration::Numeric(variable_value)
-                        )
-                    ))
-                }
-                else {
-                    None
+            _ => None
+        }
+    }
+    else {
+        None
+    }
+}
+
+/// `text` as a value of the declaration kind `kind`, like `bool` for `set-bool`
+fn static_declaration<Event: Clone+Debug+Default+PartialEq+FromStr>(kind: &str, text: &str) -> Option<DataSrc<Declaration<Event>>> {
+    let text = text.trim();
+    let declaration = match kind {
+        "bool" => Declaration::Bool(bool::from_str(text).ok()?),
+        "numeric" => Declaration::Numeric(f32::from_str(text).ok()?),
+        "text" => Declaration::Text(text.to_string()),
+        "event" => Declaration::Event(Event::from_str(text).ok()?),
+        "color" => Declaration::Color(Color::from_str(text).ok()?),
+        "image" => Declaration::Image(GlobalSymbol::new(text)),
+        _ => return None,
+    };
+    Some(DataSrc::Static(declaration))
+}
+
+/// the name and first parameter paragraph of one config line
+fn config_key(configuration_item: &Node) -> Option<(&str, &Paragraph)> {
+    if let Some(config_elements) = configuration_item.children()
+    && let Some(config) = config_elements.get(0)
+    && let Node::Paragraph(config) = config
+    && let Some(config_type) = config.children.get(0)
+    && let Node::InlineCode(config_type) = config_type {
+        Some((config_type.value.as_str(), config))
+    }
+    else {
+        None
+    }
+}
+
+/// takes the button's own keys out of `configs`, the rest are regular element configs
+fn process_button<Event: Clone+Debug+Default+PartialEq+FromStr>(configs: &mut List) -> ButtonConfig<Event> {
+    let mut button = ButtonConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "label" => {
+                match parameter_check::<String>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => button.label = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => button.label = DataSrc::Static(a),
+                    _ => {}
                 }
             }
-            "set-text" => {
-                Some((
-                    variable_name.value.trim().to_string(),
-                    DataSrc::<Declaration<Event>>::Static(
-                        Declaration::Text(variable_value.value.trim().to_string())
-                    )
-                ))
+            "emit" => {
+                match parameter_check::<Event>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => button.emit = Some(DataSrc::Dynamic(a)),
+                    AvailableParameters::SingleStatic(a) => button.emit = Some(DataSrc::Static(a)),
+                    _ => {}
+                }
             }
-            "set-event" => {
-                if let Ok(variable_value) = Event::from_str(variable_value.value.trim()) {
-                    Some((
-                        variable_name.value.trim().to_string(),
-                        DataSrc::<Declaration<Event>>::Static(
-                            Declaration::Event(variable_value)
-                        )
-                    ))
-                }
-                else {
-                    None
+            "disabled" => {
+                match parameter_check::<bool>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => button.disabled = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => button.disabled = DataSrc::Static(a),
+                    AvailableParameters::None => button.disabled = DataSrc::Static(true),
+                    _ => {}
                 }
             }
-            "set-color" => {
-                if let Ok(variable_value) = Color::from_str(&variable_value.value.trim()) {
-                    Some((
-                        variable_name.value.trim().to_string(),
-                        DataSrc::<Declaration<Event>>::Static(
-                            Declaration::Color(variable_value)
-                        )
-                    ))
+            "color" | "hover-color" | "pressed-color" | "disabled-color" => {
+                let color = match parameter_check::<Color>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => Some(DataSrc::Dynamic(a)),
+                    AvailableParameters::SingleStatic(a) => Some(DataSrc::Static(a)),
+                    _ => None
+                };
+                match key {
+                    "color" => button.color = color,
+                    "hover-color" => button.hover_color = color,
+                    "pressed-color" => button.pressed_color = color,
+                    _ => button.disabled_color = color,
                 }
-                else {
-                    None
+            }
+            _ => return true
+        }
+        false
+    });
+
+    button
+}
+
+/// takes the checkbox or radio keys out of `configs`, the rest are regular element configs
+fn process_toggle<Event: Clone+Debug+Default+PartialEq+FromStr>(configs: &mut List) -> ToggleConfig<Event> {
+    let mut toggle = ToggleConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "label" => {
+                match parameter_check::<String>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => toggle.label = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => toggle.label = DataSrc::Static(a),
+                    _ => {}
                 }
             }
-            _ => None
+            "checked" | "disabled" => {
+                let value = match parameter_check::<bool>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => DataSrc::Static(a),
+                    _ => DataSrc::Static(true),
+                };
+                match key {
+                    "checked" => toggle.checked = value,
+                    _ => toggle.disabled = value,
+                }
+            }
+            "emit" => {
+                match parameter_check::<Event>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => toggle.emit = Some(DataSrc::Dynamic(a)),
+                    AvailableParameters::SingleStatic(a) => toggle.emit = Some(DataSrc::Static(a)),
+                    _ => {}
+                }
+            }
+            "group" => {
+                if let AvailableParameters::SingleStatic(a) = parameter_check::<String>(config, "", "") {
+                    toggle.group = a;
+                }
+            }
+            _ => return true
         }
+        false
+    });
+
+    toggle
+}
+
+fn numeric_key(config: &Paragraph) -> Option<DataSrc<f32>> {
+    match parameter_check::<f32>(config, "", "") {
+        AvailableParameters::SingleDynamic(a) => Some(DataSrc::Dynamic(a)),
+        AvailableParameters::SingleStatic(a) => Some(DataSrc::Static(a)),
+        _ => None
     }
-    else {
-        None
+}
+
+fn color_key(config: &Paragraph) -> Option<DataSrc<Color>> {
+    match parameter_check::<Color>(config, "", "") {
+        AvailableParameters::SingleDynamic(a) => Some(DataSrc::Dynamic(a)),
+        AvailableParameters::SingleStatic(a) => Some(DataSrc::Static(a)),
+        _ => None
+    }
+}
+
+/// takes the drag-value keys out of `configs`, the rest are regular element configs
+fn process_drag_value<Event: Clone+Debug+Default+PartialEq+FromStr>(configs: &mut List) -> DragValueConfig<Event> {
+    let mut drag = DragValueConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "value" => drag.value = numeric_key(config).unwrap_or(drag.value.clone()),
+            "min" => drag.min = numeric_key(config),
+            "max" => drag.max = numeric_key(config),
+            "step" => drag.step = numeric_key(config).unwrap_or(drag.step.clone()),
+            "decimals" => {
+                match parameter_check::<u16>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => drag.decimals = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => drag.decimals = DataSrc::Static(a),
+                    _ => {}
+                }
+            }
+            "suffix" => {
+                match parameter_check::<String>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => drag.suffix = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => drag.suffix = DataSrc::Static(a),
+                    _ => {}
+                }
+            }
+            "disabled" => {
+                match parameter_check::<bool>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => drag.disabled = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => drag.disabled = DataSrc::Static(a),
+                    _ => drag.disabled = DataSrc::Static(true),
+                }
+            }
+            "emit" => {
+                match parameter_check::<Event>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => drag.emit = Some(DataSrc::Dynamic(a)),
+                    AvailableParameters::SingleStatic(a) => drag.emit = Some(DataSrc::Static(a)),
+                    _ => {}
+                }
+            }
+            _ => return true
+        }
+        false
+    });
+
+    drag
+}
+
+/// takes the progress bar keys out of `configs`, the rest are regular element configs
+fn process_progress(configs: &mut List) -> ProgressConfig {
+    let mut progress = ProgressConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "value" => progress.value = numeric_key(config).unwrap_or(progress.value.clone()),
+            "length" => progress.length = numeric_key(config).unwrap_or(progress.length.clone()),
+            "thickness" => progress.thickness = numeric_key(config).unwrap_or(progress.thickness.clone()),
+            "color" => progress.color = color_key(config),
+            _ => return true
+        }
+        false
+    });
+
+    progress
+}
+
+/// takes the spinner keys out of `configs`, the rest are regular element configs
+fn process_spinner(configs: &mut List) -> SpinnerConfig {
+    let mut spinner = SpinnerConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "size" => spinner.size = numeric_key(config).unwrap_or(spinner.size.clone()),
+            "color" => spinner.color = color_key(config),
+            _ => return true
+        }
+        false
+    });
+
+    spinner
+}
+
+/// takes the markdown keys out of `configs`, the rest are regular element configs
+fn process_markdown<Event: Clone+Debug+Default+PartialEq+FromStr>(configs: &mut List) -> MarkdownConfig<Event> {
+    let mut markdown = MarkdownConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "from" => {
+                match parameter_check::<String>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => markdown.from = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => markdown.from = DataSrc::Static(a),
+                    _ => {}
+                }
+            }
+            "emit" => {
+                match parameter_check::<Event>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => markdown.emit = Some(DataSrc::Dynamic(a)),
+                    AvailableParameters::SingleStatic(a) => markdown.emit = Some(DataSrc::Static(a)),
+                    _ => {}
+                }
+            }
+            _ => return true
+        }
+        false
+    });
+
+    markdown
+}
+
+/// takes the text-editor keys out of `configs`, the rest are regular element configs
+fn process_text_editor<Event: Clone+Debug+Default+PartialEq+FromStr>(configs: &mut List) -> TextEditorConfig<Event> {
+    let mut editor = TextEditorConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "text" => {
+                match parameter_check::<String>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => editor.text = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => editor.text = DataSrc::Static(a),
+                    _ => {}
+                }
+            }
+            "lines" => editor.lines = numeric_key(config).unwrap_or(editor.lines.clone()),
+            "disabled" => {
+                match parameter_check::<bool>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => editor.disabled = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => editor.disabled = DataSrc::Static(a),
+                    _ => editor.disabled = DataSrc::Static(true),
+                }
+            }
+            "emit" => {
+                match parameter_check::<Event>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => editor.emit = Some(DataSrc::Dynamic(a)),
+                    AvailableParameters::SingleStatic(a) => editor.emit = Some(DataSrc::Static(a)),
+                    _ => {}
+                }
+            }
+            _ => return true
+        }
+        false
+    });
+
+    editor
+}
+
+/// takes the `menu`, `item` and `separator` entries out of `configs`, nested menus are read from their own lists
+fn process_menu<Event: Clone+Debug+Default+PartialEq+FromStr>(configs: &mut List) -> Vec<MenuEntry<Event>> {
+    let mut entries = Vec::new();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        let (label, mnemonic) = match config.children.get(1) {
+            Some(Node::Text(label)) => parse_mnemonic(label.value.trim()),
+            _ => (String::new(), None),
+        };
+        let mut nested = match configuration_item.children().and_then(|children| children.get(1)) {
+            Some(Node::List(nested)) => nested.clone(),
+            _ => List { children: Vec::new(), position: None, ordered: false, start: None, spread: false },
+        };
+
+        match key {
+            "separator" => entries.push(MenuEntry::Separator),
+            "menu" => {
+                let items = process_menu(&mut nested);
+                let enabled = menu_flag(&nested, "enabled");
+                entries.push(MenuEntry::Menu { label, mnemonic, enabled, items });
+            }
+            "item" => {
+                let enabled = menu_flag(&nested, "enabled");
+                let checked = nested.children.iter()
+                    .any(|item| config_key(item).is_some_and(|(key, _)| key == "checked"))
+                    .then(|| menu_flag(&nested, "checked"));
+                let mut emit = None;
+                for item in &nested.children {
+                    if let Some(("emit", config)) = config_key(item) {
+                        emit = match parameter_check::<Event>(config, "", "") {
+                            AvailableParameters::SingleDynamic(a) => Some(DataSrc::Dynamic(a)),
+                            AvailableParameters::SingleStatic(a) => Some(DataSrc::Static(a)),
+                            _ => None,
+                        };
+                    }
+                }
+                entries.push(MenuEntry::Item { label, mnemonic, emit, enabled, checked });
+            }
+            _ => return true
+        }
+        false
+    });
+
+    entries
+}
+
+/// `enabled` defaults to true, a bare key is true, otherwise a static or bound bool
+fn menu_flag(configs: &List, flag: &str) -> DataSrc<bool> {
+    for item in &configs.children {
+        if let Some((key, config)) = config_key(item)
+        && key == flag {
+            return match parameter_check::<bool>(config, "", "") {
+                AvailableParameters::SingleDynamic(a) => DataSrc::Dynamic(a),
+                AvailableParameters::SingleStatic(a) => DataSrc::Static(a),
+                _ => DataSrc::Static(true),
+            }
+        }
     }
+    DataSrc::Static(flag == "enabled")
+}
+
+/// takes the menu's list and field names out of `configs`, the rest are regular element configs
+fn process_context_menu(configs: &mut List) -> ContextMenuConfig {
+    let mut menu = ContextMenuConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        let name = match config.children.get(1) {
+            Some(Node::Text(name)) => GlobalSymbol::new(name.value.trim().to_string()),
+            _ => return false
+        };
+        match key {
+            "src" => menu.src = name,
+            "label" => menu.label = name,
+            "emit" => menu.emit = name,
+            _ => return true
+        }
+        false
+    });
+
+    menu
+}
+
+/// takes the slider keys out of `configs`, the rest are regular element configs
+fn process_slider<Event: Clone+Debug+Default+PartialEq+FromStr>(configs: &mut List) -> SliderConfig<Event> {
+    let mut slider = SliderConfig::default();
+
+    configs.children.retain(|configuration_item| {
+        let Some((key, config)) = config_key(configuration_item) else {
+            return true
+        };
+        match key {
+            "value" | "min" | "max" | "step" | "length" => {
+                let value = match parameter_check::<f32>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => DataSrc::Static(a),
+                    _ => return false
+                };
+                match key {
+                    "value" => slider.value = value,
+                    "min" => slider.min = value,
+                    "max" => slider.max = value,
+                    "step" => slider.step = value,
+                    _ => slider.length = value,
+                }
+            }
+            "vertical" => slider.vertical = true,
+            "disabled" => {
+                match parameter_check::<bool>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => slider.disabled = DataSrc::Dynamic(a),
+                    AvailableParameters::SingleStatic(a) => slider.disabled = DataSrc::Static(a),
+                    _ => slider.disabled = DataSrc::Static(true),
+                }
+            }
+            "emit" => {
+                match parameter_check::<Event>(config, "", "") {
+                    AvailableParameters::SingleDynamic(a) => slider.emit = Some(DataSrc::Dynamic(a)),
+                    AvailableParameters::SingleStatic(a) => slider.emit = Some(DataSrc::Static(a)),
+                    _ => {}
+                }
+            }
+            _ => return true
+        }
+        false
+    });
+
+    slider
 }
 
 fn process_configs<Event: Clone+Debug+Default+PartialEq+FromStr>(configuration_set: &List, custom_element: &mut Option<&mut CustomElement>) -> Vec<Layout<Event>> {
     let mut configs = Vec::new();
+    // where a negative `z-index` was set, only floating elements can go under their neighbours and `floating` may come later
+    let mut negative_z_index = None;
 
     for configuration_item in &configuration_set.children {
         if let Some(config_elements) = configuration_item.children()
@@ -830,6 +1809,13 @@ fn process_configs<Event: Clone+Debug+Default+PartialEq+FromStr>(configuration_s
                         }
                     }
                 }
+                "type" => {
+                    if let Some(custom_element) = custom_element
+                    && let CustomElement::Registered(name) = custom_element
+                    && let AvailableParameters::SingleStatic(a) = parameter_check::<String>(config, "", "") {
+                        *name = a;
+                    }
+                }
                 "radius-all" => {
                     match parameter_check::<f32>(config, "", "") {
                         AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::RadiusAll(DataSrc::Dynamic(a)))),
@@ -941,6 +1927,73 @@ fn process_configs<Event: Clone+Debug+Default+PartialEq+FromStr>(configuration_s
                         configs.push(Layout::Config(Config::Image { name: src }));
                     }
                 }
+                "depth-test" => {
+                    match parameter_check::<bool>(config, "", "") {
+                        AvailableParameters::None => configs.push(Layout::Config(Config::DepthTest(DataSrc::Static(true)))),
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::DepthTest(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::DepthTest(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                "pixel-snap" => {
+                    match parameter_check::<bool>(config, "", "") {
+                        AvailableParameters::None => configs.push(Layout::Config(Config::PixelSnap(DataSrc::Static(true)))),
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::PixelSnap(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::PixelSnap(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                // ahead of the colors it fades, wherever it's written in the list
+                "opacity" => {
+                    match parameter_check::<f32>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.insert(0, Layout::Config(Config::Opacity(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.insert(0, Layout::Config(Config::Opacity(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                "visible" => {
+                    match parameter_check::<bool>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.insert(0, Layout::Config(Config::Visible(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.insert(0, Layout::Config(Config::Visible(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                "collapsed" => {
+                    match parameter_check::<bool>(config, "", "") {
+                        AvailableParameters::None => configs.push(Layout::Config(Config::Collapsed(DataSrc::Static(true)))),
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::Collapsed(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::Collapsed(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                "tooltip" => {
+                    match parameter_check::<String>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::Tooltip(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::Tooltip(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                "tooltip-delay" => {
+                    match parameter_check::<f32>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::TooltipDelay(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::TooltipDelay(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                "event-code" => {
+                    match parameter_check::<f32>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::EventCode(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::EventCode(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
+                "event-text" => {
+                    match parameter_check::<String>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::EventText(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::EventText(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
                 "floating" => {
                     configs.push(Layout::Config(Config::Floating));
                     if let Some(floating_commands) = config_elements.get(1)
@@ -1283,6 +2336,25 @@ fn process_configs<Event: Clone+Debug+Default+PartialEq+FromStr>(configuration_s
                         _ => {}
                     }
                 }
+                "font" => {
+                    match parameter_check::<String>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::Font(
+                            DataSrc::Dynamic(a)
+                        ))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::Font(
+                            DataSrc::Static(a)
+                        ))),
+                        _ => {}
+                    }
+                }
+                "rtl" => {
+                    match parameter_check::<bool>(config, "", "") {
+                        AvailableParameters::None => configs.push(Layout::Config(Config::Rtl(DataSrc::Static(true)))),
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::Rtl(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::Rtl(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
                 "font-size" => {
                     match parameter_check::<u16>(config, "", "") {
                         AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::FontSize(
@@ -1364,6 +2436,13 @@ fn process_configs<Event: Clone+Debug+Default+PartialEq+FromStr>(configuration_s
                         _ => {}
                     }
                 }
+                "anchor" => {
+                    match parameter_check::<String>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::FloatingAnchor(DataSrc::Dynamic(a)))),
+                        AvailableParameters::SingleStatic(a) => configs.push(Layout::Config(Config::FloatingAnchor(DataSrc::Static(a)))),
+                        _ => {}
+                    }
+                }
                 "attatch-parent" => {
                     if let Some(attach_point) = config.children.get(1)
                     && let Node::Text(attach_point) = attach_point {
@@ -1470,11 +2549,196 @@ fn process_configs<Event: Clone+Debug+Default+PartialEq+FromStr>(configuration_s
                         }
                     }
                 }
-                // TODO: z-index, pointer pass through
-                _ => {}
+                "attach-to" => {
+                    if let Some(other_element_id) = config.children.get(1)
+                    && let Node::Text(other_element_id) = other_element_id {
+                        configs.push(Layout::Config(Config::FloatingAttachElementToElement {
+                            other_element_id: other_element_id.value.trim().to_string()
+                        }));
+                    }
+                }
+                "attach-root" => configs.push(Layout::Config(Config::FloatingAttachElementToRoot)),
+                "z-index" => {
+                    match parameter_check::<i16>(config, "", "") {
+                        AvailableParameters::SingleDynamic(a) => configs.push(Layout::Config(Config::ZIndex {
+                            z: DataSrc::Dynamic(a), layer: CustomElement::Layer(0)
+                        })),
+                        AvailableParameters::SingleStatic(a) => {
+                            if a < 0 {
+                                negative_z_index = Some(config_type.position.clone());
+                            }
+                            configs.push(Layout::Config(Config::ZIndex {
+                                z: DataSrc::Static(a), layer: CustomElement::Layer(a)
+                            }))
+                        }
+                        _ => {}
+                    }
+                }
+                // TODO: pointer pass through
+                other => report(
+                    config_type.position.as_ref(),
+                    other,
+                    format!("unknown config `{}`", other),
+                    did_you_mean(other, CONFIGS)
+                ),
+            }
+        }
+    }
+
+    // floating elements are layered by the layout engine
+    if configs.iter().any(|config| matches!(config, Layout::Config(Config::Floating))) {
+        for config in configs.iter_mut() {
+            if let Layout::Config(Config::ZIndex { z, layer: _ }) = config {
+                *config = Layout::Config(Config::FloatingZIndex { z: z.clone() });
             }
         }
     }
+    else if let Some(position) = negative_z_index {
+        report(
+            position.as_ref(),
+            "z-index",
+            "a negative `z-index` only puts floating elements under their neighbours".to_string(),
+            Some("make the element `floating`, or raise its neighbours instead".to_string())
+        );
+    }
 
     configs
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    enum Event {
+        #[default]
+        None,
+        Save,
+    }
+
+    impl FromStr for Event {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "none" => Ok(Event::None),
+                "save" => Ok(Event::Save),
+                _ => Err(()),
+            }
+        }
+    }
+
+    /// the page `page` using `Card` with `parameters`, the use is on line 5
+    fn layout(signature: &str, parameters: &str) -> String {
+        format!("### {}\n- `element`\n\n# page\n- `use` Card\n    - {}\n", signature, parameters)
+    }
+
+    fn errors(layout: String) -> Vec<LayoutError> {
+        match process_layout::<Event>(layout) {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors,
+        }
+    }
+
+    #[test]
+    fn defaults_fill_left_out_parameters() {
+        let (_, body, _) = process_layout::<Event>(layout("Card(title: text, count: numeric = 2)", "`set-text` *title* Hello")).unwrap();
+        assert!(body.contains(&Layout::Declaration {
+            name: GlobalSymbol::new("count"),
+            value: DataSrc::Static(Declaration::Numeric(2.0))
+        }));
+    }
+
+    #[test]
+    fn unknown_parameter() {
+        let errors = errors(layout("Card(title: text = Untitled)", "`set-text` *titel* Hello"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (5, 1));
+        assert_eq!(errors[0].tag, "use");
+        assert_eq!(errors[0].hint.as_deref(), Some("did you mean `title`?"));
+    }
+
+    #[test]
+    fn wrong_type() {
+        let errors = errors(layout("Card(title: text)", "`set-numeric` *title* 3"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 5);
+        assert_eq!(errors[0].message, "`title` of `Card` is a text, not a numeric");
+    }
+
+    #[test]
+    fn missing_required_parameter() {
+        let errors = errors(layout("Card(title: text, count: numeric = 1)", "`set-numeric` *count* 3"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 5);
+        assert_eq!(errors[0].message, "`Card` needs the parameter `title`");
+    }
+
+    #[test]
+    fn parameter_without_a_type() {
+        let errors = errors(layout("Card(title)", "`set-text` *title* Hello"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (1, 1));
+        assert_eq!(errors[0].message, "parameter `title` of `Card` has no type");
+    }
+
+    #[test]
+    fn unknown_parameter_type() {
+        let errors = errors(layout("Card(title: txt)", "`set-text` *title* Hello"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 1);
+        assert_eq!(errors[0].hint.as_deref(), Some("did you mean `text`?"));
+    }
+
+    #[test]
+    fn bad_default() {
+        let errors = errors(layout("Card(count: numeric = many, action: event = save)", "`set-numeric` *count* 3"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 1);
+        assert_eq!(errors[0].message, "`many` isn't a numeric for the default of `count` in `Card`");
+    }
+
+    #[test]
+    fn validate_layouts_finds_broken_imports_unknown_reusables_and_bad_parameters() {
+        let dir = std::env::temp_dir().join(format!("telera_validate_layouts_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, text) in [
+            ("widgets.md", "### Card(title: text)\n- `element`\n"),
+            ("page.md", "`import` widgets.md\n\n# page\n- `use` widgets.Card\n    - `set-numeric` *title* 3\n"),
+            ("broken.md", "`import` missing.md\n\n# broken\n- `element`\n"),
+            ("cycle_a.md", "`import` cycle_b.md\n\n# a\n- `element`\n"),
+            ("cycle_b.md", "`import` cycle_a.md\n\n# b\n- `element`\n"),
+            ("unknown.md", "# unknown\n- `use` Crad\n    - `set-text` *title* Hello\n"),
+        ] {
+            std::fs::write(dir.join(file), text).unwrap();
+        }
+        let diagnostics = validate_layouts::<Event>(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let find = |file: &str, message: &str| diagnostics.iter()
+            .find(|diagnostic| diagnostic.file.as_deref() == Some(dir.join(file).as_path()) && diagnostic.message == message)
+            .unwrap_or_else(|| panic!("no `{}` in {}: {:#?}", message, file, diagnostics));
+
+        assert_eq!(diagnostics.len(), 5, "{:#?}", diagnostics);
+        assert_eq!(find("page.md", "`title` of `widgets.Card` is a text, not a numeric").line, 4);
+
+        let broken = find("broken.md", "failed to import `missing.md`");
+        assert_eq!((broken.line, broken.column, broken.tag.as_str()), (1, 1, "import"));
+        assert_eq!(broken.nested.len(), 1);
+
+        let cycle = find("cycle_a.md", "failed to import `cycle_b.md`");
+        assert_eq!(cycle.nested[0].message, "`cycle_a.md` imports this file back");
+        find("cycle_b.md", "failed to import `cycle_a.md`");
+
+        let unknown = find("unknown.md", "no layout defines the reusable `Crad`");
+        assert_eq!(unknown.hint.as_deref(), Some("did you mean `Card`?"));
+    }
+
+    #[test]
+    fn events_are_checked_through_from_str() {
+        let layout = "# page\n- `element`\n    - `config`\n        - `left-clicked` save\n        - `right-clicked` Save\n";
+        let errors = errors(layout.to_string());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 5);
+        assert_eq!(errors[0].message, "`Save` isn't a value `right-clicked` takes");
+    }
+}
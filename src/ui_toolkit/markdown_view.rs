@@ -0,0 +1,288 @@
+use std::fmt::Debug;
+
+use markdown::mdast::Node;
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+
+use crate::{DataSrc, API, EventContext};
+
+const DEFAULT_FONT_SIZE: u16 = 14;
+const STRONG: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 255.0 };
+const EMPHASIS: Color = Color { r: 90.0, g: 90.0, b: 90.0, a: 255.0 };
+const LINK: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const LINK_HOVER: Color = Color { r: 0.0, g: 60.0, b: 180.0, a: 255.0 };
+const CODE_BACKGROUND: Color = Color { r: 236.0, g: 236.0, b: 236.0, a: 255.0 };
+const QUOTE_BAR: Color = Color { r: 200.0, g: 200.0, b: 200.0, a: 255.0 };
+const RULE: Color = Color { r: 210.0, g: 210.0, b: 210.0, a: 255.0 };
+/// heading sizes relative to the body text, h1 to h6
+const HEADING_SCALE: [f32; 6] = [2.0, 1.6, 1.35, 1.15, 1.0, 0.9];
+
+/// `tk` markdown as parsed from a layout, `from` is the markdown text to display
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkdownConfig<Event: Clone+Debug+PartialEq+Default> {
+    pub from: DataSrc<String>,
+    /// sent when a link is clicked, `EventContext::text` is the link's url
+    pub emit: Option<DataSrc<Event>>,
+}
+
+impl<Event: Clone+Debug+PartialEq+Default> Default for MarkdownConfig<Event> {
+    fn default() -> Self {
+        Self {
+            from: DataSrc::Static(String::new()),
+            emit: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum RunKind<'doc> {
+    Plain,
+    Strong,
+    Emphasis,
+    Code,
+    Link(&'doc str),
+}
+
+/// a stretch of inline text drawn with one style
+struct Run<'doc> {
+    text: String,
+    kind: RunKind<'doc>,
+}
+
+/// lays out headings, paragraphs, lists, quotes and code blocks from `source`.
+/// styled runs in a paragraph sit side by side, each wrapping on its own
+pub fn markdown_view<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    text_config: &TextConfig,
+    source: &str,
+    link_event: Option<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    api.ui_layout.open_element();
+    config.direction(true).child_gap(8).parse();
+    api.ui_layout.configure_element(config);
+
+    let body = match text_config.font_size {
+        0 => text_config.clone().font_size(DEFAULT_FONT_SIZE).end(),
+        _ => text_config.clone(),
+    };
+
+    match markdown::to_mdast(source, &markdown::ParseOptions::gfm()) {
+        Ok(document) => if let Some(blocks) = document.children() {
+            events = add_blocks(api, blocks, &body, &link_event, events);
+        }
+        Err(_) => api.ui_layout.add_text_element(source, &body, false),
+    }
+
+    api.ui_layout.close_element();
+
+    events
+}
+
+fn add_blocks<Event: Clone>(
+    api: &mut API,
+    blocks: &[Node],
+    body: &TextConfig,
+    link_event: &Option<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    for block in blocks {
+        match block {
+            Node::Heading(heading) => {
+                let scale = HEADING_SCALE[(heading.depth as usize).clamp(1, 6) - 1];
+                let heading_config = body.clone()
+                    .font_size((body.font_size as f32 * scale).round() as u16)
+                    .color(STRONG)
+                    .end();
+                events = add_paragraph(api, &heading.children, &heading_config, link_event, events);
+            }
+            Node::Paragraph(paragraph) => {
+                events = add_paragraph(api, &paragraph.children, body, link_event, events);
+            }
+            Node::List(list) => {
+                api.ui_layout.open_element();
+                api.ui_layout.configure_element(&ElementConfiguration::new()
+                    .x_grow()
+                    .direction(true)
+                    .child_gap(4)
+                );
+                for (index, item) in list.children.iter().enumerate() {
+                    let bullet = match list.ordered {
+                        true => format!("{}.", list.start.unwrap_or(1) as usize + index),
+                        false => "\u{2022}".to_string(),
+                    };
+                    api.ui_layout.open_element();
+                    api.ui_layout.configure_element(&ElementConfiguration::new()
+                        .x_grow()
+                        .child_gap(6)
+                    );
+                        api.ui_layout.add_text_element(&bullet, body, false);
+
+                        api.ui_layout.open_element();
+                        api.ui_layout.configure_element(&ElementConfiguration::new()
+                            .x_grow()
+                            .direction(true)
+                            .child_gap(4)
+                        );
+                        if let Some(item_blocks) = item.children() {
+                            events = add_blocks(api, item_blocks, body, link_event, events);
+                        }
+                        api.ui_layout.close_element();
+                    api.ui_layout.close_element();
+                }
+                api.ui_layout.close_element();
+            }
+            Node::Code(code) => {
+                api.ui_layout.open_element();
+                api.ui_layout.configure_element(&ElementConfiguration::new()
+                    .x_grow()
+                    .padding_all(8)
+                    .color(CODE_BACKGROUND)
+                    .radius_all(4.0)
+                );
+                api.ui_layout.add_text_element(&code.value, body, false);
+                api.ui_layout.close_element();
+            }
+            Node::Blockquote(quote) => {
+                api.ui_layout.open_element();
+                api.ui_layout.configure_element(&ElementConfiguration::new()
+                    .x_grow()
+                    .child_gap(8)
+                );
+                    api.ui_layout.open_element();
+                    api.ui_layout.configure_element(&ElementConfiguration::new()
+                        .x_fixed(3.0)
+                        .y_grow()
+                        .color(QUOTE_BAR)
+                    );
+                    api.ui_layout.close_element();
+
+                    api.ui_layout.open_element();
+                    api.ui_layout.configure_element(&ElementConfiguration::new()
+                        .x_grow()
+                        .direction(true)
+                        .child_gap(4)
+                    );
+                    events = add_blocks(api, &quote.children, &body.clone().color(EMPHASIS).end(), link_event, events);
+                    api.ui_layout.close_element();
+                api.ui_layout.close_element();
+            }
+            Node::ThematicBreak(_) => {
+                api.ui_layout.open_element();
+                api.ui_layout.configure_element(&ElementConfiguration::new()
+                    .x_grow()
+                    .y_fixed(1.0)
+                    .color(RULE)
+                );
+                api.ui_layout.close_element();
+            }
+            // tables, html and the rest show their text
+            other => {
+                let text = other.to_string();
+                if !text.is_empty() {
+                    api.ui_layout.add_text_element(&text, body, false);
+                }
+            }
+        }
+    }
+
+    events
+}
+
+fn add_paragraph<Event: Clone>(
+    api: &mut API,
+    inline: &[Node],
+    text_config: &TextConfig,
+    link_event: &Option<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    let mut runs = Vec::new();
+    for node in inline {
+        collect_runs(node, RunKind::Plain, &mut runs);
+    }
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new().x_grow());
+
+    for run in runs {
+        match run.kind {
+            RunKind::Plain => api.ui_layout.add_text_element(&run.text, text_config, false),
+            RunKind::Strong => api.ui_layout.add_text_element(&run.text, &text_config.clone().color(STRONG).end(), false),
+            RunKind::Emphasis => api.ui_layout.add_text_element(&run.text, &text_config.clone().color(EMPHASIS).end(), false),
+            RunKind::Code => {
+                api.ui_layout.open_element();
+                api.ui_layout.configure_element(&ElementConfiguration::new()
+                    .padding_all(2)
+                    .color(CODE_BACKGROUND)
+                    .radius_all(3.0)
+                );
+                api.ui_layout.add_text_element(&run.text, text_config, false);
+                api.ui_layout.close_element();
+            }
+            RunKind::Link(url) => {
+                api.ui_layout.open_element();
+                let hovered = api.ui_layout.hovered();
+                api.ui_layout.configure_element(&ElementConfiguration::new());
+                api.ui_layout.add_text_element(&run.text, &text_config.clone()
+                    .color(match hovered {
+                        true => LINK_HOVER,
+                        false => LINK,
+                    })
+                    .end(), false);
+                api.ui_layout.close_element();
+
+                if hovered && api.left_mouse_clicked
+                && let Some(event) = link_event.clone() {
+                    events.push((event, Some(EventContext::from_text(url))));
+                }
+            }
+        }
+    }
+
+    api.ui_layout.close_element();
+
+    events
+}
+
+/// flattens inline markdown into runs, neighbours with the same style are merged
+fn collect_runs<'doc>(node: &'doc Node, kind: RunKind<'doc>, runs: &mut Vec<Run<'doc>>) {
+    let (text, kind) = match node {
+        Node::Text(text) => (text.value.as_str(), kind),
+        Node::InlineCode(code) => (code.value.as_str(), RunKind::Code),
+        Node::Break(_) => ("\n", kind),
+        Node::Strong(strong) => {
+            for child in &strong.children {
+                collect_runs(child, RunKind::Strong, runs);
+            }
+            return
+        }
+        Node::Emphasis(emphasis) => {
+            for child in &emphasis.children {
+                collect_runs(child, RunKind::Emphasis, runs);
+            }
+            return
+        }
+        Node::Link(link) => {
+            for child in &link.children {
+                collect_runs(child, RunKind::Link(&link.url), runs);
+            }
+            return
+        }
+        other => {
+            if let Some(children) = other.children() {
+                for child in children {
+                    collect_runs(child, kind, runs);
+                }
+            }
+            return
+        }
+    };
+
+    match runs.last_mut() {
+        Some(last) if last.kind == kind => last.text.push_str(text),
+        _ => runs.push(Run { text: text.to_string(), kind }),
+    }
+}
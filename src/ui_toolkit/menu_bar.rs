@@ -0,0 +1,451 @@
+use std::fmt::Debug;
+
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::{API, DataSrc, EventContext, ui_toolkit::checkbox::Binding};
+
+const BACKGROUND: Color = Color { r: 250.0, g: 250.0, b: 250.0, a: 255.0 };
+const BORDER: Color = Color { r: 180.0, g: 180.0, b: 180.0, a: 255.0 };
+const HIGHLIGHT: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const TEXT: Color = Color { r: 30.0, g: 30.0, b: 30.0, a: 255.0 };
+const HIGHLIGHT_TEXT: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+const DISABLED_TEXT: Color = Color { r: 150.0, g: 150.0, b: 150.0, a: 255.0 };
+const CLEAR: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+/// dropdowns sit just under context menus
+const Z_INDEX: i16 = i16::MAX - 2;
+
+/// `tk` menu-bar as parsed from a layout, an `&` in a label marks the letter after it as the mnemonic
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MenuBarConfig<Event: Clone+Debug+PartialEq+Default> {
+    pub menus: Vec<MenuEntry<Event>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MenuEntry<Event: Clone+Debug+PartialEq+Default> {
+    Item {
+        label: String,
+        mnemonic: Option<char>,
+        emit: Option<DataSrc<Event>>,
+        enabled: DataSrc<bool>,
+        /// checkable items are flipped through `set_bool` when chosen
+        checked: Option<DataSrc<bool>>,
+    },
+    Menu {
+        label: String,
+        mnemonic: Option<char>,
+        enabled: DataSrc<bool>,
+        items: Vec<MenuEntry<Event>>,
+    },
+    Separator,
+}
+
+/// a menu entry with its data resolved for this frame
+pub enum MenuRow<'frame, Event> {
+    Item {
+        label: &'frame str,
+        mnemonic: Option<char>,
+        event: Option<Event>,
+        enabled: bool,
+        checked: Option<bool>,
+        binding: Option<Binding>,
+    },
+    Menu {
+        label: &'frame str,
+        mnemonic: Option<char>,
+        enabled: bool,
+        items: Vec<MenuRow<'frame, Event>>,
+    },
+    Separator,
+}
+
+impl<'frame, Event> MenuRow<'frame, Event> {
+    fn selectable(&self) -> bool {
+        match self {
+            MenuRow::Item { enabled, .. } | MenuRow::Menu { enabled, .. } => *enabled,
+            MenuRow::Separator => false,
+        }
+    }
+
+    fn mnemonic(&self) -> Option<char> {
+        match self {
+            MenuRow::Item { mnemonic, .. } | MenuRow::Menu { mnemonic, .. } => *mnemonic,
+            MenuRow::Separator => None,
+        }
+    }
+
+    fn items(&self) -> Option<&[MenuRow<'frame, Event>]> {
+        match self {
+            MenuRow::Menu { items, .. } => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// splits `&File` into `File` and `f`, `&&` is a literal ampersand
+pub fn parse_mnemonic(label: &str) -> (String, Option<char>) {
+    let mut text = String::with_capacity(label.len());
+    let mut mnemonic = None;
+    let mut characters = label.chars();
+    while let Some(character) = characters.next() {
+        if character != '&' {
+            text.push(character);
+            continue
+        }
+        match characters.next() {
+            Some('&') => text.push('&'),
+            Some(next) => {
+                if mnemonic.is_none() {
+                    mnemonic = next.to_lowercase().next();
+                }
+                text.push(next);
+            }
+            None => {}
+        }
+    }
+    (text, mnemonic)
+}
+
+/// the open menus of a bar, `path` starts with the top menu and goes down through submenus
+pub struct MenuBarState {
+    bar: u32,
+    path: Vec<usize>,
+    /// keyboard selection in the deepest open menu
+    highlight: Option<usize>,
+}
+
+fn rows_at<'rows, 'frame, Event>(menus: &'rows [MenuRow<'frame, Event>], path: &[usize]) -> Option<&'rows [MenuRow<'frame, Event>]> {
+    let mut rows = menus;
+    for index in path {
+        rows = rows.get(*index)?.items()?;
+    }
+    Some(rows)
+}
+
+fn next_selectable<Event>(rows: &[MenuRow<Event>], from: Option<usize>, forward: bool) -> Option<usize> {
+    let count = rows.len();
+    if count == 0 {
+        return None
+    }
+    let start = match (from, forward) {
+        (Some(from), _) => from,
+        (None, true) => count - 1,
+        (None, false) => 0,
+    };
+    (1..=count)
+        .map(|offset| match forward {
+            true => (start + offset) % count,
+            false => (start + count - offset % count) % count,
+        })
+        .find(|index| rows[*index].selectable())
+}
+
+/// emits the row's event and flips its check, returns false for submenus which open instead
+fn activate<Event: Clone>(api: &mut API, row: &MenuRow<Event>, events: &mut Vec<(Event, Option<EventContext>)>) -> bool {
+    let MenuRow::Item { event, enabled: true, checked, binding, .. } = row else {
+        return false
+    };
+    let context = checked.map(|checked| EventContext::from_code(!checked as u32));
+    if let Some(event) = event {
+        events.push((event.clone(), context));
+    }
+    if let Some(checked) = checked
+    && let Some(binding) = binding {
+        api.bool_writes.set(*binding, !checked);
+    }
+    true
+}
+
+/// arrows walk the menus, enter chooses, escape backs out, and letters pick by mnemonic. alt + letter opens a top menu
+fn keyboard<Event: Clone>(
+    api: &mut API,
+    id: u32,
+    menus: &[MenuRow<Event>],
+    state: &mut Option<MenuBarState>,
+    events: &mut Vec<(Event, Option<EventContext>)>,
+) {
+    let typed = api.keys_pressed.iter().find_map(|key| match key {
+        Key::Character(text) => text.chars().next().and_then(|character| character.to_lowercase().next()),
+        _ => None,
+    });
+
+    let Some(open) = state.as_mut() else {
+        if api.modifiers.alt_key()
+        && let Some(typed) = typed
+        && let Some(index) = menus.iter().position(|menu| menu.selectable() && menu.mnemonic() == Some(typed)) {
+            match menus[index].items() {
+                Some(items) => *state = Some(MenuBarState { bar: id, path: vec![index], highlight: next_selectable(items, None, true) }),
+                None => { activate(api, &menus[index], events); }
+            }
+        }
+        return
+    };
+
+    let Some(rows) = rows_at(menus, &open.path) else {
+        *state = None;
+        return
+    };
+
+    if api.named_key_pressed(NamedKey::Escape) {
+        match open.path.len() > 1 {
+            true => open.highlight = open.path.pop(),
+            false => *state = None,
+        }
+    }
+    else if api.named_key_pressed(NamedKey::ArrowDown) {
+        open.highlight = next_selectable(rows, open.highlight, true);
+    }
+    else if api.named_key_pressed(NamedKey::ArrowUp) {
+        open.highlight = next_selectable(rows, open.highlight, false);
+    }
+    else if api.named_key_pressed(NamedKey::ArrowRight) {
+        if let Some(highlight) = open.highlight
+        && let Some(items) = rows[highlight].items() {
+            open.path.push(highlight);
+            open.highlight = next_selectable(items, None, true);
+        }
+        else if let Some(next) = next_selectable(menus, Some(open.path[0]), true) {
+            open.path = vec![next];
+            open.highlight = menus[next].items().and_then(|items| next_selectable(items, None, true));
+        }
+    }
+    else if api.named_key_pressed(NamedKey::ArrowLeft) {
+        if open.path.len() > 1 {
+            open.highlight = open.path.pop();
+        }
+        else if let Some(previous) = next_selectable(menus, Some(open.path[0]), false) {
+            open.path = vec![previous];
+            open.highlight = menus[previous].items().and_then(|items| next_selectable(items, None, true));
+        }
+    }
+    else {
+        let chosen = match api.named_key_pressed(NamedKey::Enter) || api.named_key_pressed(NamedKey::Space) {
+            true => open.highlight,
+            false => typed.and_then(|typed| rows.iter().position(|row| row.selectable() && row.mnemonic() == Some(typed))),
+        };
+        if let Some(chosen) = chosen {
+            match rows[chosen].items() {
+                Some(items) => {
+                    open.path.push(chosen);
+                    open.highlight = next_selectable(items, None, true);
+                }
+                None => {
+                    if activate(api, &rows[chosen], events) {
+                        *state = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn label(api: &mut API, label: &str, color: Color) {
+    let mut text_config = TextConfig::default();
+    text_config.font_size(14).color(color).parse();
+    api.ui_layout.add_text_element(label, &text_config, false);
+}
+
+/// draws the open menu at `depth` and whatever is open below it, returns true if the pointer is over any of them
+fn dropdown<Event: Clone>(
+    api: &mut API,
+    rows: &[MenuRow<Event>],
+    depth: usize,
+    state: &mut Option<MenuBarState>,
+    events: &mut Vec<(Event, Option<EventContext>)>,
+) -> bool {
+    let mut pointer_inside = false;
+
+    api.ui_layout.open_element();
+    let mut config = ElementConfiguration::new();
+    match depth {
+        0 => config.floating_attach_to_parent_at_bottom_left(),
+        _ => config.floating_attach_to_parent_at_top_right(),
+    };
+    api.ui_layout.configure_element(&config
+        .floating()
+        .floating_attach_element_at_top_left()
+        .floating_z_index(Z_INDEX)
+        .direction(true)
+        .padding_all(4)
+        .color(BACKGROUND)
+        .border_all(1)
+        .border_color(BORDER)
+        .radius_all(4.0)
+        .end()
+    );
+    pointer_inside |= api.ui_layout.hovered();
+
+    for (index, row) in rows.iter().enumerate() {
+        let (row_label, enabled, checked, items) = match row {
+            MenuRow::Separator => {
+                api.ui_layout.open_element();
+                api.ui_layout.configure_element(&ElementConfiguration::new()
+                    .x_grow()
+                    .y_fixed(1.0)
+                    .color(BORDER)
+                    .end()
+                );
+                api.ui_layout.close_element();
+                continue
+            }
+            MenuRow::Item { label, enabled, checked, .. } => (*label, *enabled, *checked, None),
+            MenuRow::Menu { label, enabled, items, .. } => (*label, *enabled, None, Some(items)),
+        };
+
+        api.ui_layout.open_element();
+        let hovered = enabled && api.ui_layout.hovered();
+        let Some(open) = state.as_mut() else {
+            api.ui_layout.close_element();
+            break
+        };
+
+        if hovered {
+            // hovering a row closes any submenu of its siblings and opens its own
+            open.path.truncate(depth + 1);
+            open.highlight = Some(index);
+            if items.is_some() {
+                open.path.push(index);
+                open.highlight = None;
+            }
+        }
+        let submenu_open = open.path.get(depth + 1) == Some(&index);
+        let highlighted = submenu_open || (open.path.len() == depth + 1 && open.highlight == Some(index));
+
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .x_grow()
+            .padding_all(6)
+            .child_gap(8)
+            .align_children_y_center()
+            .color(match highlighted {
+                true => HIGHLIGHT,
+                false => BACKGROUND,
+            })
+            .end()
+        );
+        let text_color = match (enabled, highlighted) {
+            (false, _) => DISABLED_TEXT,
+            (true, true) => HIGHLIGHT_TEXT,
+            (true, false) => TEXT,
+        };
+
+        // the check column is always there so labels line up
+        api.ui_layout.open_element();
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .x_fixed(10.0)
+            .y_fixed(10.0)
+            .color(match checked {
+                Some(true) => text_color,
+                _ => CLEAR,
+            })
+            .radius_all(2.0)
+            .end()
+        );
+        api.ui_layout.close_element();
+
+        label(api, row_label, text_color);
+
+        if items.is_some() {
+            api.ui_layout.open_element();
+            api.ui_layout.configure_element(&ElementConfiguration::new().x_grow().end());
+            api.ui_layout.close_element();
+            label(api, ">", text_color);
+        }
+
+        if hovered && api.left_mouse_clicked && activate(api, row, events) {
+            *state = None;
+        }
+
+        if submenu_open
+        && let Some(items) = items {
+            pointer_inside |= dropdown(api, items, depth + 1, state, events);
+        }
+
+        api.ui_layout.close_element();
+    }
+
+    api.ui_layout.close_element();
+
+    pointer_inside
+}
+
+/// `config` holds whatever the layout set for the bar itself, the menus bring their own look
+pub fn menu_bar<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    menus: Vec<MenuRow<Event>>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    api.ui_layout.open_element();
+    config.align_children_y_center().parse();
+    let id = api.ui_layout.configure_element(config);
+
+    let mut state = api.menu_bar.take_if(|state| state.bar == id);
+    keyboard(api, id, &menus, &mut state, &mut events);
+
+    let mut pointer_inside = false;
+    for (index, menu) in menus.iter().enumerate() {
+        let (menu_label, enabled, items) = match menu {
+            MenuRow::Separator => continue,
+            MenuRow::Item { label, enabled, .. } => (*label, *enabled, None),
+            MenuRow::Menu { label, enabled, items, .. } => (*label, *enabled, Some(items)),
+        };
+
+        api.ui_layout.open_element();
+        let hovered = enabled && api.ui_layout.hovered();
+        pointer_inside |= hovered;
+
+        if hovered && api.left_mouse_pressed {
+            state = match (&state, items) {
+                (Some(open), _) if open.path[0] == index => None,
+                (_, Some(_)) => Some(MenuBarState { bar: id, path: vec![index], highlight: None }),
+                (_, None) => None,
+            };
+        }
+        else if hovered
+        && let Some(open) = state.as_mut()
+        && open.path[0] != index
+        && items.is_some() {
+            // moving along an open bar switches menus without another click
+            open.path = vec![index];
+            open.highlight = None;
+        }
+        if hovered && api.left_mouse_clicked && items.is_none() {
+            activate(api, menu, &mut events);
+        }
+
+        let open_here = state.as_ref().is_some_and(|open| open.path[0] == index);
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .padding_all(6)
+            .color(match open_here {
+                true => HIGHLIGHT,
+                false => CLEAR,
+            })
+            .radius_all(3.0)
+            .end()
+        );
+        label(api, menu_label, match (enabled, open_here) {
+            (false, _) => DISABLED_TEXT,
+            (true, true) => HIGHLIGHT_TEXT,
+            (true, false) => TEXT,
+        });
+
+        if open_here
+        && let Some(items) = items {
+            pointer_inside |= dropdown(api, items, 0, &mut state, &mut events);
+        }
+
+        api.ui_layout.close_element();
+    }
+
+    if !pointer_inside && (api.left_mouse_pressed || api.right_mouse_pressed) {
+        state = None;
+    }
+    if let Some(state) = state {
+        api.menu_bar = Some(state);
+    }
+
+    api.ui_layout.close_element();
+
+    events
+}
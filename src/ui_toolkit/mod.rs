@@ -1,5 +1,21 @@
 
+pub mod builder;
+pub mod button;
+pub mod checkbox;
+pub mod context_menu;
+pub mod drag_value;
+pub mod error_overlay;
+pub mod expression;
+pub mod layout_error;
+pub mod markdown_view;
+pub mod menu_bar;
+pub mod progress;
+pub mod slider;
+pub mod stats_overlay;
+#[cfg(feature = "svg")]
+pub mod svg;
 pub mod textbox;
+pub mod tooltip;
 pub mod treeview;
 
 pub mod ui_renderer;
@@ -1,11 +1,15 @@
 use std::marker::PhantomData;
-use std::{collections::HashMap, fmt::Debug, str::FromStr};
+use std::sync::{Mutex, atomic::{AtomicBool, Ordering}};
+use std::{collections::HashMap, fmt::{Debug, Write}, str::FromStr};
+
+use bumpalo::Bump;
 
 use symbol_table::GlobalSymbol;
 //use winit::window::Cursor;
 
 use crate::{
     UIImageDescriptor,
+    ui_toolkit::ui_renderer::{DEPTH_TESTED, UNSNAPPED},
     ParserDataAccess,
     Layout,
     DataSrc,
@@ -14,6 +18,20 @@ use crate::{
     Config,
     CustomElement,
     ui_toolkit::treeview::treeview,
+    ui_toolkit::button::{button, Button, ButtonColors},
+    ui_toolkit::checkbox::{toggle, Toggle},
+    ui_toolkit::slider::{slider, Slider},
+    ui_toolkit::drag_value::{drag_value, DragValue},
+    ui_toolkit::context_menu::{context_menu, ContextMenuItem},
+    ui_toolkit::menu_bar::{menu_bar, MenuEntry, MenuRow},
+    ui_toolkit::progress::{progress_bar, spinner},
+    ui_toolkit::markdown_view::markdown_view,
+    ui_toolkit::textbox::{text_editor, TextEditor},
+    ui_toolkit::tooltip::{tooltip, DEFAULT_DELAY},
+    ui_toolkit::error_overlay::error_overlay,
+    ui_toolkit::stats_overlay::stats_overlay,
+    ui_toolkit::expression::{self, Value},
+    Widget,
     API,
     EventContext,
     EventHandler
@@ -22,6 +40,114 @@ use crate::{
 use telera_layout::{Color, ElementConfiguration, TextConfig};
 
 const DEFAULT_TEXT: &str = ":(";
+/// dialogs float above anything a page can set with `z-index`
+const DIALOG_Z_INDEX: i16 = 10000;
+const DIALOG_SCRIM: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 110.0 };
+
+/// the os color scheme, shared by every window and read while resolving `system.dark_mode`
+static SYSTEM_DARK_MODE: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn system_dark_mode() -> bool {
+    SYSTEM_DARK_MODE.load(Ordering::Relaxed)
+}
+
+pub(crate) fn set_system_dark_mode(dark: bool) {
+    SYSTEM_DARK_MODE.store(dark, Ordering::Relaxed);
+}
+
+/// whether the app's undo stack has something to undo and redo, read as `system.can_undo` and `system.can_redo`
+static CAN_UNDO: AtomicBool = AtomicBool::new(false);
+static CAN_REDO: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_undo_state(can_undo: bool, can_redo: bool) {
+    CAN_UNDO.store(can_undo, Ordering::Relaxed);
+    CAN_REDO.store(can_redo, Ordering::Relaxed);
+}
+
+/// the viewport being laid out, in layout units
+static LAYOUT_SIZE: Mutex<(f32, f32)> = Mutex::new((0.0, 0.0));
+
+pub(crate) fn set_layout_size(width: f32, height: f32) {
+    *LAYOUT_SIZE.lock().unwrap() = (width, height);
+}
+
+/// numbers every layout can read, the app's own values of the same name come first.
+/// `index` is the position of the list item being laid out
+fn system_numeric(name: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>) -> Option<f32> {
+    match name.as_str() {
+        "viewport.width" => Some(LAYOUT_SIZE.lock().unwrap().0),
+        "viewport.height" => Some(LAYOUT_SIZE.lock().unwrap().1),
+        "index" => list_data.map(|(_, index)| index as f32),
+        _ => None,
+    }
+}
+
+/// bools every layout can read, the app's own values of the same name come first
+fn system_bool(name: &GlobalSymbol) -> Option<bool> {
+    match name.as_str() {
+        "system.dark_mode" => Some(system_dark_mode()),
+        "system.can_undo" => Some(CAN_UNDO.load(Ordering::Relaxed)),
+        "system.can_redo" => Some(CAN_REDO.load(Ordering::Relaxed)),
+        _ => None,
+    }
+}
+
+/// the value of a binding named after an expression
+fn expression_value<Event, UserApp>(
+    name: &GlobalSymbol,
+    locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
+    user_app: &UserApp,
+    list_data: &Option<(GlobalSymbol, usize)>
+) -> Option<Value>
+where
+    Event: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    <Event as FromStr>::Err: Debug+Default,
+    UserApp: ParserDataAccess<Event>
+{
+    let expression = expression::registered(name)?;
+    Some(expression.evaluate(&|name| expression_operand(name, locals, user_app, list_data)))
+}
+
+/// a name in an expression, the app's numbers are looked for before its bools
+fn expression_operand<Event, UserApp>(
+    name: &GlobalSymbol,
+    locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
+    user_app: &UserApp,
+    list_data: &Option<(GlobalSymbol, usize)>
+) -> Value
+where
+    Event: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    <Event as FromStr>::Err: Debug+Default,
+    UserApp: ParserDataAccess<Event>
+{
+    if let Some(locals) = locals
+    && let Some(local) = locals.get(name) {
+        return match local {
+            DataSrc::Dynamic(local) => expression_operand::<Event, UserApp>(local, None, user_app, list_data),
+            DataSrc::Static(Declaration::Numeric(value)) => Value::Number(*value),
+            DataSrc::Static(Declaration::Bool(value)) => Value::Bool(*value),
+            DataSrc::Static(_) => Value::Number(0.0),
+        }
+    }
+    if let Some(value) = user_app.get_numeric(name, list_data) {
+        Value::Number(value)
+    }
+    else if let Some(value) = user_app.get_bool(name, list_data) {
+        Value::Bool(value)
+    }
+    else if let Some(value) = system_numeric(name, list_data) {
+        Value::Number(value)
+    }
+    else if let Some(value) = system_bool(name) {
+        Value::Bool(value)
+    }
+    else if let Some(value) = expression_value(name, None, user_app, list_data) {
+        value
+    }
+    else {
+        Value::Number(0.0)
+    }
+}
 
 pub struct Binder<Event,UserApp>
 where
@@ -54,6 +180,15 @@ where
         }
     }
 
+    /// adds the page or replaces the one with the same name
+    pub fn insert_page(&mut self, name: &str, page: Vec<Layout<Event>>) {
+        self.pages.insert(name.to_string(), page);
+    }
+
+    pub fn remove_page(&mut self, name: &str) {
+        self.pages.remove(name);
+    }
+
     pub fn add_reusable(&mut self, name: &str, page: Vec<Layout<Event>>) {
         let name = GlobalSymbol::new(name);
         if self.reusable.get(&name).is_none() {
@@ -61,6 +196,10 @@ where
         }
     }
 
+    pub fn remove_reusable(&mut self, name: &str) {
+        self.reusable.remove(&GlobalSymbol::new(name));
+    }
+
     pub fn replace_page(&mut self, name: &str, page: Vec<Layout<Event>>) -> Result<(), ()> {
         if self.pages.get(name).is_some() {
             self.pages.remove(name);
@@ -87,15 +226,41 @@ where
         user_app: &mut UserApp,
     ) -> Result<Vec::<(Event, Option<EventContext>)>, ()>
     where <Event as FromStr>::Err: Default  {
-        if let Some(viewport) = api.viewports.get_mut(&window_id)
-        && let Some(layout_commands) = self.pages.get_mut(&viewport.page) {
+        let Some(viewport) = api.viewports.get(&window_id) else {
+            return Err(())
+        };
+        let page = viewport.page.clone();
+        let dialogs = viewport.dialogs.clone();
+        self.set_named_page(&page, &dialogs, api, user_app)
+    }
+
+    /// lays out `page` with `dialogs` over it, for when there's no viewport to take them from
+    pub(crate) fn set_named_page(
+        &mut self,
+        page: &str,
+        dialogs: &[String],
+        api: &mut API,
+        user_app: &mut UserApp,
+    ) -> Result<Vec::<(Event, Option<EventContext>)>, ()>
+    where <Event as FromStr>::Err: Default  {
+        if let Some(layout_commands) = self.pages.get(page) {
 
             //println!("{:#?}\n\n", &layout_commands);
 
-            let (events, _pointer) = set_layout(
+            api.opacity.clear();
+            // what configs resolve and formatted text live here until the frame is drawn, the commands themselves aren't changed
+            let mut arena = std::mem::take(&mut api.frame_arena);
+            arena.reset();
+            let mut held_input = match dialogs.is_empty() {
+                true => None,
+                false => Some(api.hold_input()),
+            };
+
+            let (mut events, _pointer) = set_layout(
                 api,
                 layout_commands,
-                &mut self.reusable,
+                &self.reusable,
+                &arena,
                 None,
                 None,
                 None,
@@ -105,16 +270,256 @@ where
                 winit::window::CursorIcon::Default
             );
 
+            for (layer, dialog) in dialogs.iter().enumerate() {
+                let Some(dialog_commands) = self.pages.get(dialog) else {
+                    continue
+                };
+                if layer + 1 == dialogs.len()
+                && let Some(held_input) = held_input.take() {
+                    api.release_input(held_input);
+                }
+
+                // a screen sized floating root, it captures the pointer so nothing underneath is hovered
+                api.ui_layout.open_element();
+                api.ui_layout.configure_element(&ElementConfiguration::new()
+                    .floating()
+                    .floating_attach_to_root()
+                    .floating_z_index(DIALOG_Z_INDEX + layer as i16)
+                    .x_grow()
+                    .y_grow()
+                    .color(DIALOG_SCRIM)
+                    .align_children_x_center()
+                    .align_children_y_center()
+                    .end()
+                );
+                (events, _) = set_layout(
+                    api,
+                    dialog_commands,
+                    &self.reusable,
+                    &arena,
+                    None,
+                    None,
+                    None,
+                    None,
+                    user_app,
+                    events,
+                    winit::window::CursorIcon::Default
+                );
+                api.ui_layout.close_element();
+            }
+            if let Some(held_input) = held_input {
+                api.release_input(held_input);
+            }
+            api.frame_arena = arena;
+            error_overlay(api, page);
+            if api.show_frame_stats {
+                stats_overlay(api);
+            }
+
+            for ((name, list_data), value) in api.bool_writes.drain() {
+                user_app.set_bool(&name, &list_data, value);
+            }
+            for ((name, list_data), value) in api.numeric_writes.drain(..) {
+                user_app.set_numeric(&name, &list_data, value);
+            }
+            for ((name, list_data), value) in api.text_writes.drain(..) {
+                user_app.set_text(&name, &list_data, value);
+            }
+
             return Ok(events)
         }
         Err(())
     }
 }
 
+/// the app value a widget writes back to, following reusable parameters. static values aren't writable
+fn binding<T: Default, Event: Clone+Debug+PartialEq+Default>(
+    src: &DataSrc<T>,
+    locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
+    list_data: &Option<(GlobalSymbol, usize)>,
+) -> Option<(GlobalSymbol, Option<(GlobalSymbol, usize)>)> {
+    let DataSrc::Dynamic(name) = src else {
+        return None
+    };
+    match locals.and_then(|locals| locals.get(name)) {
+        Some(DataSrc::Dynamic(local)) => Some((*local, *list_data)),
+        Some(DataSrc::Static(_)) => None,
+        None => Some((*name, *list_data)),
+    }
+}
+
+/// text is shaped and reordered a line at a time and each line takes its direction from its first strong letter,
+/// a right to left mark at the start of every word makes whichever word a wrapped line starts with right to left
+fn right_to_left(text: &str) -> String {
+    format!("\u{200F}{}", text.replace(' ', " \u{200F}"))
+}
+
+/// `color` faded by the opacity of the element being configured
+fn faded(color: Color, opacity: &[f32]) -> Color {
+    Color { a: color.a * opacity.last().copied().unwrap_or(1.0), ..color }
+}
+
+/// a font color that was never set has no alpha but draws opaque, it fades from opaque.
+/// faded text keeps a sliver of alpha so it isn't taken for unset
+fn faded_text(text_config: &TextConfig, opacity: f32) -> TextConfig {
+    let color = text_config.color;
+    let alpha = match color.a {
+        0.0 => 255.0,
+        alpha => alpha,
+    };
+    text_config.clone().color(Color { a: (alpha * opacity).max(1.0), ..color }).end()
+}
+
+/// a text element faded by the opacity of the element it's in
+fn add_text(api: &mut API, text: &str, text_config: &TextConfig, rtl: bool) {
+    let faded;
+    let text_config = match api.opacity.last() {
+        Some(opacity) if *opacity < 1.0 => {
+            faded = faded_text(text_config, *opacity);
+            &faded
+        }
+        _ => text_config,
+    };
+    match rtl {
+        true => api.ui_layout.add_text_element(&right_to_left(text), text_config, false),
+        false => api.ui_layout.add_text_element(text, text_config, false),
+    }
+}
+
+/// the app's text under `name`, or its number with `decimals` places, or its bool
+fn format_value<Event, UserApp>(
+    name: &GlobalSymbol,
+    decimals: Option<usize>,
+    locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
+    user_app: &UserApp,
+    list_data: &Option<(GlobalSymbol, usize)>
+) -> String
+where
+    Event: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    <Event as FromStr>::Err: Debug+Default,
+    UserApp: ParserDataAccess<Event>
+{
+    let mut text = String::new();
+    write_value(&mut text, name, decimals, locals, user_app, list_data);
+    text
+}
+
+/// `format_value` into any text, formatted text elements write straight into the frame arena
+fn write_value<Event, UserApp>(
+    text: &mut impl Write,
+    name: &GlobalSymbol,
+    decimals: Option<usize>,
+    locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
+    user_app: &UserApp,
+    list_data: &Option<(GlobalSymbol, usize)>
+)
+where
+    Event: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    <Event as FromStr>::Err: Debug+Default,
+    UserApp: ParserDataAccess<Event>
+{
+    let text_name = match locals.and_then(|locals| locals.get(name)) {
+        Some(DataSrc::Static(Declaration::Text(value))) => {
+            let _ = text.write_str(value);
+            return
+        }
+        Some(DataSrc::Dynamic(local)) => local,
+        _ => name,
+    };
+    if let Some(value) = user_app.get_text(text_name, list_data) {
+        let _ = text.write_str(value);
+        return
+    }
+    let _ = match (expression_operand(name, locals, user_app, list_data), decimals) {
+        (Value::Number(number), Some(decimals)) => write!(text, "{:.*}", decimals, number),
+        (Value::Number(number), None) => write!(text, "{}", number),
+        (Value::Bool(value), _) => write!(text, "{}", value),
+    };
+}
+
+/// what a list's items are called in `list_data`. a list nested in an item of `orders` with the source `orders.items`
+/// names its items `orders[3].items`, any other source names them after itself
+fn list_path(src: &GlobalSymbol, list_data: &Option<(GlobalSymbol, usize)>) -> GlobalSymbol {
+    if let Some((outer, index)) = list_data {
+        let plain = outer.as_str().split('[')
+            .enumerate()
+            .map(|(part, text)| match part {
+                0 => text,
+                _ => text.split_once(']').map(|(_, rest)| rest).unwrap_or(text),
+            })
+            .collect::<String>();
+        if let Some(field) = src.as_str().strip_prefix(&plain)
+        && field.starts_with('.') {
+            return GlobalSymbol::new(format!("{}[{}]{}", outer, index, field))
+        }
+    }
+    *src
+}
+
+/// the id a keyed list item's first element gets, unique to the list and the key's value
+fn item_key<Event, UserApp>(
+    key: &GlobalSymbol,
+    locals: &HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>,
+    user_app: &UserApp,
+    item: (GlobalSymbol, usize),
+) -> String
+where
+    Event: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    <Event as FromStr>::Err: Debug+Default,
+    UserApp: ParserDataAccess<Event>
+{
+    format!("{}#{}", item.0, format_value(key, None, Some(locals), user_app, &Some(item)))
+}
+
+fn list_spacer(api: &mut API, height: f32) {
+    if height <= 0.0 {
+        return
+    }
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new().y_fixed(height).end());
+    api.ui_layout.close_element();
+}
+
+fn resolve_menu<'frame, Event, UserApp>(
+    entries: &'frame [MenuEntry<Event>],
+    locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
+    user_app: &UserApp,
+    list_data: &Option<(GlobalSymbol, usize)>,
+) -> Vec<MenuRow<'frame, Event>>
+where
+    Event: FromStr+Clone+PartialEq+Default+Debug+EventHandler<UserApplication = UserApp>,
+    <Event as FromStr>::Err: Debug+Default,
+    UserApp: ParserDataAccess<Event>
+{
+    entries.iter().map(|entry| match entry {
+        MenuEntry::Item { label, mnemonic, emit, enabled, checked } => MenuRow::Item {
+            label,
+            mnemonic: *mnemonic,
+            event: emit.as_ref().map(|event| Event::resolve_src(event, locals, user_app, list_data)),
+            enabled: bool::resolve_src(enabled, locals, user_app, list_data),
+            checked: checked.as_ref().map(|checked| bool::resolve_src(checked, locals, user_app, list_data)),
+            binding: checked.as_ref().and_then(|checked| binding(checked, locals, list_data)),
+        },
+        MenuEntry::Menu { label, mnemonic, enabled, items } => MenuRow::Menu {
+            label,
+            mnemonic: *mnemonic,
+            enabled: bool::resolve_src(enabled, locals, user_app, list_data),
+            items: resolve_menu(items, locals, user_app, list_data),
+        },
+        MenuEntry::Separator => MenuRow::Separator,
+    }).collect()
+}
+
+/// events sent from inside a list item carry the item's index in `code2`
+fn event_context(list_data: &Option<(GlobalSymbol, usize)>) -> Option<EventContext> {
+    list_data.map(|(_, index)| EventContext::from_code2(index as u32))
+}
+
 fn set_layout<'render_pass, Event, UserApp>(
     api: &mut API,
-    commands: &mut [Layout<Event>],
-    reusables: &mut HashMap<GlobalSymbol, Vec<Layout<Event>>>,
+    commands: &[Layout<Event>],
+    reusables: &HashMap<GlobalSymbol, Vec<Layout<Event>>>,
+    arena: &Bump,
     locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
     list_data: Option<(GlobalSymbol, usize)>,
     config: Option<&mut ElementConfiguration>,
@@ -131,11 +536,27 @@ where
     let mut nesting_level: u32 = 0;
     let mut skip: Option<u32> = None;
 
-    let mut recursive_commands = Vec::<Layout<Event>>::new();
     let mut recursive_call_stack = HashMap::<GlobalSymbol, &DataSrc<Declaration<Event>>>::new();
     let mut collect_declarations = false;
 
-    let mut collect_list_commands = false;
+    // the value of each match being laid out and whether one of its cases has been
+    let mut matches = Vec::<(String, bool)>::new();
+
+    // lists open around the command being collected, a list's items are laid out once it closes
+    let mut list_depth: u32 = 0;
+    // where the outermost list's item commands start, they run up to its closing command
+    let mut list_start: usize = 0;
+
+    // the element being configured shows this once it's been hovered for the delay
+    let mut tooltip_text = None;
+    let mut tooltip_delay = DEFAULT_DELAY;
+    let mut text_rtl = false;
+    // the id of the element being configured, the innermost hovered one is reported by `api.hovered_element_id`
+    let mut element_name: Option<String> = None;
+    // the event the hovered or clicked block being laid out sent, its configs can fill in the context
+    let mut emitted: Option<usize> = None;
+    // a hidden element stays transparent whatever opacity it's given
+    let mut hidden = false;
     
     let mut config = match config {
         None => &mut ElementConfiguration::default(),
@@ -148,20 +569,23 @@ where
     };
 
     #[allow(unused_variables)]
-    for (index, command) in commands.iter_mut().enumerate() {
-        if collect_list_commands {
+    for (index, command) in commands.iter().enumerate() {
+        if list_depth > 0 {
             match command {
-                Layout::Element(flow_command) => {
-                    if let Element::ListClosed(_) = flow_command {
-                        collect_list_commands = false;
+                Layout::Element(Element::ListOpened) => list_depth += 1,
+                Layout::Element(Element::ListClosed { .. } | Element::VirtualListClosed { .. } | Element::RepeatClosed { .. }) => list_depth -= 1,
+                _ => {}
+            }
+            // the closing command of the outermost list lays the items out
+            if list_depth > 0 {
+                match command {
+                    Layout::Declaration { name, value } if collect_declarations => {
+                        recursive_call_stack.insert(*name, value);
+                        list_start = index + 1;
                     }
+                    _ => collect_declarations = false,
                 }
-                Layout::Declaration{name:_,value:_} => {}
-                other => {
-                    collect_declarations = false;
-                    recursive_commands.push(other.clone());
-                    continue;
-                }
+                continue;
             }
         }
 
@@ -190,7 +614,45 @@ where
                             }
                         }
                     }
+                    Element::MatchOpened { src } => {
+                        if skip.is_none() {
+                            matches.push((format_value(src, None, locals, user_app, &list_data), false));
+                        }
+                        nesting_level += 1;
+                    }
+                    Element::MatchClosed => {
+                        nesting_level -= 1;
+                        if skip.is_none() {
+                            matches.pop();
+                        }
+                    }
+                    Element::CaseOpened { is } => {
+                        if skip.is_none() {
+                            match matches.last_mut() {
+                                Some((value, matched)) if !*matched && value == is => *matched = true,
+                                _ => skip = Some(nesting_level),
+                            }
+                        }
+                        nesting_level += 1;
+                    }
+                    Element::DefaultOpened => {
+                        if skip.is_none() {
+                            match matches.last_mut() {
+                                Some((_, matched)) if !*matched => *matched = true,
+                                _ => skip = Some(nesting_level),
+                            }
+                        }
+                        nesting_level += 1;
+                    }
+                    Element::CaseClosed => {
+                        nesting_level -= 1;
+                        if let Some(skip_level) = skip
+                        && skip_level >= nesting_level {
+                            skip = None;
+                        }
+                    }
                     Element::HoverOpened { event } => {
+                        emitted = None;
                         if skip.is_none() {
                             skip = Some(nesting_level);
 
@@ -198,7 +660,8 @@ where
                                 skip = None;
 
                                 if let Some(event) = event {
-                                    events.push((Event::resolve_src(event, locals, user_app, &list_data),None));
+                                    emitted = Some(events.len());
+                                    events.push((Event::resolve_src(event, locals, user_app, &list_data), event_context(&list_data)));
                                 }
                             }
                         }
@@ -206,6 +669,7 @@ where
                     }
                     Element::HoverClosed => {
                         nesting_level -= 1;
+                        emitted = None;
 
                         if let Some(skip_level) = skip {
                             if skip_level == nesting_level{
@@ -214,6 +678,7 @@ where
                         }
                     }
                     Element::LeftClickedOpened { event } => {
+                        emitted = None;
                         //println!("event at click opened: {:?}", event);
                         if skip.is_none() {
                             skip = Some(nesting_level);
@@ -222,7 +687,8 @@ where
                                 skip = None;
 
                                 if let Some(event) = event {
-                                    events.push((Event::resolve_src(event, locals, user_app, &list_data),None));
+                                    emitted = Some(events.len());
+                                    events.push((Event::resolve_src(event, locals, user_app, &list_data), event_context(&list_data)));
                                 }
                             }
                         }
@@ -230,6 +696,7 @@ where
                     }
                     Element::LeftClickedClosed => {
                         nesting_level -= 1;
+                        emitted = None;
 
                         if let Some(skip_level) = skip {
                             if skip_level == nesting_level{
@@ -238,6 +705,7 @@ where
                         }
                     }
                     Element::RightClickedOpened { event } => {
+                        emitted = None;
                         if skip.is_none() {
                             skip = Some(nesting_level);
 
@@ -245,7 +713,8 @@ where
                                 skip = None;
 
                                 if let Some(event) = event {
-                                    events.push((Event::resolve_src(event, locals, user_app, &list_data),None));
+                                    emitted = Some(events.len());
+                                    events.push((Event::resolve_src(event, locals, user_app, &list_data), event_context(&list_data)));
                                 }
                             }
                         }
@@ -253,6 +722,7 @@ where
                     }
                     Element::RightClickedClosed => {
                         nesting_level -= 1;
+                        emitted = None;
 
                         if let Some(skip_level) = skip {
                             if skip_level == nesting_level{
@@ -269,26 +739,29 @@ where
                         nesting_level += 1;
 
                         if skip.is_none() {
-                            recursive_commands.clear();
                             recursive_call_stack.clear();
-                            collect_list_commands = true;
+                            list_depth = 1;
+                            list_start = index + 1;
                             collect_declarations = true;
                         }
                         
                     }
-                    Element::ListClosed(src) => {
+                    Element::ListClosed { src, key } => {
                         nesting_level -= 1;
 
                         if skip.is_none(){
 
-                            if let Some(length) = user_app.get_list_length(src, &None) {
+                            if let Some(length) = user_app.get_list_length(src, &list_data) {
+                                let path = list_path(src, &list_data);
                                 for index in 0..length {
+                                    api.item_key = key.map(|key| item_key(&key, &recursive_call_stack, user_app, (path, index)));
                                     (events, pointer) = set_layout(
                                         api,
-                                        &mut recursive_commands, 
+                                        &commands[list_start..index], 
                                         reusables,
+                                        arena,
                                         Some(&recursive_call_stack), 
-                                        Some((*src, index)), 
+                                        Some((path, index)), 
                                         None, 
                                         None, 
                                         user_app,
@@ -296,21 +769,80 @@ where
                                         pointer
                                     );
                                 }
+                                api.item_key = None;
+                            }
+                        }
+                    }
+                    Element::VirtualListClosed { src, item_height, key } => {
+                        nesting_level -= 1;
+
+                        if skip.is_none()
+                        && let Some(length) = user_app.get_list_length(src, &list_data) {
+                            let path = list_path(src, &list_data);
+                            // the list sits directly in its scroll container, so the open element's offset is the list's
+                            let scroll_offset = api.ui_layout.get_scroll_offset();
+                            let item_height = item_height.max(1.0);
+                            let view_height = api.scene_renderer.viewport_size.1 / api.dpi_scale;
+                            let first = ((-scroll_offset.y / item_height).floor().max(0.0) as usize).min(length);
+                            let last = (first + (view_height / item_height).ceil() as usize + 1).min(length);
+
+                            // spacers stand in for the items out of view so the scroll range stays the same
+                            list_spacer(api, first as f32 * item_height);
+                            for index in first..last {
+                                api.item_key = key.map(|key| item_key(&key, &recursive_call_stack, user_app, (path, index)));
+                                (events, pointer) = set_layout(
+                                    api,
+                                    &commands[list_start..index],
+                                    reusables,
+                                    arena,
+                                    Some(&recursive_call_stack),
+                                    Some((path, index)),
+                                    None,
+                                    None,
+                                    user_app,
+                                    events,
+                                    pointer
+                                );
+                            }
+                            api.item_key = None;
+                            list_spacer(api, (length - last) as f32 * item_height);
+                        }
+                    }
+                    Element::RepeatClosed { times, index: index_name } => {
+                        nesting_level -= 1;
+
+                        if skip.is_none() {
+                            // the counts live in the arena, so one map of locals serves every pass
+                            let mut item_locals = recursive_call_stack.clone();
+                            for count in 0..u16::resolve_src(times, locals, user_app, &list_data) {
+                                item_locals.insert(*index_name, &*arena.alloc(DataSrc::Static(Declaration::Numeric(count as f32))));
+                                (events, pointer) = set_layout(
+                                    api,
+                                    &commands[list_start..index],
+                                    reusables,
+                                    arena,
+                                    Some(&item_locals),
+                                    list_data,
+                                    None,
+                                    None,
+                                    user_app,
+                                    events,
+                                    pointer
+                                );
                             }
                         }
                     }
                     Element::ElementOpened { id:_ } => {
                         nesting_level += 1;
+                        api.opacity.push(api.opacity.last().copied().unwrap_or(1.0));
 
                         if skip.is_none() {
                             api.ui_layout.open_element();
-                            if api.ui_layout.hovered() {
-                                let x = api.ui_layout.get_element_id("hi");
-                            }
                         }
                     }
                     Element::ElementClosed => {
                         nesting_level -= 1;
+                        api.opacity.pop();
 
                         if skip.is_none() {
                             api.ui_layout.close_element();
@@ -318,16 +850,15 @@ where
                     }
                     Element::CircleOpened { id } => {
                         nesting_level += 1;
+                        api.opacity.push(api.opacity.last().copied().unwrap_or(1.0));
 
                         if skip.is_none() {
                             api.ui_layout.open_element();
-                            if api.ui_layout.hovered() {
-                                let x = api.ui_layout.get_element_id("hi");
-                            }
                         }
                     }
                     Element::CircleClosed => {
                         nesting_level -= 1;
+                        api.opacity.pop();
 
                         if skip.is_none() {
                             api.ui_layout.close_element();
@@ -335,16 +866,15 @@ where
                     }
                     Element::LineOpened { id } => {
                         nesting_level += 1;
+                        api.opacity.push(api.opacity.last().copied().unwrap_or(1.0));
 
                         if skip.is_none() {
                             api.ui_layout.open_element();
-                            if api.ui_layout.hovered() {
-                                let x = api.ui_layout.get_element_id("hi");
-                            }
                         }
                     }
                     Element::LineClosed => {
                         nesting_level -= 1;
+                        api.opacity.pop();
 
                         if skip.is_none() {
                             api.ui_layout.close_element();
@@ -355,6 +885,13 @@ where
         
                         if skip.is_none() {
                             *config = ElementConfiguration::default();
+                            element_name = api.item_key.take();
+                            if let Some(key) = &element_name {
+                                config.id(key);
+                            }
+                            tooltip_text = None;
+                            tooltip_delay = DEFAULT_DELAY;
+                            hidden = false;
                         }
                     }
                     Element::ConfigClosed => {
@@ -368,6 +905,15 @@ where
                                 api.focus = id;
                                 //println!("focus: {:?}", api.focus);
                             }
+                            if let Some(name) = element_name.take() {
+                                if api.ui_layout.hovered() {
+                                    api.hovered_element = Some(name.clone());
+                                }
+                                api.element_ids.insert(name, id);
+                            }
+                            if let Some(text) = tooltip_text.take() {
+                                tooltip(api, id, text, tooltip_delay);
+                            }
                         }
                     }
                     Element::TextElementOpened => nesting_level += 1,
@@ -375,7 +921,15 @@ where
                         nesting_level -= 1;
                         if skip.is_none() {
                             let text_content = String::resolve_src(content, locals, user_app, &list_data);
-                            api.ui_layout.add_text_element(text_content, &text_config, false);
+                            add_text(api, text_content, text_config, text_rtl);
+                        }
+                    }
+                    Element::TextElementFormatted(format) => {
+                        nesting_level -= 1;
+                        if skip.is_none() {
+                            let mut text_content = bumpalo::collections::String::new_in(arena);
+                            format.write(&mut text_content, |text, name, decimals| write_value(text, name, decimals, locals, user_app, &list_data));
+                            add_text(api, &text_content, text_config, text_rtl);
                         }
                     }
                     Element::TextConfigOpened => {
@@ -383,6 +937,7 @@ where
 
                         if skip.is_none() {
                             *text_config = TextConfig::default();
+                            text_rtl = false;
                         }
                     }
                     Element::TextConfigClosed => {
@@ -392,7 +947,6 @@ where
                         nesting_level += 1;
 
                         if skip.is_none() {
-                            recursive_commands.clear();
                             recursive_call_stack.clear();
                             collect_declarations = true;
                         }
@@ -406,14 +960,12 @@ where
                             //println!("try to use: {:?}", recursive_source);
                             if let Some(reusable) = reusables.get(src){
                                 //println!("use: {:?}", recursive_source);
-                                for command in reusable.iter() {
-                                    recursive_commands.push(command.clone());
-                                }
                                 if recursive_call_stack.len() > 0 {
                                     (events, pointer) = set_layout(
                                         api,
-                                        &mut recursive_commands,
+                                        reusable,
                                         reusables,
+                                        arena,
                                         Some(&recursive_call_stack), 
                                         None,
                                         Some(&mut config),
@@ -426,8 +978,9 @@ where
                                 else {
                                     (events, pointer) = set_layout(
                                         api,
-                                        &mut recursive_commands,
+                                        reusable,
                                         reusables,
+                                        arena,
                                         None,
                                         None,
                                         Some(&mut config),
@@ -445,24 +998,213 @@ where
                         nesting_level += 1;
 
                         if skip.is_none() {
-                            recursive_commands.clear();
                             recursive_call_stack.clear();
                             collect_declarations = true;
                         }
                     }
-                    Element::TreeViewClosed(src) => {
+                    Element::TreeViewClosed{src, selection} => {
                         nesting_level -= 1;
 
                         if skip.is_none() {
                             collect_declarations = false;
-                            events = treeview(src, &list_data, api, user_app, events);
+                            events = treeview(src, *selection, &list_data, api, user_app, events);
+                        }
+                    }
+                    Element::ToolkitOpened => {
+                        nesting_level += 1;
+
+                        if skip.is_none() {
+                            *config = ElementConfiguration::default();
+                            *text_config = TextConfig::default();
+                        }
+                    }
+                    Element::ToolkitClosed(widget) => {
+                        nesting_level -= 1;
+
+                        if skip.is_none() {
+                            let radio = matches!(widget, Widget::Radio(_));
+                            match widget {
+                                Widget::Button(button_config) => {
+                                    let resolve_color = |color: &Option<DataSrc<Color>>, fallback: Color| match color {
+                                        Some(color) => Color::resolve_src(color, locals, user_app, &list_data),
+                                        None => fallback,
+                                    };
+                                    let defaults = ButtonColors::default();
+                                    let normal = resolve_color(&button_config.color, defaults.normal);
+                                    // a custom base color is used for every state that isn't styled on its own
+                                    let fallback = |default: Color| match button_config.color {
+                                        Some(_) => normal,
+                                        None => default,
+                                    };
+                                    let colors = ButtonColors {
+                                        normal,
+                                        hover: resolve_color(&button_config.hover_color, fallback(defaults.hover)),
+                                        pressed: resolve_color(&button_config.pressed_color, fallback(defaults.pressed)),
+                                        disabled: resolve_color(&button_config.disabled_color, fallback(defaults.disabled)),
+                                    };
+                                    events = button(
+                                        api,
+                                        config,
+                                        text_config,
+                                        Button {
+                                            label: String::resolve_src(&button_config.label, locals, user_app, &list_data),
+                                            event: button_config.emit.as_ref().map(|event| Event::resolve_src(event, locals, user_app, &list_data)),
+                                            disabled: bool::resolve_src(&button_config.disabled, locals, user_app, &list_data),
+                                            colors,
+                                        },
+                                        events
+                                    );
+                                }
+                                Widget::Slider(slider_config) => {
+                                    let new_value;
+                                    (events, new_value) = slider(
+                                        api,
+                                        config,
+                                        Slider {
+                                            value: f32::resolve_src(&slider_config.value, locals, user_app, &list_data),
+                                            min: f32::resolve_src(&slider_config.min, locals, user_app, &list_data),
+                                            max: f32::resolve_src(&slider_config.max, locals, user_app, &list_data),
+                                            step: f32::resolve_src(&slider_config.step, locals, user_app, &list_data),
+                                            length: f32::resolve_src(&slider_config.length, locals, user_app, &list_data),
+                                            vertical: slider_config.vertical,
+                                            event: slider_config.emit.as_ref().map(|event| Event::resolve_src(event, locals, user_app, &list_data)),
+                                            disabled: bool::resolve_src(&slider_config.disabled, locals, user_app, &list_data),
+                                        },
+                                        events
+                                    );
+
+                                    if let Some(value) = new_value
+                                    && let Some(bound_to) = binding(&slider_config.value, locals, &list_data) {
+                                        api.numeric_writes.push((bound_to, value));
+                                    }
+                                }
+                                Widget::DragValue(drag_config) => {
+                                    let new_value;
+                                    (events, new_value) = drag_value(
+                                        api,
+                                        config,
+                                        text_config,
+                                        DragValue {
+                                            value: f32::resolve_src(&drag_config.value, locals, user_app, &list_data),
+                                            min: drag_config.min.as_ref().map(|min| f32::resolve_src(min, locals, user_app, &list_data)),
+                                            max: drag_config.max.as_ref().map(|max| f32::resolve_src(max, locals, user_app, &list_data)),
+                                            step: f32::resolve_src(&drag_config.step, locals, user_app, &list_data),
+                                            decimals: u16::resolve_src(&drag_config.decimals, locals, user_app, &list_data),
+                                            suffix: String::resolve_src(&drag_config.suffix, locals, user_app, &list_data),
+                                            event: drag_config.emit.as_ref().map(|event| Event::resolve_src(event, locals, user_app, &list_data)),
+                                            disabled: bool::resolve_src(&drag_config.disabled, locals, user_app, &list_data),
+                                        },
+                                        events
+                                    );
+
+                                    if let Some(value) = new_value
+                                    && let Some(bound_to) = binding(&drag_config.value, locals, &list_data) {
+                                        api.numeric_writes.push((bound_to, value));
+                                    }
+                                }
+                                Widget::Progress(progress_config) => {
+                                    progress_bar(
+                                        api,
+                                        config,
+                                        f32::resolve_src(&progress_config.value, locals, user_app, &list_data),
+                                        f32::resolve_src(&progress_config.length, locals, user_app, &list_data),
+                                        f32::resolve_src(&progress_config.thickness, locals, user_app, &list_data),
+                                        progress_config.color.as_ref().map(|color| Color::resolve_src(color, locals, user_app, &list_data)),
+                                    );
+                                }
+                                Widget::Spinner(spinner_config) => {
+                                    spinner(
+                                        api,
+                                        config,
+                                        f32::resolve_src(&spinner_config.size, locals, user_app, &list_data),
+                                        spinner_config.color.as_ref().map(|color| Color::resolve_src(color, locals, user_app, &list_data)),
+                                    );
+                                }
+                                Widget::TextEditor(editor_config) => {
+                                    let new_text;
+                                    (events, new_text) = text_editor(
+                                        api,
+                                        config,
+                                        text_config,
+                                        TextEditor {
+                                            text: String::resolve_src(&editor_config.text, locals, user_app, &list_data),
+                                            lines: f32::resolve_src(&editor_config.lines, locals, user_app, &list_data),
+                                            event: editor_config.emit.as_ref().map(|event| Event::resolve_src(event, locals, user_app, &list_data)),
+                                            disabled: bool::resolve_src(&editor_config.disabled, locals, user_app, &list_data),
+                                        },
+                                        events
+                                    );
+
+                                    if let Some(text) = new_text
+                                    && let Some(bound_to) = binding(&editor_config.text, locals, &list_data) {
+                                        api.text_writes.push((bound_to, text));
+                                    }
+                                }
+                                Widget::Markdown(markdown_config) => {
+                                    events = markdown_view(
+                                        api,
+                                        config,
+                                        text_config,
+                                        String::resolve_src(&markdown_config.from, locals, user_app, &list_data),
+                                        markdown_config.emit.as_ref().map(|event| Event::resolve_src(event, locals, user_app, &list_data)),
+                                        events
+                                    );
+                                }
+                                Widget::MenuBar(bar_config) => {
+                                    let menus = resolve_menu(&bar_config.menus, locals, user_app, &list_data);
+                                    events = menu_bar(api, config, menus, events);
+                                }
+                                Widget::ContextMenu(menu_config) => {
+                                    let mut items = Vec::new();
+                                    // closed menus don't need their items
+                                    if api.context_menu.is_some()
+                                    && let Some(length) = user_app.get_list_length(&menu_config.src, &list_data) {
+                                        for index in 0..length {
+                                            let item_data = Some((menu_config.src, index));
+                                            items.push(ContextMenuItem {
+                                                label: String::resolve_name(&menu_config.label, None, user_app, &item_data),
+                                                event: user_app.get_event(&menu_config.emit, &item_data),
+                                            });
+                                        }
+                                    }
+                                    events = context_menu(api, config, items, events);
+                                }
+                                Widget::Checkbox(toggle_config) | Widget::Radio(toggle_config) => {
+                                    let bound_to = binding(&toggle_config.checked, locals, &list_data);
+                                    if radio && let Some(bound_to) = bound_to {
+                                        api.bool_writes.join_group(&toggle_config.group, bound_to);
+                                    }
+
+                                    let checked = bool::resolve_src(&toggle_config.checked, locals, user_app, &list_data);
+                                    let changed;
+                                    (events, changed) = toggle(
+                                        api,
+                                        config,
+                                        text_config,
+                                        Toggle {
+                                            label: String::resolve_src(&toggle_config.label, locals, user_app, &list_data),
+                                            checked,
+                                            event: toggle_config.emit.as_ref().map(|event| Event::resolve_src(event, locals, user_app, &list_data)),
+                                            disabled: bool::resolve_src(&toggle_config.disabled, locals, user_app, &list_data),
+                                            radio,
+                                        },
+                                        events
+                                    );
+
+                                    if changed && let Some(bound_to) = bound_to {
+                                        match radio {
+                                            true => api.bool_writes.select(&toggle_config.group, bound_to),
+                                            false => api.bool_writes.set(bound_to, !checked),
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
                     Element::TextBoxOpened => {
                         nesting_level += 1;
 
                         if skip.is_none() {
-                            recursive_commands.clear();
                             recursive_call_stack.clear();
                             collect_declarations = true;
                             // text_box_source = String::resolve_src(name, locals, user_app, &list_data);
@@ -495,13 +1237,72 @@ where
                     recursive_call_stack.insert(*name, value);
                 }
             }
+            Layout::Config(Config::Id(DataSrc::Static(id))) => {
+                if skip.is_none() {
+                    config.id(id.as_str());
+                    element_name = Some(id.clone());
+                }
+            }
+            Layout::Config(Config::Tooltip(text)) => {
+                if skip.is_none() {
+                    tooltip_text = Some(String::resolve_src(text, locals, user_app, &list_data));
+                }
+            }
+            Layout::Config(Config::TooltipDelay(delay)) => {
+                if skip.is_none() {
+                    tooltip_delay = f32::resolve_src(delay, locals, user_app, &list_data);
+                }
+            }
+            Layout::Config(Config::EventCode(code)) => {
+                if skip.is_none()
+                && let Some((_, context)) = emitted.and_then(|emitted| events.get_mut(emitted)) {
+                    let code = f32::resolve_src(code, locals, user_app, &list_data) as u32;
+                    *context = Some(context.take().unwrap_or_else(EventContext::new).code(code));
+                }
+            }
+            Layout::Config(Config::EventText(text)) => {
+                if skip.is_none()
+                && let Some((_, context)) = emitted.and_then(|emitted| events.get_mut(emitted)) {
+                    let mut with_text = context.take().unwrap_or_else(EventContext::new);
+                    with_text.text = Some(String::resolve_src(text, locals, user_app, &list_data));
+                    *context = Some(with_text);
+                }
+            }
+            Layout::Config(Config::Visible(visible)) => {
+                if skip.is_none()
+                && !bool::resolve_src(visible, locals, user_app, &list_data)
+                && let Some(current) = api.opacity.last_mut() {
+                    hidden = true;
+                    *current = 0.0;
+                }
+            }
+            Layout::Config(Config::Opacity(opacity)) => {
+                if skip.is_none() && !hidden {
+                    let opacity = f32::resolve_src(opacity, locals, user_app, &list_data).clamp(0.0, 1.0);
+                    let inherited = match api.opacity.len() {
+                        0 | 1 => 1.0,
+                        open => api.opacity[open - 2],
+                    };
+                    if let Some(current) = api.opacity.last_mut() {
+                        *current = inherited * opacity;
+                    }
+                }
+            }
+            Layout::Config(Config::Rtl(rtl)) => {
+                if skip.is_none() {
+                    text_rtl = bool::resolve_src(rtl, locals, user_app, &list_data);
+                    if text_rtl {
+                        text_config.alignment_right().parse();
+                    }
+                }
+            }
             Layout::Config(config_command) => {
                 if skip.is_none() {
                     execute_config(
                         config_command,
                         Some(&mut config),
                         Some(&mut text_config),
-                        reusables,
+                        arena,
                         locals,
                         &list_data,
                         api,
@@ -517,10 +1318,10 @@ where
 
 
 fn execute_config<'render_pass, Event, UserApp>(
-    config_command: &mut Config,
+    config_command: &Config,
     config: Option<&mut ElementConfiguration>,
     text_config: Option<&mut TextConfig>,
-    _reusables: &mut HashMap<GlobalSymbol, Vec<Layout<Event>>>,
+    arena: &Bump,
     locals: Option<&HashMap<GlobalSymbol, &DataSrc<Declaration<Event>>>>,
     list_data: &Option<(GlobalSymbol, usize)>,
     api: &mut API,
@@ -595,23 +1396,31 @@ where
         Config::ChildAlignmentYBottom  => config.align_children_y_bottom().parse(),
         Config::Color(color)  => {
             let color = Color::resolve_src(color, locals, user_app, list_data);
-            config.color(color).parse();
+            config.color(faded(color, &api.opacity)).parse();
         }
 
         Config::CustomElement(custom_element) => {
-            if let CustomElement::Line(line) = custom_element
-            && let Some(source) = line.width_source
-            && let Some(width) = user_app.get_numeric(&source, list_data) {
-                line.width = width;
+            let width = match custom_element {
+                CustomElement::Line(line) => line.width_source.and_then(|source| user_app.get_numeric(&source, list_data)),
+                _ => None,
+            };
+            match width {
+                Some(width) => {
+                    let resolved = arena.alloc(custom_element.clone());
+                    if let CustomElement::Line(line) = resolved {
+                        line.width = width;
+                    }
+                    config.custom_element(resolved).parse();
+                }
+                None => config.custom_element(custom_element).parse(),
             }
-            config.custom_element(custom_element).parse();
         }
         Config::RadiusAll(radius)  => config.radius_all(f32::resolve_src(radius, locals, user_app, list_data)).parse(),
         Config::RadiusTopLeft(radius)  => config.radius_top_left(f32::resolve_src(radius, locals, user_app, list_data)).parse(),
         Config::RadiusTopRight(radius)  => config.radius_top_right(f32::resolve_src(radius, locals, user_app, list_data)).parse(),
         Config::RadiusBottomRight(radius)  => config.radius_bottom_right(f32::resolve_src(radius, locals, user_app, list_data)).parse(),
         Config::RadiusBottomLeft(radius)  => config.radius_bottom_left(f32::resolve_src(radius, locals, user_app, list_data)).parse(),
-        Config::BorderColor(color) => config.border_color(Color::resolve_src(color, locals, user_app, list_data)).parse(),
+        Config::BorderColor(color) => config.border_color(faded(Color::resolve_src(color, locals, user_app, list_data), &api.opacity)).parse(),
         Config::BorderAll(border)  => config.border_all(u16::resolve_src(border, locals, user_app, list_data)).parse(),
         Config::BorderTop(border)  => config.border_top(u16::resolve_src(border, locals, user_app, list_data)).parse(),
         Config::BorderBottom(border)  => config.border_bottom(u16::resolve_src(border, locals, user_app, list_data)).parse(),
@@ -628,16 +1437,38 @@ where
                 config.image(image).parse();
             }
         }
+        Config::DepthTest(enabled) => {
+            if bool::resolve_src(enabled, locals, user_app, list_data) {
+                config.custom_layout_settings(&DEPTH_TESTED).parse();
+            }
+        }
+        Config::PixelSnap(enabled) => {
+            if !bool::resolve_src(enabled, locals, user_app, list_data) {
+                config.custom_layout_settings(&UNSNAPPED).parse();
+            }
+        }
         Config::Floating => config.floating().parse(),
         Config::FloatingOffset { x, y } => config.floating_offset(
             f32::resolve_src(x, locals, user_app, list_data), 
             f32::resolve_src(y, locals, user_app, list_data)
         ).parse(),
+        Config::FloatingAnchor(model) => {
+            let model = String::resolve_src(model, locals, user_app, list_data);
+            if let Some((x, y)) = api.model_on_screen(model) {
+                config.floating_offset(x, y).parse();
+            }
+        }
         Config::FloatingDimensions { width, height } => config.floating_dimensions(
             f32::resolve_src(width, locals, user_app, list_data),  
             f32::resolve_src(height, locals, user_app, list_data), 
         ).parse(),
         Config::FloatingZIndex { z } => config.floating_z_index(i16::resolve_src(z, locals, user_app, list_data)).parse(),
+        Config::ZIndex { z: DataSrc::Static(_), layer } => config.custom_element(layer).parse(),
+        // the layer a binding resolves to lives in the frame arena
+        Config::ZIndex { z, layer: _ } => {
+            let layer = arena.alloc(CustomElement::Layer(i16::resolve_src(z, locals, user_app, list_data)));
+            config.custom_element(layer).parse();
+        }
         Config::FloatingAttatchToParentAtTopLeft => config.floating_attach_to_parent_at_top_left().parse(),
         Config::FloatingAttatchToParentAtCenterLeft => config.floating_attach_to_parent_at_center_left().parse(),
         Config::FloatingAttatchToParentAtBottomLeft => config.floating_attach_to_parent_at_bottom_left().parse(),
@@ -657,9 +1488,12 @@ where
         Config::FloatingAttatchElementAtCenterRight => config.floating_attach_element_at_center_right().parse(),
         Config::FloatingAttatchElementAtBottomRight => config.floating_attach_element_at_bottom_right().parse(),
         Config::FloatingPointerPassThrough => config.floating_pointer_pass_through().parse(),
-        Config::FloatingAttachElementToElement { other_element_id:_ } => {
-            //let id = layout_engine.get_id(other_element_id);
-            config.floating_attach_to_element(0).parse()
+        Config::FloatingAttachElementToElement { other_element_id } => {
+            // elements laid out later in the frame are found from the last frame
+            match api.element_ids.get(other_element_id.as_str()) {
+                Some(id) => config.floating_attach_to_element(*id).parse(),
+                None => config.floating_attach_to_root().parse(),
+            }
         }
         Config::FloatingAttachElementToRoot => config.floating_attach_to_root().parse(),
         Config::Use { name:_ } => {
@@ -687,7 +1521,14 @@ where
         Config::AlignLeft => text_config.alignment_left().parse(),
         Config::AlignRight => text_config.alignment_right().parse(),
         Config::Editable(_state) => (),
+        // shown by the element itself in `set_layout`
+        Config::Opacity(_) | Config::Visible(_) | Config::Collapsed(_) | Config::Tooltip(_) | Config::TooltipDelay(_) | Config::Rtl(_) => (),
+        // put in the context of the event sent by the block around them in `set_layout`
+        Config::EventCode(_) | Config::EventText(_) => (),
         Config::FontId(id) => text_config.font_id(u16::resolve_src(id, locals, user_app, list_data)).parse(),
+        Config::Font(name) => if let Some(font_id) = api.font_id(String::resolve_src(name, locals, user_app, list_data)) {
+            text_config.font_id(font_id).parse()
+        },
         Config::FontColor(color)  => text_config.color(Color::resolve_src(color, locals, user_app, list_data)).parse(),
         Config::FontSize(size) => text_config.font_size(u16::resolve_src(size, locals, user_app, list_data)).parse(),
         Config::LineHeight(height) => text_config.line_height(u16::resolve_src(height, locals, user_app, list_data)).parse(),
@@ -925,7 +1766,9 @@ where
                     value
                 }
                 else {
-                    0.0
+                    system_numeric(name, list_data)
+                        .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::number))
+                        .unwrap_or(0.0)
                 }
             }
             DataSrc::Static(value) => {
@@ -955,7 +1798,9 @@ where
             value
         }
         else {
-            0.0
+            system_numeric(name, list_data)
+                .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::number))
+                .unwrap_or(0.0)
         }
     }
 }
@@ -993,7 +1838,9 @@ where
                     value as u16
                 }
                 else {
-                    0
+                    system_numeric(name, list_data)
+                        .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::number))
+                        .unwrap_or(0.0) as u16
                 }
             }
             DataSrc::Static(value) => {
@@ -1023,7 +1870,9 @@ where
             value as u16
         }
         else {
-            0
+            system_numeric(name, list_data)
+                .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::number))
+                .unwrap_or(0.0) as u16
         }
     }
 }
@@ -1061,7 +1910,9 @@ where
                     value as i16
                 }
                 else {
-                    0
+                    system_numeric(name, list_data)
+                        .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::number))
+                        .unwrap_or(0.0) as i16
                 }
             }
             DataSrc::Static(value) => {
@@ -1091,7 +1942,9 @@ where
             value as i16
         }
         else {
-            0
+            system_numeric(name, list_data)
+                .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::number))
+                .unwrap_or(0.0) as i16
         }
     }
 }
@@ -1129,7 +1982,9 @@ where
                     value
                 }
                 else {
-                    false
+                    system_bool(name)
+                        .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::truthy))
+                        .unwrap_or(false)
                 }
             }
             DataSrc::Static(value) => {
@@ -1159,7 +2014,9 @@ where
             value
         }
         else {
-            false
+            system_bool(name)
+                .or_else(|| expression_value(name, locals, user_app, list_data).map(Value::truthy))
+                .unwrap_or(false)
         }
     }
 }
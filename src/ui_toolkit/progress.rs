@@ -0,0 +1,112 @@
+use std::f32::consts::TAU;
+
+use telera_layout::{Color, ElementConfiguration};
+
+use crate::{API, DataSrc};
+
+const TRACK: Color = Color { r: 190.0, g: 190.0, b: 190.0, a: 255.0 };
+const FILL: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const SPINNER_DOTS: usize = 8;
+/// seconds per turn
+const SPINNER_PERIOD: f32 = 1.0;
+
+/// `tk` progress as parsed from a layout, `value` runs from 0 to 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressConfig {
+    pub value: DataSrc<f32>,
+    /// track length in layout units
+    pub length: DataSrc<f32>,
+    pub thickness: DataSrc<f32>,
+    pub color: Option<DataSrc<Color>>,
+}
+
+impl Default for ProgressConfig {
+    fn default() -> Self {
+        Self {
+            value: DataSrc::Static(0.0),
+            length: DataSrc::Static(160.0),
+            thickness: DataSrc::Static(6.0),
+            color: None,
+        }
+    }
+}
+
+/// `tk` spinner as parsed from a layout
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpinnerConfig {
+    pub size: DataSrc<f32>,
+    pub color: Option<DataSrc<Color>>,
+}
+
+impl Default for SpinnerConfig {
+    fn default() -> Self {
+        Self {
+            size: DataSrc::Static(24.0),
+            color: None,
+        }
+    }
+}
+
+pub fn progress_bar(api: &mut API, config: &mut ElementConfiguration, value: f32, length: f32, thickness: f32, color: Option<Color>) {
+    api.ui_layout.open_element();
+    config.x_fixed(length)
+        .y_fixed(thickness)
+        .color(TRACK)
+        .radius_all(thickness / 2.0)
+        .parse();
+    api.ui_layout.configure_element(config);
+
+    let filled = value.clamp(0.0, 1.0) * length;
+    if filled > 0.0 {
+        api.ui_layout.open_element();
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .x_fixed(filled)
+            .y_fixed(thickness)
+            .color(color.unwrap_or(FILL))
+            .radius_all(thickness / 2.0)
+            .end()
+        );
+        api.ui_layout.close_element();
+    }
+
+    api.ui_layout.close_element();
+}
+
+/// a ring of dots with a bright one running around it, keeps the viewport redrawing while it's on screen
+pub fn spinner(api: &mut API, config: &mut ElementConfiguration, size: f32, color: Option<Color>) {
+    api.ui_layout.open_element();
+    config.x_fixed(size).y_fixed(size).parse();
+    api.ui_layout.configure_element(config);
+
+    let color = color.unwrap_or(FILL);
+    let dot = size / 5.0;
+    let radius = (size - dot) / 2.0;
+    let turn = (api.started.elapsed().as_secs_f32() / SPINNER_PERIOD).fract();
+    let lead = (turn * SPINNER_DOTS as f32) as usize;
+
+    for index in 0..SPINNER_DOTS {
+        let angle = index as f32 / SPINNER_DOTS as f32 * TAU;
+        // dots fade out behind the leading one
+        let behind = (lead + SPINNER_DOTS - index) % SPINNER_DOTS;
+        let alpha = 1.0 - behind as f32 / SPINNER_DOTS as f32;
+
+        api.ui_layout.open_element();
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .floating()
+            .floating_attach_to_parent_at_center()
+            .floating_attach_element_at_center()
+            .floating_offset(angle.sin() * radius, -angle.cos() * radius)
+            .floating_pointer_pass_through()
+            .x_fixed(dot)
+            .y_fixed(dot)
+            .color(Color { a: color.a * alpha, ..color })
+            .radius_all(dot / 2.0)
+            .end()
+        );
+        api.ui_layout.close_element();
+    }
+
+    api.ui_layout.close_element();
+
+    api.request_redraw_all();
+}
@@ -0,0 +1,194 @@
+use std::fmt::Debug;
+
+use telera_layout::{Color, ElementConfiguration};
+use winit::keyboard::NamedKey;
+
+use crate::{API, DataSrc, EventContext};
+
+const TRACK: Color = Color { r: 190.0, g: 190.0, b: 190.0, a: 255.0 };
+const FILL: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const HANDLE: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+const HANDLE_BORDER: Color = Color { r: 110.0, g: 110.0, b: 110.0, a: 255.0 };
+const DISABLED: Color = Color { r: 150.0, g: 150.0, b: 150.0, a: 255.0 };
+
+const HANDLE_SIZE: f32 = 14.0;
+const TRACK_THICKNESS: f32 = 4.0;
+/// keyboard steps for continuous sliders, as a fraction of the range
+const KEYBOARD_FRACTION: f32 = 0.01;
+
+/// `tk` slider as parsed from a layout, `value` is read with `get_numeric` and written with `set_numeric`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliderConfig<Event: Clone+Debug+PartialEq+Default> {
+    pub value: DataSrc<f32>,
+    pub min: DataSrc<f32>,
+    pub max: DataSrc<f32>,
+    /// 0 slides freely
+    pub step: DataSrc<f32>,
+    /// track length in layout units
+    pub length: DataSrc<f32>,
+    pub vertical: bool,
+    pub emit: Option<DataSrc<Event>>,
+    pub disabled: DataSrc<bool>,
+}
+
+impl<Event: Clone+Debug+PartialEq+Default> Default for SliderConfig<Event> {
+    fn default() -> Self {
+        Self {
+            value: DataSrc::Static(0.0),
+            min: DataSrc::Static(0.0),
+            max: DataSrc::Static(1.0),
+            step: DataSrc::Static(0.0),
+            length: DataSrc::Static(160.0),
+            vertical: false,
+            emit: None,
+            disabled: DataSrc::Static(false),
+        }
+    }
+}
+
+/// a slider with its data resolved for this frame
+pub struct Slider<Event> {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub length: f32,
+    pub vertical: bool,
+    pub event: Option<Event>,
+    pub disabled: bool,
+}
+
+impl<Event> Slider<Event> {
+    fn snap(&self, value: f32) -> f32 {
+        let (low, high) = match self.min <= self.max {
+            true => (self.min, self.max),
+            false => (self.max, self.min),
+        };
+        let value = match self.step > 0.0 {
+            true => self.min + ((value - self.min) / self.step).round() * self.step,
+            false => value,
+        };
+        value.clamp(low, high)
+    }
+
+    fn fraction(&self, value: f32) -> f32 {
+        match self.max != self.min {
+            true => ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0),
+            false => 0.0,
+        }
+    }
+}
+
+/// returns the new value when the user moved the slider this frame, the change event carries it in `EventContext::value`
+pub fn slider<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    slider: Slider<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> (Vec::<(Event, Option<EventContext>)>, Option<f32>)
+{
+    api.ui_layout.open_element();
+
+    let hovered = !slider.disabled && api.ui_layout.hovered();
+    let travel = (slider.length - HANDLE_SIZE).max(1.0);
+    match slider.vertical {
+        true => config.x_fixed(HANDLE_SIZE).y_fixed(slider.length).direction(true).parse(),
+        false => config.x_fixed(slider.length).y_fixed(HANDLE_SIZE).parse(),
+    }
+    config.align_children_x_center().align_children_y_center().parse();
+
+    let id = api.ui_layout.configure_element(config);
+
+    if hovered && api.left_mouse_pressed {
+        api.focus = id;
+        api.slider_drag = Some((id, slider.value));
+    }
+    if (!api.left_mouse_down || slider.disabled) && api.slider_drag.is_some_and(|(drag_id, _)| drag_id == id) {
+        api.slider_drag = None;
+    }
+
+    let mut new_value = None;
+    if let Some((drag_id, start_value)) = api.slider_drag
+    && drag_id == id {
+        // vertical sliders grow upwards
+        let moved = match slider.vertical {
+            true => api.y_at_click - api.mouse_poistion.1 / api.dpi_scale,
+            false => api.mouse_poistion.0 / api.dpi_scale - api.x_at_click,
+        };
+        new_value = Some(slider.snap(start_value + moved / travel * (slider.max - slider.min)));
+    }
+    else if !slider.disabled && api.focus == id {
+        let step = match slider.step > 0.0 {
+            true => slider.step,
+            false => (slider.max - slider.min) * KEYBOARD_FRACTION,
+        };
+        if api.named_key_pressed(NamedKey::ArrowRight) || api.named_key_pressed(NamedKey::ArrowUp) {
+            new_value = Some(slider.snap(slider.value + step));
+        }
+        else if api.named_key_pressed(NamedKey::ArrowLeft) || api.named_key_pressed(NamedKey::ArrowDown) {
+            new_value = Some(slider.snap(slider.value - step));
+        }
+        else if api.named_key_pressed(NamedKey::Home) {
+            new_value = Some(slider.min);
+        }
+        else if api.named_key_pressed(NamedKey::End) {
+            new_value = Some(slider.max);
+        }
+    }
+    let new_value = new_value.filter(|value| *value != slider.value);
+
+    if let Some(value) = new_value
+    && let Some(event) = slider.event.clone() {
+        events.push((event, Some(EventContext::from_value(value))));
+    }
+
+    let filled = slider.fraction(new_value.unwrap_or(slider.value)) * travel;
+    let (fill, handle_border) = match slider.disabled {
+        true => (DISABLED, DISABLED),
+        false => (FILL, match hovered || api.slider_drag.is_some_and(|(drag_id, _)| drag_id == id) {
+            true => FILL,
+            false => HANDLE_BORDER,
+        }),
+    };
+    let bar = |length: f32, color: Color| {
+        let (width, height) = match slider.vertical {
+            true => (TRACK_THICKNESS, length),
+            false => (length, TRACK_THICKNESS),
+        };
+        ElementConfiguration::new()
+            .x_fixed(width)
+            .y_fixed(height)
+            .color(color)
+            .radius_all(TRACK_THICKNESS / 2.0)
+            .end()
+    };
+
+    let (before, after) = match slider.vertical {
+        true => (bar(travel - filled, TRACK), bar(filled, fill)),
+        false => (bar(filled, fill), bar(travel - filled, TRACK)),
+    };
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&before);
+    api.ui_layout.close_element();
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new()
+        .x_fixed(HANDLE_SIZE)
+        .y_fixed(HANDLE_SIZE)
+        .color(HANDLE)
+        .border_all(2)
+        .border_color(handle_border)
+        .radius_all(HANDLE_SIZE / 2.0)
+        .end()
+    );
+    api.ui_layout.close_element();
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&after);
+    api.ui_layout.close_element();
+
+    api.ui_layout.close_element();
+
+    (events, new_value)
+}
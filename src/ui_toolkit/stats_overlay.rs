@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+
+use crate::API;
+
+const BACKGROUND: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 180.0 };
+const TEXT: Color = Color { r: 120.0, g: 255.0, b: 120.0, a: 255.0 };
+/// under the error overlay
+const Z_INDEX: i16 = i16::MAX - 2;
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f32() * 1000.0)
+}
+
+/// the times of the last frame in the top left corner, toggled with F12 or `api.show_frame_stats`
+pub fn stats_overlay(api: &mut API) {
+    let stats = api.frame_stats;
+    let mut lines = vec![
+        format!("frame  {}", milliseconds(stats.frame)),
+        format!("layout {}", milliseconds(stats.layout)),
+        format!("ui     {}", milliseconds(stats.ui_render)),
+        format!("scene  {}", milliseconds(stats.scene_render)),
+        format!("draws  {}", stats.ui_draw_calls),
+    ];
+    if let Some(allocations) = stats.layout_allocations {
+        lines.push(format!("allocs {}", allocations));
+    }
+
+    let mut text_config = TextConfig::default();
+    text_config.font_size(12).color(TEXT).parse();
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new()
+        .floating()
+        .floating_attach_to_root()
+        .floating_offset(8.0, 8.0)
+        .floating_z_index(Z_INDEX)
+        .floating_pointer_pass_through()
+        .padding_all(6)
+        .child_gap(2)
+        .direction(true)
+        .radius_all(3.0)
+        .color(BACKGROUND)
+        .end()
+    );
+        for line in &lines {
+            api.ui_layout.add_text_element(line, &text_config, false);
+        }
+    api.ui_layout.close_element();
+}
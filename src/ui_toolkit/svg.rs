@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use image::{DynamicImage, RgbaImage};
+use resvg::{tiny_skia, usvg};
+
+/// a parsed svg and the rasters made of it so far, keyed by hundredths of a dpi scale
+pub struct SvgImage {
+    tree: usvg::Tree,
+    rasters: HashMap<u32, DynamicImage>,
+    /// the scale whose raster is in the atlas
+    uploaded: Option<u32>,
+}
+
+impl SvgImage {
+    pub fn parse(name: &str, source: &str) -> Result<Self, ()> {
+        let tree = usvg::Tree::from_str(source, &usvg::Options::default())
+            .map_err(|e| tracing::error!(target: "telera::render", "failed to parse svg {}: {}", name, e))?;
+        Ok(Self { tree, rasters: HashMap::new(), uploaded: None })
+    }
+
+    /// the raster for `dpi_scale` when the atlas holds a different one, rasterizing it the first time it's asked for
+    pub fn raster_for(&mut self, dpi_scale: f32) -> Option<DynamicImage> {
+        let key = (dpi_scale * 100.0).round() as u32;
+        if self.uploaded == Some(key) {
+            return None
+        }
+        if !self.rasters.contains_key(&key) {
+            self.rasters.insert(key, rasterize(&self.tree, dpi_scale)?);
+        }
+        self.uploaded = Some(key);
+        self.rasters.get(&key).cloned()
+    }
+}
+
+/// the svg's own size is in layout units, so it's drawn `dpi_scale` times larger
+fn rasterize(tree: &usvg::Tree, dpi_scale: f32) -> Option<DynamicImage> {
+    let size = tree.size().to_int_size().scale_by(dpi_scale)?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+    resvg::render(tree, tiny_skia::Transform::from_scale(dpi_scale, dpi_scale), &mut pixmap.as_mut());
+
+    // tiny-skia keeps premultiplied alpha, the ui shader expects it straight
+    let pixels = pixmap.pixels().iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(size.width(), size.height(), pixels).map(DynamicImage::ImageRgba8)
+}
@@ -1,13 +1,105 @@
 use std::str::FromStr;
 use std::fmt::Debug;
+use std::ops::Range;
 
 use symbol_table::GlobalSymbol;
 use telera_layout::{Color, TextConfig};
 use telera_layout::ElementConfiguration;
 
-use crate::{ParserDataAccess, API, EventContext, EventHandler};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::{ParserDataAccess, API, CustomElement, DataSrc, EventContext, EventHandler, ui_toolkit::ui_shapes::TextMarks};
 
 const DEFAULT_TEXT: &str = "";
+const BOX: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+const BORDER: Color = Color { r: 110.0, g: 110.0, b: 110.0, a: 255.0 };
+const BORDER_ACTIVE: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+const SELECTION: Color = Color { r: 180.0, g: 210.0, b: 255.0, a: 255.0 };
+const DISABLED_TEXT: Color = Color { r: 120.0, g: 120.0, b: 120.0, a: 255.0 };
+const DEFAULT_FONT_SIZE: u16 = 14;
+const TAB: &str = "    ";
+/// undo steps kept per editor
+const UNDO_LIMIT: usize = 200;
+
+/// `tk` text-editor as parsed from a layout, `text` is read with `get_text` and written with `set_text`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEditorConfig<Event: Clone+Debug+PartialEq+Default> {
+    pub text: DataSrc<String>,
+    /// visible rows, the rest scrolls
+    pub lines: DataSrc<f32>,
+    pub emit: Option<DataSrc<Event>>,
+    pub disabled: DataSrc<bool>,
+}
+
+impl<Event: Clone+Debug+PartialEq+Default> Default for TextEditorConfig<Event> {
+    fn default() -> Self {
+        Self {
+            text: DataSrc::Static(String::new()),
+            lines: DataSrc::Static(6.0),
+            emit: None,
+            disabled: DataSrc::Static(false),
+        }
+    }
+}
+
+/// a text editor with its data resolved for this frame
+pub struct TextEditor<'frame, Event> {
+    pub text: &'frame str,
+    pub lines: f32,
+    pub event: Option<Event>,
+    pub disabled: bool,
+}
+
+/// cursor, selection and history of the editor that has focus, byte offsets into its text
+#[derive(Default)]
+pub struct EditorState {
+    cursor: usize,
+    /// the other end of the selection
+    anchor: Option<usize>,
+    undo: Vec<(String, usize)>,
+    redo: Vec<(String, usize)>,
+    /// typing a word is undone in one step
+    typing: bool,
+}
+
+impl EditorState {
+    fn selection(&self) -> Option<Range<usize>> {
+        self.anchor
+            .filter(|anchor| *anchor != self.cursor)
+            .map(|anchor| anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    /// moves the cursor, `extend` grows the selection instead of dropping it
+    fn move_to(&mut self, position: usize, extend: bool) {
+        match extend {
+            true => if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+            false => self.anchor = None,
+        }
+        self.cursor = position;
+    }
+
+    /// remembers `text` before it's edited, runs of typing share one step
+    fn checkpoint(&mut self, text: &str, typing: bool) {
+        if !(typing && self.typing) {
+            self.undo.push((text.to_string(), self.cursor));
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.typing = typing;
+    }
+
+    /// replaces the selection, or inserts at the cursor without one
+    fn replace(&mut self, text: &mut String, with: &str) {
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        text.replace_range(range.clone(), with);
+        self.cursor = range.start + with.len();
+        self.anchor = None;
+    }
+}
 
 #[allow(dead_code)]
 pub fn text_box<UserApp, Event>(
@@ -51,4 +143,343 @@ where
     clay.close_element();
 
     events
-}
\ No newline at end of file
+}
+
+/// a text box being typed into, `buffer` is edited in place.
+/// returns `Some(true)` when the edit is committed with enter or a click elsewhere, `Some(false)` when escape cancels it
+pub fn text_edit(api: &mut API, buffer: &mut String, text_config: &TextConfig) -> Option<bool> {
+    let mut finished = None;
+    for key in &api.keys_pressed {
+        match key {
+            Key::Character(text) => buffer.push_str(text),
+            Key::Named(NamedKey::Space) => buffer.push(' '),
+            Key::Named(NamedKey::Backspace) => { buffer.pop(); }
+            Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Tab) => finished = Some(true),
+            Key::Named(NamedKey::Escape) => finished = Some(false),
+            _ => {}
+        }
+    }
+
+    api.ui_layout.open_element();
+    let hovered = api.ui_layout.hovered();
+    api.ui_layout.configure_element(&ElementConfiguration::new()
+        .border_all(1)
+        .border_color(Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 })
+        .x_fit_min(80.0)
+        .color(Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 })
+        .padding_all(2)
+        .end()
+    );
+    api.ui_layout.add_text_element(&format!("{}|", buffer), text_config, false);
+    api.ui_layout.close_element();
+
+    if finished.is_none() && api.left_mouse_pressed && !hovered {
+        finished = Some(true);
+    }
+
+    finished
+}
+
+/// multi-line text that wraps and scrolls, with word and line cursor movement, selection and undo.
+/// returns the new text when it was edited this frame, the change event carries it in `EventContext::text`
+pub fn text_editor<Event: Clone>(
+    api: &mut API,
+    config: &mut ElementConfiguration,
+    text_config: &mut TextConfig,
+    editor: TextEditor<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> (Vec::<(Event, Option<EventContext>)>, Option<String>)
+{
+    if text_config.font_size == 0 {
+        text_config.font_size(DEFAULT_FONT_SIZE).parse();
+    }
+    if editor.disabled {
+        text_config.color(DISABLED_TEXT).parse();
+    }
+    let line_height = match text_config.line_height {
+        0 => text_config.font_size as f32 * 1.2,
+        height => height as f32,
+    };
+
+    api.ui_layout.open_element();
+    let hovered = !editor.disabled && api.ui_layout.hovered();
+    let scroll_offset = api.ui_layout.get_scroll_offset();
+    config.direction(true)
+        .y_fixed(editor.lines.max(1.0) * line_height + 12.0)
+        .padding_all(6)
+        .color(BOX)
+        .border_all(1)
+        .border_color(match hovered {
+            true => BORDER_ACTIVE,
+            false => BORDER,
+        })
+        .radius_all(3.0)
+        .scroll(true, false, scroll_offset)
+        .parse();
+    let id = api.ui_layout.configure_element(config);
+
+    if hovered && api.left_mouse_pressed {
+        api.focus = id;
+        if !api.text_editor.as_ref().is_some_and(|(editing, _)| *editing == id) {
+            api.text_editor = Some((id, EditorState { cursor: editor.text.len(), ..Default::default() }));
+        }
+    }
+    let focused = api.focus == id && !editor.disabled;
+    if !focused && api.text_editor.as_ref().is_some_and(|(editing, _)| *editing == id) {
+        api.text_editor = None;
+    }
+
+    let mut text = editor.text.to_string();
+    let mut state = match api.text_editor.take() {
+        Some((editing, state)) if editing == id => Some(state),
+        other => {
+            api.text_editor = other;
+            None
+        }
+    };
+
+    if let Some(state) = state.as_mut() {
+        // the text may have changed under the editor since last frame
+        state.cursor = floor_char(&text, state.cursor);
+        state.anchor = state.anchor.map(|anchor| floor_char(&text, anchor));
+        edit(api, state, &mut text);
+    }
+
+    let cursor_color = [text_config.color.r / 255.0, text_config.color.g / 255.0, text_config.color.b / 255.0];
+    let pointer = (api.mouse_poistion.0 / api.dpi_scale, api.mouse_poistion.1 / api.dpi_scale);
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        let line_range = line_start..line_start + line.len();
+        line_start = line_range.end + 1;
+
+        // the cursor and selection are drawn by the renderer from the line's glyphs, the text itself is left whole
+        let marks = CustomElement::TextMarks(TextMarks {
+            editor: id,
+            line_start: line_range.start,
+            text: line.to_string(),
+            font_id: text_config.font_id,
+            font_size: text_config.font_size as f32,
+            line_height,
+            cursor: state.as_ref()
+                .map(|state| state.cursor)
+                .filter(|cursor| (line_range.start..=line_range.end).contains(cursor))
+                .map(|cursor| cursor - line_range.start),
+            selection: state.as_ref()
+                .and_then(|state| state.selection())
+                .map(|selection| selection.start.max(line_range.start)..selection.end.min(line_range.end))
+                .filter(|selected| selected.start < selected.end)
+                .map(|selected| selected.start - line_range.start..selected.end - line_range.start),
+            cursor_color,
+        });
+
+        api.ui_layout.open_element();
+        let line_hovered = api.ui_layout.hovered();
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .x_grow()
+            .y_fit_min(line_height)
+            .color(SELECTION)
+            .custom_element(&marks)
+            .end()
+        );
+        api.ui_layout.add_text_element(match line.is_empty() {
+            true => " ",
+            false => line,
+        }, text_config, false);
+        api.ui_layout.close_element();
+
+        // clicks land between the glyphs the line showed last frame, before it's been drawn once they go to its end
+        if line_hovered && api.left_mouse_pressed
+        && let Some(state) = state.as_mut() {
+            let column = api.editor_lines.iter()
+                .find(|layout| layout.editor == id && layout.line_start == line_range.start)
+                .map(|layout| floor_char(line, layout.hit(pointer)))
+                .unwrap_or(line.len());
+            let extend = api.modifiers.shift_key();
+            state.move_to(line_range.start + column, extend);
+            state.typing = false;
+        }
+    }
+
+    api.ui_layout.close_element();
+
+    if let Some(state) = state {
+        api.text_editor = Some((id, state));
+    }
+
+    if text == editor.text {
+        return (events, None)
+    }
+    if let Some(event) = editor.event {
+        events.push((event, Some(EventContext::from_text(&text))));
+    }
+    (events, Some(text))
+}
+
+/// applies this frame's keys to the focused editor
+fn edit(api: &API, state: &mut EditorState, text: &mut String) {
+    let command = api.modifiers.control_key() || api.modifiers.super_key();
+    let shift = api.modifiers.shift_key();
+
+    for key in &api.keys_pressed {
+        match key {
+            Key::Character(typed) if command => match typed.to_lowercase().as_str() {
+                "a" => {
+                    state.anchor = Some(0);
+                    state.cursor = text.len();
+                }
+                "z" if shift => redo(state, text),
+                "z" => undo(state, text),
+                "y" => redo(state, text),
+                _ => {}
+            }
+            Key::Character(typed) => {
+                let word = typed.chars().all(|character| !character.is_whitespace());
+                state.checkpoint(text, word);
+                state.replace(text, typed);
+            }
+            Key::Named(NamedKey::Space) => {
+                state.checkpoint(text, false);
+                state.replace(text, " ");
+            }
+            Key::Named(NamedKey::Enter) => {
+                state.checkpoint(text, false);
+                state.replace(text, "\n");
+            }
+            Key::Named(NamedKey::Tab) => {
+                state.checkpoint(text, false);
+                state.replace(text, TAB);
+            }
+            Key::Named(NamedKey::Backspace) | Key::Named(NamedKey::Delete) => {
+                if state.selection().is_none() {
+                    let backwards = *key == Key::Named(NamedKey::Backspace);
+                    let to = match (backwards, command) {
+                        (true, true) => word_left(text, state.cursor),
+                        (true, false) => previous_char(text, state.cursor),
+                        (false, true) => word_right(text, state.cursor),
+                        (false, false) => next_char(text, state.cursor),
+                    };
+                    if to == state.cursor {
+                        continue
+                    }
+                    state.anchor = Some(to);
+                }
+                state.checkpoint(text, false);
+                state.replace(text, "");
+            }
+            Key::Named(NamedKey::ArrowLeft) => {
+                let to = match command {
+                    true => word_left(text, state.cursor),
+                    false => previous_char(text, state.cursor),
+                };
+                state.move_to(to, shift);
+                state.typing = false;
+            }
+            Key::Named(NamedKey::ArrowRight) => {
+                let to = match command {
+                    true => word_right(text, state.cursor),
+                    false => next_char(text, state.cursor),
+                };
+                state.move_to(to, shift);
+                state.typing = false;
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                let start = line_start(text, state.cursor);
+                let to = match start {
+                    0 => 0,
+                    _ => at_column(text, line_start(text, start - 1), text[start..state.cursor].chars().count()),
+                };
+                state.move_to(to, shift);
+                state.typing = false;
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                let start = line_start(text, state.cursor);
+                let end = line_end(text, state.cursor);
+                let to = match end == text.len() {
+                    true => text.len(),
+                    false => at_column(text, end + 1, text[start..state.cursor].chars().count()),
+                };
+                state.move_to(to, shift);
+                state.typing = false;
+            }
+            Key::Named(NamedKey::Home) => {
+                let to = match command {
+                    true => 0,
+                    false => line_start(text, state.cursor),
+                };
+                state.move_to(to, shift);
+                state.typing = false;
+            }
+            Key::Named(NamedKey::End) => {
+                let to = match command {
+                    true => text.len(),
+                    false => line_end(text, state.cursor),
+                };
+                state.move_to(to, shift);
+                state.typing = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn undo(state: &mut EditorState, text: &mut String) {
+    if let Some((previous, cursor)) = state.undo.pop() {
+        state.redo.push((std::mem::replace(text, previous), state.cursor));
+        state.cursor = cursor;
+        state.anchor = None;
+        state.typing = false;
+    }
+}
+
+fn redo(state: &mut EditorState, text: &mut String) {
+    if let Some((next, cursor)) = state.redo.pop() {
+        state.undo.push((std::mem::replace(text, next), state.cursor));
+        state.cursor = cursor;
+        state.anchor = None;
+        state.typing = false;
+    }
+}
+
+fn floor_char(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn previous_char(text: &str, index: usize) -> usize {
+    text[..index].char_indices().next_back().map(|(previous, _)| previous).unwrap_or(0)
+}
+
+fn next_char(text: &str, index: usize) -> usize {
+    text[index..].chars().next().map(|character| index + character.len_utf8()).unwrap_or(index)
+}
+
+fn word_left(text: &str, index: usize) -> usize {
+    text[..index]
+        .trim_end_matches(|character: char| !character.is_alphanumeric())
+        .trim_end_matches(|character: char| character.is_alphanumeric())
+        .len()
+}
+
+fn word_right(text: &str, index: usize) -> usize {
+    let rest = text[index..]
+        .trim_start_matches(|character: char| !character.is_alphanumeric())
+        .trim_start_matches(|character: char| character.is_alphanumeric());
+    text.len() - rest.len()
+}
+
+fn line_start(text: &str, index: usize) -> usize {
+    text[..index].rfind('\n').map(|newline| newline + 1).unwrap_or(0)
+}
+
+fn line_end(text: &str, index: usize) -> usize {
+    text[index..].find('\n').map(|newline| index + newline).unwrap_or(text.len())
+}
+
+/// the offset `column` characters into the line starting at `start`, or the line's end
+fn at_column(text: &str, start: usize, column: usize) -> usize {
+    let end = line_end(text, start);
+    text[start..end].char_indices().nth(column).map(|(offset, _)| start + offset).unwrap_or(end)
+}
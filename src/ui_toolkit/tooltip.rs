@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+use telera_layout::{Color, ElementConfiguration, TextConfig};
+
+use crate::API;
+
+const BACKGROUND: Color = Color { r: 40.0, g: 40.0, b: 40.0, a: 235.0 };
+const TEXT: Color = Color { r: 240.0, g: 240.0, b: 240.0, a: 255.0 };
+/// above dialogs, a tooltip always belongs to the topmost thing under the pointer
+const Z_INDEX: i16 = i16::MAX;
+/// milliseconds
+pub const DEFAULT_DELAY: f32 = 500.0;
+
+/// call right after the element is configured, `delay` is in milliseconds
+pub fn tooltip(api: &mut API, id: u32, text: &str, delay: f32) {
+    if !api.ui_layout.hovered() {
+        if api.tooltip_hover.is_some_and(|(hovered, _)| hovered == id) {
+            api.tooltip_hover = None;
+        }
+        return
+    }
+
+    let hovered_since = match api.tooltip_hover {
+        Some((hovered, since)) if hovered == id => since,
+        _ => {
+            let now = Instant::now();
+            api.tooltip_hover = Some((id, now));
+            now
+        }
+    };
+
+    // nothing else redraws while the pointer sits still, so the loop is woken once the delay is up
+    let shown_at = hovered_since + Duration::from_secs_f32(delay.max(0.0) / 1000.0);
+    if Instant::now() < shown_at {
+        api.request_redraw_at(shown_at);
+        return
+    }
+
+    api.ui_layout.open_element();
+    api.ui_layout.configure_element(&ElementConfiguration::new()
+        .floating()
+        .floating_attach_to_parent_at_bottom_center()
+        .floating_attach_element_at_top_center()
+        .floating_offset(0.0, 6.0)
+        .floating_z_index(Z_INDEX)
+        // the tooltip must not steal the hover that keeps it open
+        .floating_pointer_pass_through()
+        .padding_all(6)
+        .color(BACKGROUND)
+        .radius_all(4.0)
+        .end()
+    );
+    let mut text_config = TextConfig::default();
+    text_config.font_size(14).color(TEXT).parse();
+    api.ui_layout.add_text_element(text, &text_config, false);
+    api.ui_layout.close_element();
+}
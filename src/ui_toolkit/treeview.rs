@@ -5,9 +5,62 @@ use symbol_table::GlobalSymbol;
 use telera_layout::{Color, TextConfig};
 use telera_layout::ElementConfiguration;
 
-use crate::{CustomElement, ui_toolkit::ui_shapes::LineConfig, API, EventContext, EventHandler};
+use crate::{CustomElement, ui_toolkit::{checkbox::Binding, textbox::text_edit, ui_shapes::LineConfig}, API, EventContext, EventHandler};
 use crate::ParserDataAccess;
 
+const SELECTED: Color = Color { r: 200.0, g: 220.0, b: 255.0, a: 255.0 };
+const CHECK_BOX: Color = Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
+const CHECK_BORDER: Color = Color { r: 110.0, g: 110.0, b: 110.0, a: 255.0 };
+const CHECK_MARK: Color = Color { r: 0.0, g: 96.0, b: 255.0, a: 255.0 };
+
+/// how clicking a label selects nodes
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TreeSelectionMode {
+    #[default]
+    None,
+    Single,
+    /// ctrl toggles a node, shift selects the range from the last clicked node
+    Multi,
+}
+
+/// a node's checkbox, written back with `set_bool` when it's toggled and `bool_name` is set
+#[derive(Clone)]
+pub struct TreeViewCheckbox {
+    pub checked: bool,
+    pub bool_name: Option<GlobalSymbol>,
+    pub list_data: Option<(GlobalSymbol, usize)>,
+}
+
+/// selection and renaming a treeview keeps between frames, nodes are known by their labels
+#[derive(Default)]
+pub struct TreeViewState {
+    selected: Vec<String>,
+    anchor: Option<String>,
+    /// the node being renamed and what's been typed
+    renaming: Option<(String, String)>,
+}
+
+impl TreeViewState {
+    pub fn selected(&self) -> &[String] {
+        &self.selected
+    }
+
+    pub(crate) fn is_renaming(&self) -> bool {
+        self.renaming.is_some()
+    }
+}
+
+/// one treeview's layout pass
+struct TreeContext<'tree, UserEvent: FromStr+Clone+PartialEq+Debug+EventHandler> {
+    state: TreeViewState,
+    mode: TreeSelectionMode,
+    toggle: bool,
+    range: bool,
+    /// nodes in the order they're drawn, for range selection
+    order: Vec<(&'tree str, Option<&'tree TreeViewEvents<UserEvent>>)>,
+    clicked: Option<&'tree str>,
+}
+
 #[derive(Clone)]
 pub struct TreeViewEvents<UserEvent: FromStr+Clone+PartialEq+Debug+EventHandler> {
     pub bubble_left_clicked: Option<UserEvent>, 
@@ -16,6 +69,15 @@ pub struct TreeViewEvents<UserEvent: FromStr+Clone+PartialEq+Debug+EventHandler>
     pub label_right_clicked: Option<UserEvent>,
     pub icon_left_clicked: Option<UserEvent>, 
     pub icon_right_clicked: Option<UserEvent>,
+    /// sent when a collapsed item is opened, so its children can be loaded only once they're wanted
+    pub need_children: Option<UserEvent>,
+    /// sent for every node that's selected or deselected, `value` is 1 or 0
+    pub selection_changed: Option<UserEvent>,
+    /// sent when the checkbox is clicked, `value` is the new state as 1 or 0
+    pub checked_changed: Option<UserEvent>,
+    /// double clicking the label renames the node when set, `text` is the new name
+    pub renamed: Option<UserEvent>,
+    pub checkbox: Option<TreeViewCheckbox>,
     pub user_context: Option<EventContext>
 }
 
@@ -28,6 +90,11 @@ impl <UserEvent: FromStr+Clone+PartialEq+Debug+EventHandler> TreeViewEvents<User
             label_right_clicked: None,
             icon_left_clicked: None,
             icon_right_clicked: None,
+            need_children: None,
+            selection_changed: None,
+            checked_changed: None,
+            renamed: None,
+            checkbox: None,
             user_context: None
         }
     }
@@ -39,6 +106,11 @@ impl <UserEvent: FromStr+Clone+PartialEq+Debug+EventHandler> TreeViewEvents<User
             label_right_clicked: None,
             icon_left_clicked: None,
             icon_right_clicked: None,
+            need_children: None,
+            selection_changed: None,
+            checked_changed: None,
+            renamed: None,
+            checkbox: None,
             user_context: None
         }
     }
@@ -46,6 +118,23 @@ impl <UserEvent: FromStr+Clone+PartialEq+Debug+EventHandler> TreeViewEvents<User
         self.label_right_clicked = Some(event);
         self
     }
+    pub fn add_need_children(mut self, event: UserEvent) -> Self {
+        self.need_children = Some(event);
+        self
+    }
+    pub fn add_selection_changed(mut self, event: UserEvent) -> Self {
+        self.selection_changed = Some(event);
+        self
+    }
+    pub fn add_checkbox(mut self, checkbox: TreeViewCheckbox, event: Option<UserEvent>) -> Self {
+        self.checkbox = Some(checkbox);
+        self.checked_changed = event;
+        self
+    }
+    pub fn add_renamed(mut self, event: UserEvent) -> Self {
+        self.renamed = Some(event);
+        self
+    }
     pub fn add_context(mut self, context: EventContext) -> Self{
         self.user_context = Some(context);
         self
@@ -60,10 +149,26 @@ pub enum TreeViewItem<'frame, UserEvent: FromStr+Clone+PartialEq+Debug+EventHand
     EmptyItem{label: &'frame str, event_definitions: Option<TreeViewEvents<UserEvent>>},
     CollapsedItem{label: &'frame str, event_definitions: Option<TreeViewEvents<UserEvent>>},
     ExpandedItem{label: &'frame str, event_definitions: Option<TreeViewEvents<UserEvent>>, items: Vec<TreeViewItem<'frame, UserEvent>>},
+    /// expanded while its children are still being fetched
+    LoadingItem{label: &'frame str, event_definitions: Option<TreeViewEvents<UserEvent>>},
+}
+
+impl<'frame, UserEvent: FromStr+Clone+PartialEq+Debug+EventHandler> TreeViewItem<'frame, UserEvent> {
+    pub fn label(&self) -> &'frame str {
+        match self {
+            TreeViewItem::EmptyRoot { label, .. } |
+            TreeViewItem::Root { label, .. } |
+            TreeViewItem::EmptyItem { label, .. } |
+            TreeViewItem::CollapsedItem { label, .. } |
+            TreeViewItem::ExpandedItem { label, .. } |
+            TreeViewItem::LoadingItem { label, .. } => *label,
+        }
+    }
 }
 
 pub fn treeview<UserApp, Event>(
     name: &GlobalSymbol,
+    selection: TreeSelectionMode,
     list_data: &Option<(GlobalSymbol, usize)>,
     api: &mut API,
     user_app: &UserApp,
@@ -74,15 +179,168 @@ where
     UserApp: ParserDataAccess<Event>,
 {
     if let Some(treeview) = user_app.get_treeview(name, list_data) {
-        events = recursive_treeview_layout(api, &treeview, events);
+        let key: Binding = (*name, *list_data);
+        let mut tree = TreeContext {
+            state: api.treeviews.remove(&key).unwrap_or_default(),
+            mode: selection,
+            toggle: api.modifiers.control_key() || api.modifiers.super_key(),
+            range: api.modifiers.shift_key(),
+            order: Vec::new(),
+            clicked: None,
+        };
+        events = recursive_treeview_layout(api, &treeview, &mut tree, events);
+        events = update_selection(&mut tree, events);
+        api.treeviews.insert(key, tree.state);
+    }
+
+    events
+}
+
+/// applies this frame's label click and reports every node whose selection changed
+fn update_selection<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
+    tree: &mut TreeContext<Event>,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    let Some(clicked) = tree.clicked else {
+        return events
+    };
+    let previous = tree.state.selected.clone();
+    let anchor = tree.state.anchor.as_deref()
+        .and_then(|anchor| tree.order.iter().position(|(label, _)| *label == anchor));
+    let position = tree.order.iter().position(|(label, _)| *label == clicked);
+
+    if tree.mode == TreeSelectionMode::Multi && tree.range
+    && let Some(anchor) = anchor
+    && let Some(position) = position {
+        tree.state.selected = tree.order[anchor.min(position)..=anchor.max(position)].iter()
+            .map(|(label, _)| label.to_string())
+            .collect();
+        // the anchor stays put so the range can be grown or shrunk from it
+        return selection_events(tree, &previous, events)
     }
 
+    match tree.mode {
+        TreeSelectionMode::None => return events,
+        TreeSelectionMode::Multi if tree.toggle => {
+            match tree.state.selected.iter().position(|selected| selected == clicked) {
+                Some(index) => { tree.state.selected.remove(index); }
+                None => tree.state.selected.push(clicked.to_string()),
+            }
+        }
+        TreeSelectionMode::Single | TreeSelectionMode::Multi => {
+            tree.state.selected = vec![clicked.to_string()];
+        }
+    }
+    tree.state.anchor = Some(clicked.to_string());
+
+    selection_events(tree, &previous, events)
+}
+
+fn selection_events<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
+    tree: &TreeContext<Event>,
+    previous: &[String],
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    for (label, event_definitions) in &tree.order {
+        let was = previous.iter().any(|selected| selected == label);
+        let is = tree.state.selected.iter().any(|selected| selected == label);
+        if was != is
+        && let Some(eventsd) = event_definitions
+        && let Some(selection_event) = eventsd.selection_changed.clone() {
+            events.push((selection_event, node_context(label, eventsd, Some(if is {1.0} else {0.0}))));
+        }
+    }
     events
 }
 
-fn recursive_treeview_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
+fn node_context<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(text: &str, eventsd: &TreeViewEvents<Event>, value: Option<f32>) -> Option<EventContext> {
+    match &eventsd.user_context {
+        Some(cc) => Some(EventContext{text:Some(text.to_string()),code:cc.code,code2:cc.code2,value:value.or(cc.value)}),
+        None => Some(EventContext { text: Some(text.to_string()), code: None, code2: None, value })
+    }
+}
+
+/// checkbox, then the label or its rename box, shared by every kind of node
+fn add_treeview_label<'tree, Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
     api: &mut API,
-    treeview: &TreeViewItem<Event>,
+    tree: &mut TreeContext<'tree, Event>,
+    label: &'tree str,
+    event_definitions: Option<&'tree TreeViewEvents<Event>>,
+    label_config: &TextConfig,
+    mut events: Vec::<(Event, Option<EventContext>)>
+) -> Vec::<(Event, Option<EventContext>)>
+{
+    tree.order.push((label, event_definitions));
+
+    if let Some(eventsd) = event_definitions
+    && let Some(checkbox) = &eventsd.checkbox {
+        api.ui_layout.open_element();
+        let hovered = api.ui_layout.hovered();
+        api.ui_layout.configure_element(&ElementConfiguration::new()
+            .x_fixed(12.0)
+            .y_fixed(12.0)
+            .border_all(1)
+            .border_color(CHECK_BORDER)
+            .color(match checkbox.checked {
+                true => CHECK_MARK,
+                false => CHECK_BOX,
+            })
+            .radius_all(2.0)
+        );
+        api.ui_layout.close_element();
+
+        if hovered && api.left_mouse_clicked {
+            if let Some(bool_name) = checkbox.bool_name {
+                api.bool_writes.set((bool_name, checkbox.list_data), !checkbox.checked);
+            }
+            if let Some(checked_event) = eventsd.checked_changed.clone() {
+                events.push((checked_event, node_context(label, eventsd, Some(if checkbox.checked {0.0} else {1.0}))));
+            }
+        }
+    }
+
+    if let Some((renamed, mut buffer)) = tree.state.renaming.take() {
+        if renamed == label {
+            match text_edit(api, &mut buffer, label_config) {
+                Some(true) => if let Some(eventsd) = event_definitions
+                && let Some(renamed_event) = eventsd.renamed.clone() {
+                    events.push((renamed_event, node_context(&buffer, eventsd, None)));
+                }
+                Some(false) => {}
+                None => tree.state.renaming = Some((renamed, buffer)),
+            }
+            return events
+        }
+        tree.state.renaming = Some((renamed, buffer));
+    }
+
+    api.ui_layout.open_element();
+    let hovered = api.ui_layout.hovered();
+    api.ui_layout.configure_element(&ElementConfiguration::new());
+    api.ui_layout.add_text_element(
+        label, 
+        label_config,
+        false,
+    );
+    api.ui_layout.close_element();
+
+    if hovered && api.left_mouse_clicked {
+        tree.clicked = Some(label);
+    }
+    if hovered && api.left_mouse_double_clicked
+    && event_definitions.is_some_and(|eventsd| eventsd.renamed.is_some()) {
+        tree.state.renaming = Some((label.to_string(), label.to_string()));
+    }
+
+    events
+}
+
+fn recursive_treeview_layout<'tree, Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
+    api: &mut API,
+    treeview: &'tree TreeViewItem<Event>,
+    tree: &mut TreeContext<'tree, Event>,
     mut events: Vec::<(Event, Option<EventContext>)>
 ) -> Vec::<(Event, Option<EventContext>)>
 {
@@ -95,13 +353,14 @@ fn recursive_treeview_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
     events = add_treeview_image_to_layout(
         treeview,
         api,
+        tree,
         events,
     );
 
     match treeview {
         TreeViewItem::Root{label:_, event_definitions:_, items} => {
             for item in items {
-                events = recursive_treeview_layout(api, item, events);
+                events = recursive_treeview_layout(api, item, tree, events);
             }
         }
         TreeViewItem::ExpandedItem{label:_, event_definitions:_, items} => {
@@ -131,11 +390,27 @@ fn recursive_treeview_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
                 );
                 
                 for item in items {
-                    events = recursive_treeview_layout(api, item, events);
+                    events = recursive_treeview_layout(api, item, tree, events);
                 }
                 api.ui_layout.close_element();
             api.ui_layout.close_element();
         }
+        TreeViewItem::LoadingItem{label:_, event_definitions:_} => {
+            api.ui_layout.open_element();
+            api.ui_layout.configure_element(&ElementConfiguration::new()
+                .x_grow()
+                .padding_left(23)
+            );
+                api.ui_layout.add_text_element(
+                    "loading...",
+                    &TextConfig::new()
+                        .color(Color{r:120.0,g:120.0,b:120.0,a:255.0})
+                        .font_size(12)
+                        .end(),
+                    false,
+                );
+            api.ui_layout.close_element();
+        }
         _ => {}
     }
     api.ui_layout.close_element();
@@ -143,9 +418,10 @@ fn recursive_treeview_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
     events
 }
 
-fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
-    treeview_type: &TreeViewItem<Event>,
+fn add_treeview_image_to_layout<'tree, Event: FromStr+Clone+PartialEq+Debug+EventHandler>(
+    treeview_type: &'tree TreeViewItem<Event>,
     api: &mut API,
+    tree: &mut TreeContext<'tree, Event>,
     mut events: Vec::<(Event, Option<EventContext>)>,
 ) -> Vec::<(Event, Option<EventContext>)>
 {
@@ -179,6 +455,9 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
         container_config = container_config.color(blue).end();
         label_config = label_config.color(white).end();
     }
+    else if tree.state.selected.iter().any(|selected| selected == treeview_type.label()) {
+        container_config = container_config.color(SELECTED).end();
+    }
 
     api.ui_layout.configure_element(&container_config);
     match treeview_type {
@@ -196,8 +475,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                     {
                         let eee = {
                             match &eventsd.user_context {
-                                Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                                None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                                Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                                None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                             }
                         };
                         events.push((left_click_event.clone(), eee));
@@ -206,8 +485,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                     {
                         let eee = {
                             match &eventsd.user_context {
-                                Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                                None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                                Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                                None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                             }
                         };
                         events.push((right_click_event.clone(), eee));
@@ -223,13 +502,9 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 api.ui_layout.close_element();
             api.ui_layout.close_element();
 
-            api.ui_layout.add_text_element(
-                label, 
-                &label_config,
-                false,
-            );
+            events = add_treeview_label(api, tree, label, event_definitions.as_ref(), &label_config, events);
         }
-        TreeViewItem::Root{label, event_definitions:_, items:_} => {
+        TreeViewItem::Root{label, event_definitions, items:_} => {
             api.ui_layout.open_element();
 
             api.ui_layout.configure_element(&ElementConfiguration::new()
@@ -247,11 +522,7 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 api.ui_layout.close_element();
             api.ui_layout.close_element();
 
-            api.ui_layout.add_text_element(
-                label, 
-                &label_config,
-                false,
-            );
+            events = add_treeview_label(api, tree, label, event_definitions.as_ref(), &label_config, events);
         }
         TreeViewItem::EmptyItem{label, event_definitions} => {
             if api.right_mouse_clicked
@@ -259,8 +530,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
             && let Some(right_click_event) = eventsd.label_right_clicked.clone() {
                     let eee = {
                     match &eventsd.user_context {
-                        Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                        None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                        Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                        None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                     }
                 };
                 events.push((right_click_event.clone(), eee));
@@ -273,8 +544,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 {
                     let eee = {
                         match &eventsd.user_context {
-                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                         }
                     };
                     events.push((left_click_event.clone(), eee));
@@ -283,8 +554,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 && let Some(right_click_event) = eventsd.bubble_right_clicked.clone() {
                         let eee = {
                         match &eventsd.user_context {
-                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                         }
                     };
                     events.push((right_click_event.clone(), eee));
@@ -306,11 +577,7 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 api.ui_layout.close_element();
             api.ui_layout.close_element();
             
-            api.ui_layout.add_text_element(
-                label, 
-                &label_config,
-                false,
-            );
+            events = add_treeview_label(api, tree, label, event_definitions.as_ref(), &label_config, events);
         }
         TreeViewItem::CollapsedItem { label, event_definitions } => {
 
@@ -319,8 +586,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
             && let Some(right_click_event) = eventsd.label_right_clicked.clone() {
                     let eee = {
                     match &eventsd.user_context {
-                        Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                        None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                        Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                        None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                     }
                 };
                 events.push((right_click_event.clone(), eee));
@@ -329,12 +596,22 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
             api.ui_layout.open_element();
 
             if api.ui_layout.hovered() && let Some (eventsd) = event_definitions {
+                if api.left_mouse_clicked && let Some(need_children_event) = eventsd.need_children.clone()
+                {
+                    let eee = {
+                        match &eventsd.user_context {
+                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
+                        }
+                    };
+                    events.push((need_children_event, eee));
+                }
                 if api.left_mouse_clicked && let Some(left_click_event) = eventsd.bubble_left_clicked.clone()
                 {
                     let eee = {
                         match &eventsd.user_context {
-                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                         }
                     };
                     events.push((left_click_event.clone(), eee));
@@ -343,8 +620,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 && let Some(right_click_event) = eventsd.bubble_right_clicked.clone() {
                         let eee = {
                         match &eventsd.user_context {
-                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                         }
                     };
                     events.push((right_click_event.clone(), eee));
@@ -356,20 +633,16 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
             );
             api.ui_layout.close_element();
 
-            api.ui_layout.add_text_element(
-                label, 
-                &label_config,
-                false,
-            );
+            events = add_treeview_label(api, tree, label, event_definitions.as_ref(), &label_config, events);
         }
-        TreeViewItem::ExpandedItem { label, event_definitions, items: _ } => {
+        TreeViewItem::ExpandedItem { label, event_definitions, .. } | TreeViewItem::LoadingItem { label, event_definitions } => {
             if api.right_mouse_clicked
             && let Some (eventsd) = event_definitions
             && let Some(right_click_event) = eventsd.label_right_clicked.clone() {
                     let eee = {
                     match &eventsd.user_context {
-                        Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                        None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                        Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                        None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                     }
                 };
                 events.push((right_click_event.clone(), eee));
@@ -382,8 +655,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 {
                     let eee = {
                         match &eventsd.user_context {
-                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                         }
                     };
                     events.push((left_click_event.clone(), eee));
@@ -392,8 +665,8 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
                 && let Some(right_click_event) = eventsd.bubble_right_clicked.clone() {
                         let eee = {
                         match &eventsd.user_context {
-                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2}),
-                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None })
+                            Some(cc) => Some(EventContext{text:Some(label.to_string()),code:cc.code,code2:cc.code2,value:cc.value}),
+                            None => Some(EventContext { text: Some(label.to_string()), code: None, code2: None, value: None })
                         }
                     };
                     events.push((right_click_event.clone(), eee));
@@ -405,11 +678,7 @@ fn add_treeview_image_to_layout<Event: FromStr+Clone+PartialEq+Debug+EventHandle
             );
             api.ui_layout.close_element();
 
-            api.ui_layout.add_text_element(
-                label, 
-                &label_config,
-                false,
-            );
+            events = add_treeview_label(api, tree, label, event_definitions.as_ref(), &label_config, events);
         }
     }
     api.ui_layout.close_element();
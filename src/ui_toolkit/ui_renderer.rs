@@ -12,12 +12,13 @@ use lyon::tessellation::*;
 
 use image::{DynamicImage, RgbImage};
 use std::collections::HashMap;
-use std::ops::{Add, Div, Mul, Sub};
+use std::ops::{Add, Div, Mul, Range, Sub};
+use std::sync::Arc;
 use wgpu::util::DeviceExt;
 
 use telera_layout::{MeasureText, RenderCommand, Vec2};
 
-use crate::ui_toolkit::ui_shapes::CustomElement;
+use crate::ui_toolkit::ui_shapes::{CustomBounds, CustomElement, CustomElementRenderer, TextMarks};
 
 pub struct TextLine {
     line: glyphon::Buffer,
@@ -30,7 +31,71 @@ pub struct TextLine {
 #[derive(Debug)]
 pub enum CustomLayoutSettings {
     Radii{top_left:f32,top_right:f32,bottom_left:f32,bottom_right:f32},
-    Inverted
+    Inverted,
+    DepthTested,
+    Unsnapped,
+}
+
+pub static DEPTH_TESTED: CustomLayoutSettings = CustomLayoutSettings::DepthTested;
+pub static UNSNAPPED: CustomLayoutSettings = CustomLayoutSettings::Unsnapped;
+
+fn is_depth_tested(settings: Option<&CustomLayoutSettings>) -> bool {
+    matches!(settings, Some(CustomLayoutSettings::DepthTested))
+}
+
+fn is_square(radii: &BorderRadii) -> bool {
+    radii.top_left <= 0.0 && radii.top_right <= 0.0 && radii.bottom_left <= 0.0 && radii.bottom_right <= 0.0
+}
+
+/// grows `last` over `next` when one draw call can do both.
+/// untextured vertices don't sample the atlas, so they draw fine with whichever atlas is bound.
+/// quad batches count instances and the rest count indices, so the two never merge
+fn merge_batch(last: &mut RenderBatch, next: &RenderBatch) -> bool {
+    match (&mut *last, next) {
+        (RenderBatch::Basic { end, quads, .. } | RenderBatch::Atlas { end, quads, .. }, RenderBatch::Basic { begin, end: next_end, quads: next_quads })
+        if *end == *begin && quads == next_quads => {
+            *end = *next_end;
+            true
+        }
+        (RenderBatch::Atlas { end, atlas, quads, .. }, RenderBatch::Atlas { begin, end: next_end, atlas: next_atlas, quads: next_quads })
+        if *end == *begin && atlas == next_atlas && quads == next_quads => {
+            *end = *next_end;
+            true
+        }
+        (RenderBatch::Basic { begin: first, end, quads }, RenderBatch::Atlas { begin, end: next_end, atlas, quads: next_quads })
+        if *end == *begin && quads == next_quads => {
+            *last = RenderBatch::Atlas { begin: *first, end: *next_end, atlas: atlas.clone(), quads: *quads };
+            true
+        }
+        (RenderBatch::Scissor { end, position, size, quads, .. }, RenderBatch::Scissor { begin, end: next_end, position: next_position, size: next_size, quads: next_quads })
+        if *end == *begin && position == next_position && size == next_size && quads == next_quads => {
+            *end = *next_end;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// quad batches count instances of the unit square, the rest count indices
+fn draw_batch(render_pass: &mut wgpu::RenderPass, range: Range<u32>, quads: bool) {
+    match quads {
+        true => render_pass.draw(0..6, range),
+        false => render_pass.draw_indexed(range, 0, 0..1),
+    }
+}
+
+fn is_snapped(settings: Option<&CustomLayoutSettings>) -> bool {
+    !matches!(settings, Some(CustomLayoutSettings::Unsnapped))
+}
+
+/// where the ui is drawn relative to the 3d scene
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum UILayer {
+    /// ui is drawn after the scene, on top of it
+    #[default]
+    Overlay,
+    /// ui is drawn before the scene, so models cover it
+    Underlay,
 }
 
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -41,7 +106,7 @@ pub struct UIColor {
     pub b: f32,
 }
 
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
 pub struct UIPosition {
     pub x: f32,
@@ -169,6 +234,8 @@ pub struct UIVertex {
     pub position: UIPosition,
     pub texture: u32,
     pub color: UIColor,
+    /// 0 to 1, textured vertices scale the sampled alpha by it
+    pub alpha: f32,
 }
 
 impl UIVertex {
@@ -185,12 +252,13 @@ impl UIVertex {
                 g: 0.0,
                 b: 0.0,
             },
+            alpha: 1.0,
         }
     }
 
     pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
-        const ATTR: [wgpu::VertexAttribute; 3] =
-            wgpu::vertex_attr_array![0 => Float32x3, 1=>Uint32, 2 => Float32x3];
+        const ATTR: [wgpu::VertexAttribute; 4] =
+            wgpu::vertex_attr_array![0 => Float32x3, 1=>Uint32, 2 => Float32x3, 3 => Float32];
 
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<UIVertex>() as u64,
@@ -200,6 +268,45 @@ impl UIVertex {
     }
 }
 
+/// a box without rounded corners, the quad pipeline stretches a unit square over it
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct UIQuad {
+    /// top left corner in device pixels
+    pub position: UIPosition,
+    pub size: [f32; 2],
+    pub texture: u32,
+    pub color: UIColor,
+    pub alpha: f32,
+    /// atlas coordinates of the top left and bottom right corners, only textured quads sample them
+    pub uv: [f32; 4],
+}
+
+impl UIQuad {
+    pub fn new((x, y, width, height): (f32, f32, f32, f32), z: f32, color: UIColor, alpha: f32) -> Self {
+        Self {
+            position: UIPosition { x, y, z },
+            size: [width, height],
+            texture: 0,
+            color,
+            alpha,
+            uv: [0.0; 4],
+        }
+    }
+
+    pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
+        const ATTR: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+            0 => Float32x3, 1 => Float32x2, 2 => Uint32, 3 => Float32x3, 4 => Float32, 5 => Float32x4
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<UIQuad>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTR,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct SizeUniform {
@@ -207,34 +314,159 @@ pub struct SizeUniform {
     y: f32,
 }
 
+/// `quads` batches are ranges of `UIRenderer::instances`, the others ranges of `UIRenderer::indices`
 pub enum RenderBatch {
     Basic {
         begin: u32,
         end: u32,
+        quads: bool,
     },
     Scissor {
         begin: u32,
         end: u32,
         position: UIPosition,
         size: UIPosition,
+        quads: bool,
     },
     Atlas {
         begin: u32,
         end: u32,
         atlas: String,
+        quads: bool,
+    },
+    DepthTest {
+        enabled: bool,
+    },
+    Custom {
+        name: String,
+        bounds: CustomBounds,
+        scissor: Option<(UIPosition, UIPosition)>,
     },
 }
 
 #[repr(C)]
+/// look of the overlay scrollbars drawn over scroll containers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollbarStyle {
+    /// layout units
+    pub width: f32,
+    pub thumb: [f32; 3],
+    /// no track is drawn without a color
+    pub track: Option<[f32; 3]>,
+    /// only show a container's bars while the pointer is over it
+    pub auto_hide: bool,
+}
+
+impl Default for ScrollbarStyle {
+    fn default() -> Self {
+        Self {
+            width: 6.0,
+            thumb: [0.55, 0.55, 0.55],
+            track: None,
+            auto_hide: true,
+        }
+    }
+}
+
+/// a scrollbar as drawn last frame in layout units, kept to hit test the pointer against
+#[derive(Debug, Clone, Copy)]
+pub struct Scrollbar {
+    pub vertical: bool,
+    /// x, y, width, height
+    pub track: (f32, f32, f32, f32),
+    pub thumb: (f32, f32, f32, f32),
+    /// content scrolled for every layout unit the thumb moves
+    pub content_per_unit: f32,
+    /// content scrolled by a click on the track
+    pub page: f32,
+}
+
+/// a text editor line as drawn last frame in layout units, kept to place clicks between its glyphs
+#[derive(Debug, Clone)]
+pub struct TextLayout {
+    pub editor: u32,
+    /// byte offset of the line in the editor's text
+    pub line_start: usize,
+    /// top and height of every row the line wrapped into,
+    /// with the byte offsets into the line the cursor can sit at and their x
+    pub rows: Vec<(f32, f32, Vec<(usize, f32)>)>,
+}
+
+impl TextLayout {
+    /// the offset into the line closest to `pointer`, rows above and below the line count as its first and last
+    pub fn hit(&self, pointer: (f32, f32)) -> usize {
+        self.rows.iter()
+            .find(|(top, height, _)| pointer.1 < top + height)
+            .or(self.rows.last())
+            .and_then(|(_, _, stops)| stops.iter().min_by(|a, b| (a.1 - pointer.0).abs().total_cmp(&(b.1 - pointer.0).abs())))
+            .map(|(offset, _)| *offset)
+            .unwrap_or(0)
+    }
+
+    /// top, height and x of the cursor at `offset`, on a wrap it goes to the start of the next row
+    pub fn caret(&self, offset: usize) -> Option<(f32, f32, f32)> {
+        self.rows.iter().rev()
+            .find(|(_, _, stops)| stops.iter().any(|(stop, _)| *stop <= offset) && stops.iter().any(|(stop, _)| *stop >= offset))
+            .and_then(|(top, height, stops)| {
+                // inside a ligature the cursor sits at its start
+                let (_, x) = stops.iter().filter(|(stop, _)| *stop <= offset).max_by_key(|(stop, _)| *stop)?;
+                Some((*top, *height, *x))
+            })
+    }
+}
+
+const MIN_THUMB: f32 = 20.0;
+/// printable ascii, rasterized ahead of time for every font at every monitor's scale
+const WARM_TEXT: &str = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+/// the font sizes layouts use most, in layout units
+const WARM_SIZES: [f32; 4] = [12.0, 14.0, 16.0, 20.0];
+/// glyphs are rasterized at quarter pixel offsets, text lands on any of them
+const WARM_OFFSETS: [f32; 4] = [0.0, 0.25, 0.5, 0.75];
+
+/// a font waiting for the renderer
+pub enum FontFace {
+    /// a font file's contents
+    Data(Vec<u8>),
+    /// installed families, the first one found is used
+    Installed(Vec<String>),
+}
+
+/// a scissored element and the extent of everything drawn inside it
+struct ScrollArea {
+    clip: (f32, f32, f32, f32),
+    min: (f32, f32),
+    max: (f32, f32),
+}
+
+/// a z-indexed element's subtree, held back until the rest of its pass is drawn
+struct Layer<'render_pass> {
+    z_index: i16,
+    bounds: (f32, f32, f32, f32),
+    /// the clip it was in, in device pixels
+    scissor: Option<(UIPosition, UIPosition)>,
+    /// scissors opened inside the subtree and not closed yet
+    scissors: u32,
+    commands: Vec<RenderCommand<'render_pass, UIImageDescriptor, CustomElement, CustomLayoutSettings>>,
+}
+
 pub struct UIRenderer {
     pub vertices: Vec<UIVertex>,
     pub indices: Vec<u32>,
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
+    pub instances: Vec<UIQuad>,
+    pub instance_buffer: wgpu::Buffer,
 
     pub batches: Vec<RenderBatch>,
     pub batch_index_begin: u32,
     pub batch_index_end: u32,
+    /// whether the open batch is counted in instances rather than indices
+    pub batch_quads: bool,
+    /// draw calls the last frame took, after neighbouring batches were merged
+    pub draw_calls: u32,
+    /// kept between shapes so their buffers aren't allocated for every one
+    fill_tessellator: FillTessellator,
+    stroke_tessellator: StrokeTessellator,
 
     pub scissor_active: bool,
     pub scissor_position: UIPosition,
@@ -242,13 +474,24 @@ pub struct UIRenderer {
 
     pub staged_images: Vec<(String, DynamicImage)>,
     pub atlas_map: HashMap<String, wgpu::BindGroup>,
+    /// images from `api.update_image_from_rgba`, their shown frame is in `atlas_map`
+    pub streamed_images: HashMap<String, StreamedImage>,
     pub active_atlas: String,
     pub new_atlas_binding_required: bool,
 
     pub render_pipeline: Option<wgpu::RenderPipeline>,
+    pub depth_tested_pipeline: Option<wgpu::RenderPipeline>,
+    pub quad_pipeline: Option<wgpu::RenderPipeline>,
+    pub depth_tested_quad_pipeline: Option<wgpu::RenderPipeline>,
+    pub depth_test_active: bool,
+    pub layer: UILayer,
 
     pub font_system: FontSystem,
+    /// family by font id, id 0 and ids without a family use the default serif
+    fonts: Vec<Option<String>>,
     swash_cache: SwashCache,
+    /// scales whose common glyphs are already in the swash cache
+    warmed_scales: Vec<f32>,
     text_viewport: Option<glyphon::Viewport>,
     text_atlas: Option<glyphon::TextAtlas>,
     text_renderer: Option<glyphon::TextRenderer>,
@@ -261,6 +504,19 @@ pub struct UIRenderer {
     size_bind_group_layout: wgpu::BindGroupLayout,
 
     pub dpi_scale: f32,
+    /// rounds element edges and border widths to whole device pixels
+    pub pixel_snapping: bool,
+    pub custom_renderers: HashMap<String, Box<dyn CustomElementRenderer>>,
+
+    pub scrollbar_style: ScrollbarStyle,
+    pub scrollbars: Vec<Scrollbar>,
+    /// text editor lines drawn this frame
+    pub text_layouts: Vec<TextLayout>,
+    /// layout units, for auto hiding scrollbars
+    pub pointer: (f32, f32),
+    /// keeps the bars shown while one is being dragged off its container
+    pub scrollbar_dragging: bool,
+    scroll_areas: Vec<ScrollArea>,
 }
 
 impl MeasureText for UIRenderer {
@@ -280,7 +536,7 @@ impl MeasureText for UIRenderer {
         self.measurement_buffer.set_text(
             &mut self.font_system,
             text,
-            Attrs::new().family(Family::Serif),
+            Attrs::new().family(font_family(&self.fonts, text_config.font_id)),
             Shaping::Advanced,
         );
         for ele in self.measurement_buffer.lines.iter_mut() {
@@ -372,8 +628,20 @@ impl UIRenderer {
                 usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
             }
         );
+        let instances = Vec::new();
+        let instance_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("ui_instances"),
+                contents: bytemuck::cast_slice(&[UIQuad::new((0.0, 0.0, 0.0, 0.0), 0.0, UIColor { r: 0.0, g: 0.0, b: 0.0 }, 1.0)]),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            }
+        );
 
         let mut font_system = FontSystem::new();
+        // color glyphs go to glyphon's color atlas, but only if some font has them
+        if !font_system.db().faces().any(|face| face.families.iter().any(|(family, _)| family.contains("Emoji"))) {
+            tracing::warn!(target: "telera::render", "no emoji font installed, emoji draw as boxes until one is added with add_fallback_font");
+        }
         let swash_cache = SwashCache::new();
         let measurement_buffer = Buffer::new(&mut font_system, Metrics::new(30.0, 42.0));
 
@@ -381,6 +649,10 @@ impl UIRenderer {
             batches: Vec::<RenderBatch>::new(),
             batch_index_begin: 0,
             batch_index_end: 0,
+            batch_quads: false,
+            draw_calls: 0,
+            fill_tessellator: FillTessellator::new(),
+            stroke_tessellator: StrokeTessellator::new(),
             scissor_active: false,
             scissor_position: UIPosition::new(),
             scissor_size: UIPosition::new(),
@@ -389,23 +661,41 @@ impl UIRenderer {
             vertices,
             indices,
             index_buffer,
+            instances,
+            instance_buffer,
 
             staged_images: Vec::<(String, DynamicImage)>::new(),
             atlas_map: atlas_dictionary,
+            streamed_images: HashMap::new(),
             active_atlas,
             new_atlas_binding_required: false,
 
             render_pipeline: None,
+            depth_tested_pipeline: None,
+            quad_pipeline: None,
+            depth_tested_quad_pipeline: None,
+            depth_test_active: false,
+            layer: UILayer::Overlay,
 
             font_system,
+            fonts: Vec::new(),
             swash_cache,
+            warmed_scales: Vec::new(),
             text_viewport: None,
             text_atlas: None,
             text_renderer: None,
             measurement_buffer,
             lines: Vec::<TextLine>::new(),
             dpi_scale: 1.0,
+            pixel_snapping: true,
             viewport_size: (1.0,1.0),
+            custom_renderers: HashMap::new(),
+            scrollbar_style: ScrollbarStyle::default(),
+            scrollbars: Vec::new(),
+            text_layouts: Vec::new(),
+            pointer: (0.0, 0.0),
+            scrollbar_dragging: false,
+            scroll_areas: Vec::new(),
             size_buffer,
             size_bind_group,
             size_bind_group_layout
@@ -438,6 +728,19 @@ impl UIRenderer {
         else {
             queue.write_buffer(&self.index_buffer, 0, slice);
         }
+
+        let slice = bytemuck::cast_slice(self.instances.as_slice());
+        if slice.len() > self.instance_buffer.size() as usize {
+            let instance_buffer_descriptor = wgpu::util::BufferInitDescriptor {
+                label: Some("ui_instances"),
+                contents: bytemuck::cast_slice(&self.instances),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            };
+            self.instance_buffer = device.create_buffer_init(&instance_buffer_descriptor);
+        }
+        else {
+            queue.write_buffer(&self.instance_buffer, 0, slice);
+        }
     }
 
     pub fn build_shaders(
@@ -461,6 +764,45 @@ impl UIRenderer {
             },
         ));
 
+        ui_pipeline_builder.set_depth_test(true);
+
+        self.depth_tested_pipeline = Some(ui_pipeline_builder.build_pipeline(
+            &device,
+            &self.size_bind_group_layout,
+            wgpu::MultisampleState {
+                count: multi_sample_count,
+                mask: 1,
+                alpha_to_coverage_enabled: false,
+            },
+        ));
+
+        let mut quad_pipeline_builder = UIPipeline::new(config.format);
+
+        quad_pipeline_builder.add_buffer_layout(UIQuad::get_layout());
+        quad_pipeline_builder.set_vertex_entry("vs_quad");
+
+        self.quad_pipeline = Some(quad_pipeline_builder.build_pipeline(
+            &device,
+            &self.size_bind_group_layout,
+            wgpu::MultisampleState {
+                count: multi_sample_count,
+                mask: 1,
+                alpha_to_coverage_enabled: false,
+            },
+        ));
+
+        quad_pipeline_builder.set_depth_test(true);
+
+        self.depth_tested_quad_pipeline = Some(quad_pipeline_builder.build_pipeline(
+            &device,
+            &self.size_bind_group_layout,
+            wgpu::MultisampleState {
+                count: multi_sample_count,
+                mask: 1,
+                alpha_to_coverage_enabled: false,
+            },
+        ));
+
         let cache = Cache::new(&device);
         let mut atlas = TextAtlas::new(&device, &queue, &cache, config.format);
         let text_renderer = TextRenderer::new(
@@ -518,10 +860,14 @@ impl UIRenderer {
         self.add_atlas(&device, &queue);
         self.vertices.clear();
         self.indices.clear();
+        self.instances.clear();
 
         self.batches.clear();
         self.batch_index_begin = 0;
         self.batch_index_end = 0;
+        self.batch_quads = false;
+        self.draw_calls = 0;
+        self.depth_test_active = false;
 
         match self.render_pipeline.as_mut() {
             None => return,
@@ -549,6 +895,7 @@ impl UIRenderer {
             self.batches.push(RenderBatch::Basic {
                 begin: self.batch_index_begin,
                 end: self.batch_index_end,
+                quads: self.batch_quads,
             });
             self.batch_index_begin = self.batch_index_end;
         }
@@ -590,6 +937,7 @@ impl UIRenderer {
                         end: self.batch_index_end,
                         position: self.scissor_position,
                         size: self.scissor_size,
+                        quads: self.batch_quads,
                     });
                     self.batch_index_begin = self.batch_index_end;
                 }
@@ -611,6 +959,7 @@ impl UIRenderer {
                         end: self.batch_index_end,
                         position: self.scissor_position,
                         size: self.scissor_size,
+                        quads: self.batch_quads,
                     });
                     self.batch_index_begin = self.batch_index_end;
                 }
@@ -624,6 +973,223 @@ impl UIRenderer {
         self.new_atlas_binding_required = true;
     }
 
+    /// depth tested geometry is pushed behind the rest of the ui and hidden by any scene geometry in front of it
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        if enabled == self.depth_test_active {
+            return;
+        }
+
+        match self.scissor_active {
+            true => {
+                if self.batch_index_end > self.batch_index_begin {
+                    self.batches.push(RenderBatch::Scissor {
+                        begin: self.batch_index_begin,
+                        end: self.batch_index_end,
+                        position: self.scissor_position,
+                        size: self.scissor_size,
+                        quads: self.batch_quads,
+                    });
+                    self.batch_index_begin = self.batch_index_end;
+                }
+            }
+            false => {
+                self.batch();
+            }
+        }
+
+        self.batches.push(RenderBatch::DepthTest { enabled });
+        self.depth_test_active = enabled;
+    }
+
+    /// scales a layout box into device pixels, rounding the edges (not the size) so neighbours stay flush
+    fn device_box(&self, x: f32, y: f32, width: f32, height: f32, snapped: bool) -> (f32, f32, f32, f32) {
+        let left = x * self.dpi_scale;
+        let top = y * self.dpi_scale;
+        let right = (x + width) * self.dpi_scale;
+        let bottom = (y + height) * self.dpi_scale;
+
+        match self.pixel_snapping && snapped {
+            true => (left.round(), top.round(), right.round() - left.round(), bottom.round() - top.round()),
+            false => (left, top, right - left, bottom - top),
+        }
+    }
+
+    /// overlay bars along the right and bottom edges of a scroll area whose content doesn't fit
+    fn draw_scrollbars(&mut self, area: ScrollArea, z: f32) {
+        if area.min.0 > area.max.0 {
+            return
+        }
+        let (x, y, width, height) = area.clip;
+        let style = self.scrollbar_style;
+        let hovered = self.pointer.0 >= x && self.pointer.0 <= x + width
+            && self.pointer.1 >= y && self.pointer.1 <= y + height;
+        let shown = !style.auto_hide || hovered || self.scrollbar_dragging;
+
+        for vertical in [true, false] {
+            let (start, view, content_min, content_max) = match vertical {
+                true => (y, height, area.min.1, area.max.1),
+                false => (x, width, area.min.0, area.max.0),
+            };
+            // content that hasn't been scrolled starts at the edge of the area, padding or not
+            let content_start = content_min.min(start);
+            let content = content_max.max(start + view) - content_start;
+            if content <= view + 0.5 {
+                continue
+            }
+
+            let thumb_length = (view / content * view).clamp(MIN_THUMB.min(view), view);
+            let travel = view - thumb_length;
+            let thumb_start = start + (start - content_start) / (content - view) * travel;
+            let (track, thumb) = match vertical {
+                true => (
+                    (x + width - style.width, y, style.width, height),
+                    (x + width - style.width, thumb_start, style.width, thumb_length),
+                ),
+                false => (
+                    (x, y + height - style.width, width, style.width),
+                    (thumb_start, y + height - style.width, thumb_length, style.width),
+                ),
+            };
+            self.scrollbars.push(Scrollbar {
+                vertical,
+                track,
+                thumb,
+                content_per_unit: (content - view) / travel.max(1.0),
+                page: view,
+            });
+
+            if shown {
+                self.set_depth_test(false);
+                if let Some(track_color) = style.track {
+                    self.fill_rectangle(track, 0.0, track_color, 1.0, z);
+                }
+                self.fill_rectangle(thumb, style.width / 2.0, style.thumb, 1.0, z);
+            }
+        }
+    }
+
+    /// a rounded rectangle in layout units that no layout element asked for
+    /// fills a box in device pixels with each corner rounded on its own, for element backgrounds
+    fn fill_rounded(&mut self, (x, y, width, height): (f32, f32, f32, f32), radii: &BorderRadii, color: [f32; 3], alpha: f32, z: f32) {
+        let vertex = |x: f32, y: f32| UIVertex {
+            position: UIPosition { x, y, z },
+            texture: 0,
+            color: UIColor { r: color[0], g: color[1], b: color[2] },
+            alpha,
+        };
+        if is_square(radii) {
+            self.push_quad(UIQuad::new((x, y, width, height), z, UIColor { r: color[0], g: color[1], b: color[2] }, alpha));
+            return
+        }
+        let mut builder = Path::builder();
+        builder.add_rounded_rectangle(
+            &Box2D::from_origin_and_size(Point2D::new(x, y), Size2D::new(width, height)),
+            radii,
+            path::Winding::Negative
+        );
+        let path = builder.build();
+
+        let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
+        if self.fill_tessellator.tessellate_path(
+                &path,
+                &FillOptions::default().with_tolerance(0.1).with_fill_rule(lyon::tessellation::FillRule::EvenOdd),
+                &mut BuffersBuilder::new(&mut geometry, |fill: FillVertex| vertex(fill.position().x, fill.position().y)),
+            ).is_ok() {
+            self.append_geometry(geometry);
+        }
+    }
+
+    fn fill_rectangle(&mut self, (x, y, width, height): (f32, f32, f32, f32), radius: f32, color: [f32; 3], alpha: f32, z: f32) {
+        let (x, y, width, height) = self.device_box(x, y, width, height, true);
+        let radius = radius * self.dpi_scale;
+        let vertex = |x: f32, y: f32| UIVertex {
+            position: UIPosition { x, y, z },
+            texture: 0,
+            color: UIColor { r: color[0], g: color[1], b: color[2] },
+            alpha,
+        };
+        if radius <= 0.0 {
+            self.push_quad(UIQuad::new((x, y, width, height), z, UIColor { r: color[0], g: color[1], b: color[2] }, alpha));
+            return
+        }
+        let mut builder = Path::builder();
+        builder.add_rounded_rectangle(
+            &Box2D::from_origin_and_size(Point2D::new(x, y), Size2D::new(width, height)),
+            &BorderRadii { top_left: radius, top_right: radius, bottom_left: radius, bottom_right: radius },
+            path::Winding::Negative
+        );
+        let path = builder.build();
+
+        let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
+        if self.fill_tessellator.tessellate_path(
+                &path,
+                &FillOptions::default().with_tolerance(0.1),
+                &mut BuffersBuilder::new(&mut geometry, |fill: FillVertex| vertex(fill.position().x, fill.position().y)),
+            ).is_ok() {
+            self.append_geometry(geometry);
+        }
+    }
+
+    /// tessellated geometry goes after everything already queued, in the one vertex buffer of the frame
+    fn append_geometry(&mut self, geometry: VertexBuffers<UIVertex, u32>) {
+        self.set_quads(false);
+        let offset = self.vertices.len() as u32;
+        self.vertices.extend(geometry.vertices);
+        self.indices.extend(geometry.indices.iter().map(|index| index + offset));
+        self.batch_index_end = self.indices.len() as u32;
+    }
+
+    /// a box without rounded corners is one instance of the quad pipeline, most boxes on a page don't need the tessellator
+    fn push_quad(&mut self, quad: UIQuad) {
+        if quad.size[0] <= 0.0 || quad.size[1] <= 0.0 {
+            return
+        }
+        self.set_quads(true);
+        self.instances.push(quad);
+        self.batch_index_end = self.instances.len() as u32;
+    }
+
+    /// quads and tessellated geometry are drawn by different pipelines, so switching between them closes the open batch
+    fn set_quads(&mut self, quads: bool) {
+        if quads == self.batch_quads {
+            return
+        }
+
+        match (self.new_atlas_binding_required, self.scissor_active) {
+            (true, _) => self.end_atlas(),
+            (false, true) => {
+                if self.batch_index_end > self.batch_index_begin {
+                    self.batches.push(RenderBatch::Scissor {
+                        begin: self.batch_index_begin,
+                        end: self.batch_index_end,
+                        position: self.scissor_position,
+                        size: self.scissor_size,
+                        quads: self.batch_quads,
+                    });
+                }
+            }
+            (false, false) => self.batch(),
+        }
+
+        self.batch_quads = quads;
+        self.batch_index_begin = match quads {
+            true => self.instances.len() as u32,
+            false => self.indices.len() as u32,
+        };
+        self.batch_index_end = self.batch_index_begin;
+    }
+
+    fn depth(&self, z: f32) -> f32 {
+        // underlay ui sits on the far plane so the depth tested scene drawn after it covers all of it
+        if self.layer == UILayer::Underlay {
+            return 1.0
+        }
+        match self.depth_test_active {
+            true => z + 0.9,
+            false => z,
+        }
+    }
+
     pub fn end_atlas(&mut self) {
         if !self.new_atlas_binding_required {
             return;
@@ -634,12 +1200,58 @@ impl UIRenderer {
                 begin: self.batch_index_begin,
                 end: self.batch_index_end,
                 atlas: self.active_atlas.clone(),
+                quads: self.batch_quads,
             });
             self.batch_index_begin = self.batch_index_end;
             self.new_atlas_binding_required = false;
         }
     }
 
+    /// joins batches that can be drawn with one call, so a page of thousands of boxes isn't thousands of draws
+    fn merge_batches(&mut self) {
+        let mut merged: Vec<RenderBatch> = Vec::with_capacity(self.batches.len());
+        let mut depth_tested = false;
+
+        for batch in self.batches.drain(..) {
+            if let RenderBatch::DepthTest { enabled } = batch {
+                // a switch with nothing drawn since the last one cancels it out
+                if let Some(RenderBatch::DepthTest { .. }) = merged.last() {
+                    merged.pop();
+                    depth_tested = !depth_tested;
+                }
+                if enabled != depth_tested {
+                    merged.push(batch);
+                    depth_tested = enabled;
+                }
+                continue
+            }
+            if let Some(last) = merged.last_mut()
+            && merge_batch(last, &batch) {
+                continue
+            }
+            merged.push(batch);
+        }
+
+        self.batches = merged;
+    }
+
+    /// the pipeline for the depth state and kind of batch, with the buffer its vertex stage reads
+    fn bind_pipeline(&self, render_pass: &mut wgpu::RenderPass, depth_tested: bool, quads: bool) {
+        let pipeline = match (depth_tested, quads) {
+            (false, false) => &self.render_pipeline,
+            (true, false) => &self.depth_tested_pipeline,
+            (false, true) => &self.quad_pipeline,
+            (true, true) => &self.depth_tested_quad_pipeline,
+        };
+        if let Some(pipeline) = pipeline {
+            render_pass.set_pipeline(pipeline);
+        }
+        match quads {
+            true => render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..)),
+            false => render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..)),
+        }
+    }
+
     pub fn end(
         &mut self,
         render_pass: &mut wgpu::RenderPass,
@@ -651,6 +1263,7 @@ impl UIRenderer {
             false => self.batch(),
             true => self.end_scissor(),
         }
+        self.merge_batches();
 
         match self.render_pipeline {
             None => return,
@@ -660,16 +1273,28 @@ impl UIRenderer {
                 render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
                 render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
 
+                // custom renderers can change anything on the pass, this is what gets put back afterwards
+                let mut bound_atlas = self.active_atlas.clone();
+                let mut depth_tested = false;
+                let mut bound_quads = false;
+
                 for render_batch in self.batches.iter() {
+                    if let RenderBatch::Basic { quads, .. } | RenderBatch::Scissor { quads, .. } | RenderBatch::Atlas { quads, .. } = render_batch
+                    && *quads != bound_quads {
+                        self.bind_pipeline(render_pass, depth_tested, *quads);
+                        bound_quads = *quads;
+                    }
                     match render_batch {
-                        RenderBatch::Basic { begin, end } => {
-                            render_pass.draw_indexed(*begin..*end as u32, 0, 0..1);
+                        RenderBatch::Basic { begin, end, quads } => {
+                            draw_batch(render_pass, *begin..*end, *quads);
+                            self.draw_calls += 1;
                         }
                         RenderBatch::Scissor {
                             begin,
                             end,
                             position,
                             size,
+                            quads,
                         } => {
                             render_pass.set_scissor_rect(
                                 position.x as u32,
@@ -677,7 +1302,8 @@ impl UIRenderer {
                                 size.x as u32,
                                 size.y as u32,
                             );
-                            render_pass.draw_indexed(*begin..*end, 0, 0..1);
+                            draw_batch(render_pass, *begin..*end, *quads);
+                            self.draw_calls += 1;
                             render_pass.set_scissor_rect(
                                 0,
                                 0,
@@ -685,15 +1311,39 @@ impl UIRenderer {
                                 self.viewport_size.1 as u32,
                             );
                         }
-                        RenderBatch::Atlas { begin, end, atlas } => {
+                        RenderBatch::Atlas { begin, end, atlas, quads } => {
                             match self.atlas_map.get(atlas) {
                                 None => continue,
-                                Some(atlas) => {
-                                    render_pass.set_bind_group(0, atlas, &[]);
-                                    render_pass.draw_indexed(*begin..*end, 0, 0..1);
+                                Some(atlas_bind_group) => {
+                                    render_pass.set_bind_group(0, atlas_bind_group, &[]);
+                                    draw_batch(render_pass, *begin..*end, *quads);
+                                    self.draw_calls += 1;
+                                    bound_atlas = atlas.clone();
                                 }
                             }
                         }
+                        RenderBatch::DepthTest { enabled } => {
+                            self.bind_pipeline(render_pass, *enabled, bound_quads);
+                            depth_tested = *enabled;
+                        }
+                        RenderBatch::Custom { name, bounds, scissor } => {
+                            let Some(renderer) = self.custom_renderers.get_mut(name) else {
+                                continue;
+                            };
+                            if let Some((position, size)) = scissor {
+                                render_pass.set_scissor_rect(position.x as u32, position.y as u32, size.x as u32, size.y as u32);
+                            }
+
+                            renderer.render(*bounds, render_pass, device, queue, surface_config);
+
+                            render_pass.set_scissor_rect(0, 0, self.viewport_size.0 as u32, self.viewport_size.1 as u32);
+                            self.bind_pipeline(render_pass, depth_tested, bound_quads);
+                            let atlas = self.atlas_map.get(&bound_atlas)
+                                .unwrap_or_else(|| self.atlas_map.get("default_atlas").unwrap());
+                            render_pass.set_bind_group(0, atlas, &[]);
+                            render_pass.set_bind_group(1, &self.size_bind_group, &[]);
+                            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                        }
                     }
                 }
 
@@ -719,280 +1369,464 @@ impl UIRenderer {
         let mut z: f32 = 0.1;
 
         self.begin(render_pass, device, queue);
+        self.scrollbars.clear();
+        self.text_layouts.clear();
+        self.scroll_areas.clear();
 
         //println!("{:#?}", &render_commands);
 
+        self.draw_commands(render_commands, &mut z);
+
+        self.set_depth_test(false);
+        self.end(render_pass, &device, &queue, &surface_config);
+    }
+
+    /// draws commands in order, except that the subtree of a layer element is drawn after everything around it.
+    /// a layer's subtree is the run of commands that follows it and stays inside its box
+    fn draw_commands<'render_pass>(
+        &mut self,
+        render_commands: Vec<RenderCommand<'render_pass, UIImageDescriptor, CustomElement, CustomLayoutSettings>>,
+        z: &mut f32,
+    ) {
+        let mut layers = Vec::<Layer<'render_pass>>::new();
+        let mut open_layer: Option<usize> = None;
+
         for command in render_commands {
-            match command {
-                RenderCommand::Rectangle(r) => {
-                    let mut builder = Path::builder();
-                    builder.add_rounded_rectangle(
-                        &Box2D::from_origin_and_size(
-                                Point2D::new(
-                                    r.bounding_box.x * self.dpi_scale,
-                                    r.bounding_box.y * self.dpi_scale
-                                ), 
-                                Size2D::new(
-                                    r.bounding_box.width * self.dpi_scale,
-                                    r.bounding_box.height * self.dpi_scale
-                                )
-                            ),
-                            &BorderRadii {
-                                top_left: r.corner_radii.top_left * self.dpi_scale,
-                                top_right: r.corner_radii.top_right * self.dpi_scale,
-                                bottom_left: r.corner_radii.bottom_left * self.dpi_scale,
-                                bottom_right: r.corner_radii.bottom_right * self.dpi_scale
-                            },
-                        path::Winding::Negative
-                    );
-                    let path = builder.build();
-
-                    let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
-                    let mut tessellator = FillTessellator::new();
-                    if tessellator.tessellate_path(
-                            &path,
-                            &FillOptions::default().with_tolerance(0.1).with_fill_rule(lyon::tessellation::FillRule::EvenOdd),
-                            &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| { 
-                                UIVertex {
-                                    position: UIPosition { 
-                                        x: vertex.position().x,
-                                        y: vertex.position().y,
-                                        z
-                                    },
-                                    texture: 0,
-                                    color: UIColor {
-                                        r: r.color.r / 255.0,
-                                        g: r.color.g / 255.0,
-                                        b: r.color.b / 255.0,
-                                    },
-                                }
-                            }),
-                        ).is_ok() {
-                        let mut offset_indices = geometry.indices.iter().map(|index|{index+self.vertices.len() as u32}).collect::<Vec::<u32>>();
-                        self.vertices.append(&mut geometry.vertices);
-                        self.indices.append(&mut offset_indices);
-                        self.batch_index_end = self.indices.len() as u32;
-                    }
+            if let Some((x, y, width, height)) = command_bounds(&command) {
+                for area in self.scroll_areas.iter_mut() {
+                    area.min = (area.min.0.min(x), area.min.1.min(y));
+                    area.max = (area.max.0.max(x + width), area.max.1.max(y + height));
                 }
-                RenderCommand::Border(b) => {
-                    let mut builder = Path::builder();
-                    builder.add_rounded_rectangle(
-                        &Box2D::from_origin_and_size(
-                                Point2D::new(
-                                    b.bounding_box.x * self.dpi_scale, 
-                                    b.bounding_box.y * self.dpi_scale, 
-                                ), 
-                                Size2D::new(
-                                    b.bounding_box.width * self.dpi_scale,
-                                    b.bounding_box.height * self.dpi_scale,
-                                )
-                            ),
-                            &BorderRadii { 
-                                top_left: b.corner_radii.top_left * self.dpi_scale,
-                                top_right: b.corner_radii.top_right * self.dpi_scale,
-                                bottom_left: b.corner_radii.bottom_left * self.dpi_scale,
-                                bottom_right: b.corner_radii.bottom_right * self.dpi_scale
-                            },
-                        path::Winding::Negative
-                    );
-                    let path = builder.build();
-
-                    let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
-                    let mut tessellator = StrokeTessellator::new();
-                    if tessellator.tessellate_path(
-                            &path,
-                            &StrokeOptions::default().with_line_width(b.width.top as f32),
-                            &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex  | { 
-                                UIVertex {
-                                    position: vertex.position().into(),
-                                    texture: 0,
-                                    color: UIColor {
-                                        r: b.color.r / 255.0,
-                                        g: b.color.g / 255.0,
-                                        b: b.color.b / 255.0,
-                                    }
-                                }
-                            }),
-                        ).is_ok() {
-                            let mut offset_indices = geometry.indices.iter().map(|index|{index+self.vertices.len() as u32}).collect::<Vec::<u32>>();
-                        self.vertices.append(&mut geometry.vertices);
-                        self.indices.append(&mut offset_indices);
-                        self.batch_index_end = self.indices.len() as u32;
+            }
+
+            if let Some(open) = open_layer {
+                let layer = &mut layers[open];
+                let inside = match (&command, command_bounds(&command)) {
+                    (RenderCommand::ScissorEnd, _) => layer.scissors > 0,
+                    (_, Some(bounds)) => contains(layer.bounds, bounds),
+                    (_, None) => true,
+                };
+                if inside {
+                    match command {
+                        RenderCommand::ScissorStart(_) => layer.scissors += 1,
+                        RenderCommand::ScissorEnd => layer.scissors -= 1,
+                        _ => {}
                     }
+                    layer.commands.push(command);
+                    continue
                 }
-                RenderCommand::Text(t) => self.draw_text(
-                    t.text,
-                    (t.font_size as f32) * self.dpi_scale,
-                    match t.line_height {
-                        0 => (t.font_size as f32) * 1.2 * self.dpi_scale,
-                        _ => (t.line_height as f32) * self.dpi_scale,
+                open_layer = None;
+            }
+
+            if let RenderCommand::Custom(shape) = &command
+            && let CustomElement::Layer(z_index) = &shape.data
+            && *z_index > 0 {
+                layers.push(Layer {
+                    z_index: *z_index,
+                    bounds: (shape.bounding_box.x, shape.bounding_box.y, shape.bounding_box.width, shape.bounding_box.height),
+                    scissor: self.scissor_active.then_some((self.scissor_position, self.scissor_size)),
+                    scissors: 0,
+                    commands: vec![command],
+                });
+                open_layer = Some(layers.len() - 1);
+                continue
+            }
+
+            self.draw_command(command, *z);
+            *z -= 0.0001;
+        }
+
+        // later layers of the same z-index stay on top, like siblings do
+        layers.sort_by_key(|layer| layer.z_index);
+        for mut layer in layers {
+            if let Some((position, size)) = layer.scissor {
+                self.begin_scissor(position, size);
+            }
+            let background = layer.commands.remove(0);
+            self.draw_command(background, *z);
+            *z -= 0.0001;
+            self.draw_commands(layer.commands, z);
+            if layer.scissor.is_some() {
+                self.end_scissor();
+            }
+        }
+    }
+
+    fn draw_command<'render_pass>(
+        &mut self,
+        command: RenderCommand<'render_pass, UIImageDescriptor, CustomElement, CustomLayoutSettings>,
+        z: f32,
+    ) {
+        match command {
+            RenderCommand::Rectangle(r) => {
+                self.set_depth_test(is_depth_tested(r.custom_layout_settings));
+                let z = self.depth(z);
+                let (x, y, width, height) = self.device_box(
+                    r.bounding_box.x,
+                    r.bounding_box.y,
+                    r.bounding_box.width,
+                    r.bounding_box.height,
+                    is_snapped(r.custom_layout_settings)
+                );
+                let radii = BorderRadii {
+                    top_left: r.corner_radii.top_left * self.dpi_scale,
+                    top_right: r.corner_radii.top_right * self.dpi_scale,
+                    bottom_left: r.corner_radii.bottom_left * self.dpi_scale,
+                    bottom_right: r.corner_radii.bottom_right * self.dpi_scale
+                };
+                self.fill_rounded(
+                    (x, y, width, height),
+                    &radii,
+                    [r.color.r / 255.0, r.color.g / 255.0, r.color.b / 255.0],
+                    r.color.a / 255.0,
+                    z
+                );
+            }
+            RenderCommand::Border(b) => {
+                self.set_depth_test(is_depth_tested(b.custom_layout_settings));
+                let z = self.depth(z);
+                let snapped = is_snapped(b.custom_layout_settings);
+                let (x, y, width, height) = self.device_box(
+                    b.bounding_box.x,
+                    b.bounding_box.y,
+                    b.bounding_box.width,
+                    b.bounding_box.height,
+                    snapped
+                );
+                let line_width = match self.pixel_snapping && snapped {
+                    true => (b.width.top as f32 * self.dpi_scale).round().max(1.0),
+                    false => b.width.top as f32 * self.dpi_scale,
+                };
+                // the stroke is centered on the path, inset it so the whole border lands inside the element
+                let inset = line_width / 2.0;
+                let radii = BorderRadii {
+                    top_left: b.corner_radii.top_left * self.dpi_scale,
+                    top_right: b.corner_radii.top_right * self.dpi_scale,
+                    bottom_left: b.corner_radii.bottom_left * self.dpi_scale,
+                    bottom_right: b.corner_radii.bottom_right * self.dpi_scale
+                };
+                let color = UIColor {
+                    r: b.color.r / 255.0,
+                    g: b.color.g / 255.0,
+                    b: b.color.b / 255.0,
+                };
+                let alpha = b.color.a / 255.0;
+                let vertex = |x: f32, y: f32| UIVertex {
+                    position: UIPosition { x, y, z },
+                    texture: 0,
+                    color,
+                    alpha,
+                };
+                // square corners are four quads, the same pixels a mitered stroke covers
+                if is_square(&radii) {
+                    let line_width = line_width.min(width / 2.0).min(height / 2.0);
+                    let side = height - line_width * 2.0;
+                    self.push_quad(UIQuad::new((x, y, width, line_width), z, color, alpha));
+                    self.push_quad(UIQuad::new((x, y + height - line_width, width, line_width), z, color, alpha));
+                    self.push_quad(UIQuad::new((x, y + line_width, line_width, side), z, color, alpha));
+                    self.push_quad(UIQuad::new((x + width - line_width, y + line_width, line_width, side), z, color, alpha));
+                    return
+                }
+                let mut builder = Path::builder();
+                builder.add_rounded_rectangle(
+                    &Box2D::from_origin_and_size(
+                            Point2D::new(x + inset, y + inset), 
+                            Size2D::new((width - line_width).max(0.0), (height - line_width).max(0.0))
+                        ),
+                        &radii,
+                    path::Winding::Negative
+                );
+                let path = builder.build();
+
+                let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
+                if self.stroke_tessellator.tessellate_path(
+                        &path,
+                        &StrokeOptions::default().with_line_width(line_width),
+                        &mut BuffersBuilder::new(&mut geometry, |stroke: StrokeVertex| vertex(stroke.position().x, stroke.position().y)),
+                    ).is_ok() {
+                    self.append_geometry(geometry);
+                }
+            }
+            RenderCommand::Text(t) => self.draw_text(
+                t.text,
+                t.font_id,
+                (t.font_size as f32) * self.dpi_scale,
+                match t.line_height {
+                    0 => (t.font_size as f32) * 1.2 * self.dpi_scale,
+                    _ => (t.line_height as f32) * self.dpi_scale,
+                },
+                match self.pixel_snapping {
+                    true => UIPosition {
+                        x: (t.bounding_box.x * self.dpi_scale).round(),
+                        y: (t.bounding_box.y * self.dpi_scale).round(),
+                        z,
                     },
-                    UIPosition {
+                    false => UIPosition {
                         x: t.bounding_box.x * self.dpi_scale,
                         y: t.bounding_box.y * self.dpi_scale,
                         z,
                     },
-                    match self.scissor_active {
-                        true => Some((self.scissor_position.clone(), self.scissor_size.clone())),
-                        false => None,
-                    },
-                    Color::rgb(t.color.r as u8, t.color.g as u8, t.color.b as u8),
-                    z,
-                ),
-                RenderCommand::ScissorStart(b) => self.begin_scissor(
+                },
+                match self.scissor_active {
+                    true => Some((self.scissor_position.clone(), self.scissor_size.clone())),
+                    false => None,
+                },
+                // a font color that was never set has no alpha, it's drawn opaque like it always was
+                match t.color.a {
+                    0.0 => Color::rgb(t.color.r as u8, t.color.g as u8, t.color.b as u8),
+                    alpha => Color::rgba(t.color.r as u8, t.color.g as u8, t.color.b as u8, alpha as u8),
+                },
+                z,
+            ),
+            RenderCommand::ScissorStart(b) => {
+                self.scroll_areas.push(ScrollArea {
+                    clip: (b.x, b.y, b.width, b.height),
+                    min: (f32::INFINITY, f32::INFINITY),
+                    max: (f32::NEG_INFINITY, f32::NEG_INFINITY),
+                });
+                self.begin_scissor(
                     UIPosition::xy(b.x, b.y) * self.dpi_scale,
                     UIPosition::xy(b.width, b.height) * self.dpi_scale,
-                ),
-                RenderCommand::ScissorEnd => self.end_scissor(),
-                RenderCommand::Image(image) => {
-                    let ipx = image.bounding_box.x * self.dpi_scale;
-                    let ipy = image.bounding_box.y * self.dpi_scale;
-                    let isx = image.bounding_box.width * self.dpi_scale;
-                    let isy = image.bounding_box.height * self.dpi_scale;
-                    let radii = if let Some(settings) = image.custom_layout_settings
-                    && let CustomLayoutSettings::Radii { top_left, top_right, bottom_left, bottom_right } = settings {
-                        BorderRadii {
-                            top_left: top_left * self.dpi_scale,
-                            top_right: top_right * self.dpi_scale,
-                            bottom_left: bottom_left * self.dpi_scale,
-                            bottom_right: bottom_right * self.dpi_scale
-                        }
+                )
+            }
+            RenderCommand::ScissorEnd => {
+                self.end_scissor();
+                if let Some(area) = self.scroll_areas.pop() {
+                    self.draw_scrollbars(area, z);
+                }
+            }
+            RenderCommand::Image(image) => {
+                self.set_depth_test(is_depth_tested(image.custom_layout_settings));
+                let z = self.depth(z);
+                let (ipx, ipy, isx, isy) = self.device_box(
+                    image.bounding_box.x,
+                    image.bounding_box.y,
+                    image.bounding_box.width,
+                    image.bounding_box.height,
+                    is_snapped(image.custom_layout_settings)
+                );
+                let radii = if let Some(settings) = image.custom_layout_settings
+                && let CustomLayoutSettings::Radii { top_left, top_right, bottom_left, bottom_right } = settings {
+                    BorderRadii {
+                        top_left: top_left * self.dpi_scale,
+                        top_right: top_right * self.dpi_scale,
+                        bottom_left: bottom_left * self.dpi_scale,
+                        bottom_right: bottom_right * self.dpi_scale
                     }
-                    else { 
-                        BorderRadii {
-                            top_left: 0.0 * self.dpi_scale,
-                            top_right: 0.0 * self.dpi_scale,
-                            bottom_left: 0.0 * self.dpi_scale,
-                            bottom_right: 0.0 * self.dpi_scale
-                        }
-                    };
-                    
-                    let mut builder = Path::builder();
-                    builder.add_rounded_rectangle(
-                        &Box2D::from_origin_and_size(
-                                Point2D::new(ipx, ipy), 
-                                Size2D::new(isx, isy)
-                            ),
-                            &radii,
-                        path::Winding::Negative
-                    );
-                    let path = builder.build();
-
-                    let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
-                    let mut tessellator = FillTessellator::new();
-                    if tessellator.tessellate_path(
-                            &path,
-                            &FillOptions::default().with_tolerance(0.1).with_fill_rule(lyon::tessellation::FillRule::EvenOdd),
-                            &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
-                                let x = vertex.position().x;
-                                let y = vertex.position().y;
-                                let r = (x - ipx) / isx;
-                                let g = (y - ipy) / isy;
-                                UIVertex {
-                                    position: UIPosition {x,y,z},
-                                    texture: 1,
-                                    color: UIColor {r,g,b: 0.}
-                                }
-                            }),
-                        ).is_ok() {
-                        self.bind_atlas(&image.data.atlas);
-                        let mut offset_indices = geometry.indices.iter().map(|index|{index+self.vertices.len() as u32}).collect::<Vec::<u32>>();
-                        self.vertices.append(&mut geometry.vertices);
-                        self.indices.append(&mut offset_indices);
-                        self.batch_index_end = self.indices.len() as u32;
-                        self.end_atlas();
+                }
+                else { 
+                    BorderRadii {
+                        top_left: 0.0 * self.dpi_scale,
+                        top_right: 0.0 * self.dpi_scale,
+                        bottom_left: 0.0 * self.dpi_scale,
+                        bottom_right: 0.0 * self.dpi_scale
                     }
+                };
+                let vertex = |x: f32, y: f32| UIVertex {
+                    position: UIPosition { x, y, z },
+                    texture: 1,
+                    color: UIColor { r: (x - ipx) / isx, g: (y - ipy) / isy, b: 0. },
+                    alpha: 1.0,
+                };
+                if is_square(&radii) {
+                    self.bind_atlas(&image.data.atlas);
+                    self.push_quad(UIQuad {
+                        texture: 1,
+                        uv: [0.0, 0.0, 1.0, 1.0],
+                        ..UIQuad::new((ipx, ipy, isx, isy), z, UIColor { r: 0.0, g: 0.0, b: 0.0 }, 1.0)
+                    });
+                    self.end_atlas();
+                    return
                 }
-                RenderCommand::Custom(shape) => {
-                    match shape.data {
-                        CustomElement::Circle => {
-                            let mut builder = Path::builder();
-                            builder.add_circle(
-                                Point2D::new(
-                                    (shape.bounding_box.x + (shape.bounding_box.width/2.0)) * self.dpi_scale,
-                                    (shape.bounding_box.y + (shape.bounding_box.height/2.0)) * self.dpi_scale
-                                ), 
-                                shape.bounding_box.width/2.0,
-                                path::Winding::Negative
-                            );
-                            let path = builder.build();
-
-                            let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
-                            let mut tessellator = FillTessellator::new();
-                            if tessellator.tessellate_path(
-                                    &path,
-                                    &FillOptions::default().with_tolerance(0.1).with_fill_rule(lyon::tessellation::FillRule::EvenOdd),
-                                    &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| { 
-                                        UIVertex {
-                                            position: UIPosition { 
-                                                x: vertex.position().x,
-                                                y: vertex.position().y,
-                                                z
-                                            },
-                                            texture: 0,
-                                            color: UIColor {
-                                                r: shape.background_color.r / 255.0,
-                                                g: shape.background_color.g / 255.0,
-                                                b: shape.background_color.b / 255.0,
-                                            },
-                                        }
-                                    }),
-                                ).is_ok() {
-                                let mut offset_indices = geometry.indices.iter().map(|index|{index+self.vertices.len() as u32}).collect::<Vec::<u32>>();
-                                self.vertices.append(&mut geometry.vertices);
-                                self.indices.append(&mut offset_indices);
-                                self.batch_index_end = self.indices.len() as u32;
-                            }
+
+                let mut builder = Path::builder();
+                builder.add_rounded_rectangle(
+                    &Box2D::from_origin_and_size(
+                            Point2D::new(ipx, ipy), 
+                            Size2D::new(isx, isy)
+                        ),
+                        &radii,
+                    path::Winding::Negative
+                );
+                let path = builder.build();
+
+                let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
+                if self.fill_tessellator.tessellate_path(
+                        &path,
+                        &FillOptions::default().with_tolerance(0.1).with_fill_rule(lyon::tessellation::FillRule::EvenOdd),
+                        &mut BuffersBuilder::new(&mut geometry, |fill: FillVertex| vertex(fill.position().x, fill.position().y)),
+                    ).is_ok() {
+                    self.bind_atlas(&image.data.atlas);
+                    self.append_geometry(geometry);
+                    self.end_atlas();
+                }
+            }
+            RenderCommand::Custom(shape) => {
+                match shape.data {
+                    CustomElement::Circle => {
+                        let mut builder = Path::builder();
+                        builder.add_circle(
+                            Point2D::new(
+                                (shape.bounding_box.x + (shape.bounding_box.width/2.0)) * self.dpi_scale,
+                                (shape.bounding_box.y + (shape.bounding_box.height/2.0)) * self.dpi_scale
+                            ), 
+                            shape.bounding_box.width/2.0,
+                            path::Winding::Negative
+                        );
+                        let path = builder.build();
+
+                        let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
+                        if self.fill_tessellator.tessellate_path(
+                                &path,
+                                &FillOptions::default().with_tolerance(0.1).with_fill_rule(lyon::tessellation::FillRule::EvenOdd),
+                                &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| { 
+                                    UIVertex {
+                                        position: UIPosition { 
+                                            x: vertex.position().x,
+                                            y: vertex.position().y,
+                                            z
+                                        },
+                                        texture: 0,
+                                        color: UIColor {
+                                            r: shape.background_color.r / 255.0,
+                                            g: shape.background_color.g / 255.0,
+                                            b: shape.background_color.b / 255.0,
+                                        },
+                                        alpha: shape.background_color.a / 255.0,
+                                    }
+                                }),
+                            ).is_ok() {
+                            self.append_geometry(geometry);
                         }
-                        CustomElement::Line(line_config) => {
-                            let mut builder = Path::builder();
-                            builder.begin(
-                                Point2D::new(
-                                    (shape.bounding_box.x+(shape.bounding_box.width/2.0)-(line_config.width/2.0)) * self.dpi_scale,
-                                    shape.bounding_box.y * self.dpi_scale
-                                )
+                    }
+                    CustomElement::Layer(_) => {
+                        let color = shape.background_color;
+                        if color.a > 0.0 {
+                            let (x, y, width, height) = self.device_box(
+                                shape.bounding_box.x,
+                                shape.bounding_box.y,
+                                shape.bounding_box.width,
+                                shape.bounding_box.height,
+                                true
                             );
-                            builder.line_to(
-                                Point2D::new(
-                                    (shape.bounding_box.x+(shape.bounding_box.width/2.0)-(line_config.width/2.0)) * self.dpi_scale,
-                                    (shape.bounding_box.y+shape.bounding_box.height) * self.dpi_scale
-                                )
+                            let radii = BorderRadii {
+                                top_left: shape.corner_radii.top_left * self.dpi_scale,
+                                top_right: shape.corner_radii.top_right * self.dpi_scale,
+                                bottom_left: shape.corner_radii.bottom_left * self.dpi_scale,
+                                bottom_right: shape.corner_radii.bottom_right * self.dpi_scale
+                            };
+                            self.fill_rounded(
+                                (x, y, width, height),
+                                &radii,
+                                [color.r / 255.0, color.g / 255.0, color.b / 255.0],
+                                color.a / 255.0,
+                                z
                             );
-                            builder.end(true);
-                            
-                            let path = builder.build();
-
-                            let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
-                            let mut tessellator = StrokeTessellator::new();
-                            if tessellator.tessellate_path(
-                                    &path,
-                                    &StrokeOptions::default().with_line_width(line_config.width as f32),
-                                    &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex  | { 
-                                        UIVertex {
-                                            position: vertex.position().into(),
-                                            texture: 0,
-                                            color: UIColor {
-                                                r: shape.background_color.r / 255.0,
-                                                g: shape.background_color.g / 255.0,
-                                                b: shape.background_color.b / 255.0,
-                                            }
-                                        }
-                                    }),
-                                ).is_ok() {
-                                let mut offset_indices = geometry.indices.iter().map(|index|{index+self.vertices.len() as u32}).collect::<Vec::<u32>>();
-                                self.vertices.append(&mut geometry.vertices);
-                                self.indices.append(&mut offset_indices);
-                                self.batch_index_end = self.indices.len() as u32;
+                        }
+                    }
+                    CustomElement::TextMarks(marks) => {
+                        let z = self.depth(z);
+                        let layout = self.text_layout(marks, (shape.bounding_box.x, shape.bounding_box.y, shape.bounding_box.width));
+                        let color = shape.background_color;
+                        // drawn before any text, so the selection ends up under the glyphs
+                        if let Some(selection) = &marks.selection {
+                            for (top, height, stops) in &layout.rows {
+                                let (left, right) = stops.iter()
+                                    .filter(|(offset, _)| selection.start <= *offset && *offset <= selection.end)
+                                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(left, right), (_, x)| (left.min(*x), right.max(*x)));
+                                if left < right {
+                                    self.fill_rectangle(
+                                        (left, *top, right - left, *height),
+                                        0.0,
+                                        [color.r / 255.0, color.g / 255.0, color.b / 255.0],
+                                        color.a / 255.0,
+                                        z
+                                    );
+                                }
+                            }
+                        }
+                        if let Some(cursor) = marks.cursor
+                        && let Some((top, height, x)) = layout.caret(cursor) {
+                            self.fill_rectangle((x, top, 1.0, height), 0.0, marks.cursor_color, 1.0, z);
+                        }
+                        self.text_layouts.push(layout);
+                    }
+                    CustomElement::Registered(name) => {
+                        let (x, y, width, height) = self.device_box(
+                            shape.bounding_box.x,
+                            shape.bounding_box.y,
+                            shape.bounding_box.width,
+                            shape.bounding_box.height,
+                            false
+                        );
+                        let depth = self.depth(z);
+                        let scissor = self.scissor_active.then_some((self.scissor_position, self.scissor_size));
+
+                        // everything queued so far has to be drawn before the custom renderer runs
+                        match self.scissor_active {
+                            true => {
+                                if self.batch_index_end > self.batch_index_begin {
+                                    self.batches.push(RenderBatch::Scissor {
+                                        begin: self.batch_index_begin,
+                                        end: self.batch_index_end,
+                                        position: self.scissor_position,
+                                        size: self.scissor_size,
+                                        quads: self.batch_quads,
+                                    });
+                                    self.batch_index_begin = self.batch_index_end;
+                                }
                             }
+                            false => self.batch(),
+                        }
+
+                        self.batches.push(RenderBatch::Custom {
+                            name: name.clone(),
+                            bounds: CustomBounds { x, y, width, height, depth },
+                            scissor,
+                        });
+                    }
+                    CustomElement::Line(line_config) => {
+                        let mut builder = Path::builder();
+                        builder.begin(
+                            Point2D::new(
+                                (shape.bounding_box.x+(shape.bounding_box.width/2.0)-(line_config.width/2.0)) * self.dpi_scale,
+                                shape.bounding_box.y * self.dpi_scale
+                            )
+                        );
+                        builder.line_to(
+                            Point2D::new(
+                                (shape.bounding_box.x+(shape.bounding_box.width/2.0)-(line_config.width/2.0)) * self.dpi_scale,
+                                (shape.bounding_box.y+shape.bounding_box.height) * self.dpi_scale
+                            )
+                        );
+                        builder.end(true);
+                        
+                        let path = builder.build();
+
+                        let mut geometry: VertexBuffers<UIVertex, u32> = VertexBuffers::new();
+                        if self.stroke_tessellator.tessellate_path(
+                                &path,
+                                &StrokeOptions::default().with_line_width(line_config.width as f32),
+                                &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex  | { 
+                                    UIVertex {
+                                        position: vertex.position().into(),
+                                        texture: 0,
+                                        color: UIColor {
+                                            r: shape.background_color.r / 255.0,
+                                            g: shape.background_color.g / 255.0,
+                                            b: shape.background_color.b / 255.0,
+                                        },
+                                        alpha: shape.background_color.a / 255.0,
+                                    }
+                                }),
+                            ).is_ok() {
+                            self.append_geometry(geometry);
                         }
                     }
                 }
-                RenderCommand::None => {}
             }
-            z -= 0.0001;
+            RenderCommand::None => {}
         }
-
-        self.end(render_pass, &device, &queue, &surface_config);
     }
 
     fn render_text(
@@ -1006,12 +1840,8 @@ impl UIRenderer {
         let viewport = self.text_viewport.as_mut().unwrap();
         let renderer = self.text_renderer.as_mut().unwrap();
 
-        atlas.trim();
-
-        let mut areas = Vec::<TextArea>::new();
-
-        for text_line in self.lines.iter_mut() {
-            areas.push(TextArea {
+        let areas = || self.lines.iter().map(|text_line| {
+            TextArea {
                 buffer: &text_line.line,
                 left: text_line.left,
                 top: text_line.top,
@@ -1032,21 +1862,22 @@ impl UIRenderer {
                 },
                 default_color: text_line.color,
                 custom_glyphs: &[],
-            });
-        }
+            }
+        });
+        let underlay = self.layer == UILayer::Underlay;
+        let depth = |metadata: usize| match underlay {
+            true => 1.0,
+            false => (metadata as f32) / 10000.0,
+        };
 
-        renderer
-            .prepare_with_depth(
-                device,
-                queue,
-                &mut self.font_system,
-                atlas,
-                viewport,
-                areas.into_iter(),
-                &mut self.swash_cache,
-                |metadata| (metadata as f32) / 10000.0,
-            )
-            .unwrap();
+        // glyphs stay in the atlas across frames, so windows at different scales don't push out each other's glyphs.
+        // it's only trimmed down to what this frame uses once it's full
+        let mut prepared = renderer.prepare_with_depth(device, queue, &mut self.font_system, atlas, viewport, areas(), &mut self.swash_cache, depth);
+        if let Err(glyphon::PrepareError::AtlasFull) = prepared {
+            atlas.trim();
+            prepared = renderer.prepare_with_depth(device, queue, &mut self.font_system, atlas, viewport, areas(), &mut self.swash_cache, depth);
+        }
+        prepared.unwrap();
 
         renderer.render(atlas, viewport, render_pass).unwrap();
 
@@ -1056,6 +1887,7 @@ impl UIRenderer {
     pub fn draw_text(
         &mut self,
         text: &str,
+        font_id: u16,
         font_size: f32,
         line_height: f32,
         position: UIPosition,
@@ -1069,7 +1901,7 @@ impl UIRenderer {
             &mut self.font_system,
             text,
             Attrs::new()
-                .family(Family::Serif)
+                .family(font_family(&self.fonts, font_id))
                 .metadata((draw_order * 10000.0) as usize),
             Shaping::Advanced,
         );
@@ -1085,10 +1917,171 @@ impl UIRenderer {
         });
     }
 
+    /// shapes a text editor line wrapped the way the layout wrapped it, to find where its glyphs are.
+    /// `x`, `y` and `width` are the line element's, in layout units
+    fn text_layout(&mut self, marks: &TextMarks, (x, y, width): (f32, f32, f32)) -> TextLayout {
+        let dpi_scale = self.dpi_scale;
+        let line_height = marks.line_height;
+        let mut line = Buffer::new(&mut self.font_system, Metrics::new(marks.font_size * dpi_scale, line_height * dpi_scale));
+        line.set_size(&mut self.font_system, Some(width * dpi_scale), None);
+        line.set_text(
+            &mut self.font_system,
+            &marks.text,
+            Attrs::new().family(font_family(&self.fonts, marks.font_id)),
+            Shaping::Advanced,
+        );
+        line.shape_until_scroll(&mut self.font_system, false);
+
+        let mut rows = line.layout_runs().map(|run| {
+            let mut stops = run.glyphs.iter()
+                .flat_map(|glyph| [(glyph.start, glyph.x), (glyph.end, glyph.x + glyph.w)])
+                .map(|(offset, glyph_x)| (offset, x + glyph_x / dpi_scale))
+                .collect::<Vec<(usize, f32)>>();
+            if stops.is_empty() {
+                stops.push((0, x));
+            }
+            (y + run.line_top / dpi_scale, line_height, stops)
+        }).collect::<Vec<_>>();
+        if rows.is_empty() {
+            rows.push((y, line_height, vec![(0, x)]));
+        }
+
+        TextLayout { editor: marks.editor, line_start: marks.line_start, rows }
+    }
+
+    /// a single line of text centered on `x`, `y` in device pixels
+    pub fn draw_label(
+        &mut self,
+        text: &str,
+        font_size: f32,
+        x: f32,
+        y: f32,
+        color: cosmic_text::Color,
+        draw_order: f32,
+    ) {
+        let mut line = Buffer::new(&mut self.font_system, Metrics::new(font_size, font_size * 1.2));
+
+        line.set_text(
+            &mut self.font_system,
+            text,
+            Attrs::new()
+                .family(Family::Serif)
+                .metadata((draw_order * 10000.0) as usize),
+            Shaping::Advanced,
+        );
+
+        line.shape_until_scroll(&mut self.font_system, false);
+
+        let width = line.layout_runs().map(|run| run.line_w).fold(0.0, f32::max);
+
+        self.lines.push(TextLine {
+            line,
+            left: x - width / 2.0,
+            top: y - font_size * 0.6,
+            color,
+            bounds: None,
+        });
+    }
+
+    /// loads a font that's only used for glyphs the chosen font doesn't have, like color emoji
+    pub fn add_fallback_font(&mut self, data: Vec<u8>) {
+        let faces = self.font_system.db_mut().load_font_source(cosmic_text::fontdb::Source::Binary(Arc::new(data)));
+        if faces.is_empty() {
+            tracing::warn!(target: "telera::render", "fallback font has no usable face");
+        }
+    }
+
+    /// loads the font for `font_id`, text drawn with it before this used the default
+    pub fn set_font(&mut self, font_id: u16, face: FontFace) {
+        let family = match face {
+            FontFace::Data(data) => {
+                let faces = self.font_system.db_mut().load_font_source(cosmic_text::fontdb::Source::Binary(Arc::new(data)));
+                faces.first()
+                    .and_then(|face| self.font_system.db().face(*face))
+                    .and_then(|face| face.families.first())
+                    .map(|(family, _)| family.clone())
+            }
+            FontFace::Installed(families) => families.into_iter().find(|family| {
+                self.font_system.db().faces().any(|face| face.families.iter().any(|(name, _)| name == family))
+            }),
+        };
+        if family.is_none() {
+            tracing::warn!(target: "telera::render", "font {} has no usable face, its text uses the default font", font_id);
+        }
+
+        if self.fonts.len() <= font_id as usize {
+            self.fonts.resize(font_id as usize + 1, None);
+        }
+        self.fonts[font_id as usize] = family;
+        for scale in self.warmed_scales.clone() {
+            self.warm_font(font_id, scale);
+        }
+    }
+
+    /// rasterizes the common glyphs of every font at the scales that haven't been yet,
+    /// so a window moved to a monitor with another scale doesn't rasterize them all in one frame
+    pub fn warm_glyphs(&mut self, scales: impl IntoIterator<Item = f32>) {
+        for scale in scales {
+            if self.warmed_scales.contains(&scale) {
+                continue
+            }
+            self.warmed_scales.push(scale);
+            for font_id in 0..self.fonts.len().max(1) {
+                self.warm_font(font_id as u16, scale);
+            }
+        }
+    }
+
+    pub fn glyphs_warmed(&self) -> bool {
+        !self.warmed_scales.is_empty()
+    }
+
+    /// puts `WARM_TEXT` in the swash cache, keyed by font, physical size and offset like the glyphs text draws
+    fn warm_font(&mut self, font_id: u16, scale: f32) {
+        for size in WARM_SIZES {
+            let font_size = size * scale;
+            let mut buffer = Buffer::new(&mut self.font_system, Metrics::new(font_size, font_size * 1.2));
+            buffer.set_text(
+                &mut self.font_system,
+                WARM_TEXT,
+                Attrs::new().family(font_family(&self.fonts, font_id)),
+                Shaping::Advanced,
+            );
+            buffer.shape_until_scroll(&mut self.font_system, false);
+            let keys = buffer.layout_runs()
+                .flat_map(|run| run.glyphs.iter())
+                .flat_map(|glyph| WARM_OFFSETS.map(|offset| glyph.physical((offset, 0.0), 1.0).cache_key))
+                .collect::<Vec<_>>();
+            for key in keys {
+                self.swash_cache.get_image(&mut self.font_system, key);
+            }
+        }
+    }
+
     pub fn stage_atlas(&mut self, name: String, atlas_data: DynamicImage) {
         self.staged_images.push((name, atlas_data));
     }
 
+    /// writes a frame of the streamed image `name` and shows it, the textures are only made again when the size changes
+    pub fn stream_image(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, name: &str, rgba: &[u8], size: (u32, u32)) {
+        let reusable = self.atlas_map.contains_key(name) && self.streamed_images.get(name)
+            .is_some_and(|stream| (stream.shown.width(), stream.shown.height()) == size);
+        if !reusable {
+            let shown = atlas_texture(device, size);
+            let back = atlas_texture(device, size);
+            let back_bind_group = atlas_bind_group(device, &back);
+            self.atlas_map.insert(name.to_string(), atlas_bind_group(device, &shown));
+            self.streamed_images.insert(name.to_string(), StreamedImage { shown, back: (back, back_bind_group) });
+        }
+        let (Some(stream), Some(shown_bind_group)) = (self.streamed_images.get_mut(name), self.atlas_map.get_mut(name)) else {
+            unreachable!()
+        };
+
+        write_atlas(queue, &stream.back.0, rgba);
+        std::mem::swap(shown_bind_group, &mut stream.back.1);
+        std::mem::swap(&mut stream.shown, &mut stream.back.0);
+    }
+
     fn add_atlas(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
         if self.staged_images.len() > 0 {
             let (name, staged_image) = self.staged_images.pop().unwrap();
@@ -1102,6 +2095,8 @@ impl UIRenderer {
 pub struct UIPipeline {
     pixel_format: wgpu::TextureFormat,
     vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'static>>,
+    vertex_entry: &'static str,
+    depth_test: bool,
 }
 
 impl UIPipeline {
@@ -1109,6 +2104,8 @@ impl UIPipeline {
         Self {
             pixel_format,
             vertex_buffer_layouts: Vec::new(),
+            vertex_entry: "vs_main",
+            depth_test: false,
         }
     }
 
@@ -1116,6 +2113,15 @@ impl UIPipeline {
         self.vertex_buffer_layouts.push(layout);
     }
 
+    pub fn set_depth_test(&mut self, depth_test: bool) {
+        self.depth_test = depth_test;
+    }
+
+    /// `vs_quad` expands instanced `UIQuad`s, `vs_main` reads plain vertices
+    pub fn set_vertex_entry(&mut self, entry: &'static str) {
+        self.vertex_entry = entry;
+    }
+
     pub fn build_pipeline(
         &self,
         device: &wgpu::Device,
@@ -1171,7 +2177,7 @@ impl UIPipeline {
             layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
                 module: &shader_module,
-                entry_point: Some("vs_main"),
+                entry_point: Some(self.vertex_entry),
                 buffers: &self.vertex_buffer_layouts,
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
             },
@@ -1192,9 +2198,12 @@ impl UIPipeline {
             }),
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Always, // 1.
-                stencil: wgpu::StencilState::default(),       // 2.
+                depth_write_enabled: !self.depth_test,
+                depth_compare: match self.depth_test {
+                    true => wgpu::CompareFunction::LessEqual,
+                    false => wgpu::CompareFunction::Always,
+                },
+                stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
             multisample,
@@ -1222,92 +2231,132 @@ pub trait UIAtlasCreation {
 impl UIAtlasCreation for wgpu::BindGroup {
     fn create_atlas(atlas_data: DynamicImage, device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
         let diffuse_rgba = atlas_data.to_rgba8();
+        let texture = atlas_texture(device, diffuse_rgba.dimensions());
+        write_atlas(queue, &texture, &diffuse_rgba);
+        atlas_bind_group(device, &texture)
+    }
+}
 
-        use image::GenericImageView;
-        let dimensions = atlas_data.dimensions();
-
-        let texture_size = wgpu::Extent3d {
-            width: dimensions.0,
-            height: dimensions.1,
+fn atlas_texture(device: &wgpu::Device, (width, height): (u32, u32)) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width,
+            height,
             depth_or_array_layers: 1,
-        };
-
-        let diffuse_texture = device.create_texture(&wgpu::TextureDescriptor {
-            size: texture_size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            label: Some("diffuse_texture"),
-            view_formats: &[],
-        });
-
-        queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &diffuse_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &diffuse_rgba,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * dimensions.0),
-                rows_per_image: Some(dimensions.1),
-            },
-            texture_size,
-        );
-
-        let diffuse_texture_view =
-            diffuse_texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        let diffuse_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        label: Some("diffuse_texture"),
+        view_formats: &[],
+    })
+}
 
-        let texture_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Texture {
-                            multisampled: false,
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                        count: None,
-                    },
-                ],
-                label: Some("texture_bind_group_layout"),
-            });
+/// `rgba` has to fill the whole texture
+fn write_atlas(queue: &wgpu::Queue, texture: &wgpu::Texture, rgba: &[u8]) {
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        rgba,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * texture.width()),
+            rows_per_image: Some(texture.height()),
+        },
+        texture.size(),
+    );
+}
 
-        device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &texture_bind_group_layout,
+fn atlas_bind_group(device: &wgpu::Device, texture: &wgpu::Texture) -> wgpu::BindGroup {
+    let diffuse_texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let diffuse_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Nearest,
+        mipmap_filter: wgpu::FilterMode::Nearest,
+        ..Default::default()
+    });
+
+    let texture_bind_group_layout =
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             entries: &[
-                wgpu::BindGroupEntry {
+                wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&diffuse_texture_view),
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
                 },
-                wgpu::BindGroupEntry {
+                wgpu::BindGroupLayoutEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&diffuse_sampler),
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
                 },
             ],
-            label: Some("diffuse_bind_group"),
-        })
+            label: Some("texture_bind_group_layout"),
+        });
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &texture_bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&diffuse_texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&diffuse_sampler),
+            },
+        ],
+        label: Some("diffuse_bind_group"),
+    })
+}
+
+/// a ui image rewritten every frame, like video. frames go into the texture that isn't shown, then the two swap,
+/// so a frame is never written while the last one is still being drawn
+pub struct StreamedImage {
+    /// the texture of the bind group in `atlas_map`
+    shown: wgpu::Texture,
+    back: (wgpu::Texture, wgpu::BindGroup),
+}
+
+/// whether `inner` lies within `outer`, give or take a pixel of rounding
+fn contains(outer: (f32, f32, f32, f32), inner: (f32, f32, f32, f32)) -> bool {
+    inner.0 >= outer.0 - 1.0
+    && inner.1 >= outer.1 - 1.0
+    && inner.0 + inner.2 <= outer.0 + outer.2 + 1.0
+    && inner.1 + inner.3 <= outer.1 + outer.3 + 1.0
+}
+
+/// the layout box of anything that draws, in layout units
+fn command_bounds(command: &RenderCommand<UIImageDescriptor, CustomElement, CustomLayoutSettings>) -> Option<(f32, f32, f32, f32)> {
+    match command {
+        RenderCommand::Rectangle(r) => Some((r.bounding_box.x, r.bounding_box.y, r.bounding_box.width, r.bounding_box.height)),
+        RenderCommand::Border(b) => Some((b.bounding_box.x, b.bounding_box.y, b.bounding_box.width, b.bounding_box.height)),
+        RenderCommand::Text(t) => Some((t.bounding_box.x, t.bounding_box.y, t.bounding_box.width, t.bounding_box.height)),
+        RenderCommand::Image(i) => Some((i.bounding_box.x, i.bounding_box.y, i.bounding_box.width, i.bounding_box.height)),
+        RenderCommand::Custom(c) => Some((c.bounding_box.x, c.bounding_box.y, c.bounding_box.width, c.bounding_box.height)),
+        _ => None,
+    }
+}
+
+/// glyphs missing from the family are still found in any other loaded font
+fn font_family(fonts: &[Option<String>], font_id: u16) -> Family<'_> {
+    match fonts.get(font_id as usize) {
+        Some(Some(family)) => Family::Name(family),
+        _ => Family::Serif,
     }
 }
@@ -7,12 +7,14 @@ struct Vertex {
     @location(0)position: vec3<f32>,
     @location(1)texture: u32,
     @location(2)color: vec3<f32>,
+    @location(3)alpha: f32,
 };
 
 struct VertexPayload {
     @builtin(position) position: vec4<f32>,
     @location(0) color: vec3<f32>,
     @location(1) texture: u32,
+    @location(2) alpha: f32,
     @location(3) tex_coords: vec2<f32>
 };
 
@@ -30,11 +32,49 @@ fn vs_main(vertex: Vertex) -> VertexPayload {
     );
     out.color = vertex.color;
     out.texture = vertex.texture;
+    out.alpha = vertex.alpha;
     out.tex_coords.x = vertex.color.x;
     out.tex_coords.y = vertex.color.y;
     return out;
 }
 
+struct Quad {
+    @location(0)position: vec3<f32>,
+    @location(1)size: vec2<f32>,
+    @location(2)texture: u32,
+    @location(3)color: vec3<f32>,
+    @location(4)alpha: f32,
+    @location(5)uv: vec4<f32>,
+};
+
+@vertex
+fn vs_quad(@builtin(vertex_index) index: u32, quad: Quad) -> VertexPayload {
+    // two triangles over the unit square, wound like the tessellated shapes
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+    );
+    let corner = corners[index];
+    let position = quad.position.xy + corner * quad.size;
+
+    var out: VertexPayload;
+    out.position = vec4<f32>(
+        (position.x/(size.x/2.0))-1,
+        -((position.y/(size.y/2.0))-1),
+        quad.position.z,
+        1.0
+    );
+    out.color = quad.color;
+    out.texture = quad.texture;
+    out.alpha = quad.alpha;
+    out.tex_coords = mix(quad.uv.xy, quad.uv.zw, corner);
+    return out;
+}
+
 @group(0) @binding(0)
 var t_diffuse: texture_2d<f32>;
 @group(0) @binding(1)
@@ -43,8 +83,8 @@ var s_diffuse: sampler;
 @fragment
 fn fs_main(in:VertexPayload) -> @location(0) vec4<f32> {
     switch in.texture {
-        case 0u { return vec4<f32>(in.color, 1.0); }
-        case 1u { return textureSample(t_diffuse, s_diffuse, in.tex_coords); }
-        case default { return textureSample(t_diffuse, s_diffuse, in.tex_coords); }
+        case 0u { return vec4<f32>(in.color, in.alpha); }
+        case 1u { return textureSample(t_diffuse, s_diffuse, in.tex_coords) * vec4<f32>(1.0, 1.0, 1.0, in.alpha); }
+        case default { return textureSample(t_diffuse, s_diffuse, in.tex_coords) * vec4<f32>(1.0, 1.0, 1.0, in.alpha); }
     }
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use symbol_table::GlobalSymbol;
 
 //use crate::DataSrc;
@@ -25,5 +27,55 @@ pub struct LineConfig{
 pub enum CustomElement {
     #[default]
     Circle,
-    Line(LineConfig)
+    Line(LineConfig),
+    /// drawn by the renderer registered under this name with `api.register_custom_element`
+    Registered(String),
+    /// an element with a `z-index`, above 0 it and its children draw over the elements around them.
+    /// 0 and below draw in place, layouts only allow negatives on floating elements
+    Layer(i16),
+    /// a text editor line, its cursor and selection are drawn where the line's glyphs are
+    TextMarks(TextMarks),
+}
+
+/// what's drawn over a line of a text editor, the selection takes the element's background color
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TextMarks {
+    /// the editor the line belongs to, its glyphs are kept under this id to place clicks next frame
+    pub editor: u32,
+    /// byte offset of the line in the editor's text
+    pub line_start: usize,
+    pub text: String,
+    pub font_id: u16,
+    /// layout units
+    pub font_size: f32,
+    pub line_height: f32,
+    /// byte offsets into `text`
+    pub cursor: Option<usize>,
+    pub selection: Option<Range<usize>>,
+    pub cursor_color: [f32; 3],
+}
+
+/// where a registered custom element landed, in device pixels
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CustomBounds {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// the ui depth at this element's place in the draw order, for pipelines that test against the depth buffer
+    pub depth: f32,
+}
+
+/// user drawing inside the ui render pass, called for every element using it in ui draw order
+pub trait CustomElementRenderer {
+    /// the pass has the ui pipeline and buffers bound, they are restored afterwards so anything can be changed.
+    /// text is drawn after every other ui element, so it ends up on top of custom drawing
+    fn render(
+        &mut self,
+        bounds: CustomBounds,
+        render_pass: &mut wgpu::RenderPass,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &wgpu::SurfaceConfiguration,
+    );
 }
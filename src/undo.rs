@@ -0,0 +1,80 @@
+use crate::API;
+
+/// a change the app can take back and make again, pushed with `api.push_undo`
+pub trait Command<UserApp>: 'static {
+    fn undo(&mut self, app: &mut UserApp, api: &mut API);
+    fn redo(&mut self, app: &mut UserApp, api: &mut API);
+}
+
+/// commands in the order they were done, the ones from `position` on were undone and can be redone
+pub struct UndoStack<C> {
+    commands: Vec<C>,
+    position: usize,
+    /// the oldest commands are dropped past this many, 0 keeps them all
+    limit: usize,
+}
+
+impl<C> Default for UndoStack<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> UndoStack<C> {
+    pub fn new() -> Self {
+        Self { commands: Vec::new(), position: 0, limit: 0 }
+    }
+
+    pub fn with_limit(limit: usize) -> Self {
+        Self { commands: Vec::new(), position: 0, limit }
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    /// what was undone can't be redone after a new command
+    pub fn push(&mut self, command: C) {
+        self.commands.truncate(self.position);
+        self.commands.push(command);
+        self.trim();
+        self.position = self.commands.len();
+    }
+
+    /// the command to undo, it's the next one to redo after
+    pub fn undo(&mut self) -> Option<&mut C> {
+        self.position = self.position.checked_sub(1)?;
+        self.commands.get_mut(self.position)
+    }
+
+    /// the command to redo, it's the next one to undo after
+    pub fn redo(&mut self) -> Option<&mut C> {
+        if self.position >= self.commands.len() {
+            return None
+        }
+        self.position += 1;
+        self.commands.get_mut(self.position - 1)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.position < self.commands.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.position = 0;
+    }
+
+    fn trim(&mut self) {
+        if self.limit > 0 && self.commands.len() > self.limit {
+            let dropped = self.commands.len() - self.limit;
+            self.commands.drain(..dropped);
+            self.position = self.position.saturating_sub(dropped);
+        }
+    }
+}
@@ -65,7 +65,54 @@ pub fn handler_dispatch(item: proc_macro::TokenStream) -> proc_macro::TokenStrea
     impl_handler_trait(ast)
 }
 
-#[proc_macro_derive(ParserDataAccess, attributes(event_handler))]
+/// how a field is bound, from `#[bind(rename = "name", skip, with = "getter", writable)]`
+#[derive(Default)]
+struct Bind {
+    /// the name layouts use instead of the field's
+    rename: Option<String>,
+    skip: bool,
+    /// a function taking `&self` whose result is bound instead of the field
+    with: Option<syn::Path>,
+    /// widgets bound to the field, like sliders and text editors, write to it
+    writable: bool,
+}
+
+impl Bind {
+    fn from_attributes(attributes: &[syn::Attribute]) -> Self {
+        let mut bind = Bind::default();
+        for attribute in attributes.iter().filter(|attribute| attribute.path().is_ident("bind")) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    bind.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                }
+                else if meta.path.is_ident("skip") {
+                    bind.skip = true;
+                }
+                else if meta.path.is_ident("with") {
+                    bind.with = Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Path>()?);
+                }
+                else if meta.path.is_ident("writable") {
+                    bind.writable = true;
+                }
+                else {
+                    return Err(meta.error("expected `rename`, `skip`, `with` or `writable`"))
+                }
+                Ok(())
+            }).unwrap_or_else(|error| panic!("invalid bind attribute: {}", error));
+        }
+        if bind.writable && bind.with.is_some() {
+            panic!("a field bound `with` a getter can't be writable");
+        }
+        bind
+    }
+}
+
+/// fields are read only, widgets bound to them write them back through `set_*` once they're marked `#[bind(writable)]`.
+/// fields marked `#[variant]` hold a fieldless enum deriving `LayoutEnum`.
+/// fields marked `#[nested]` hold another struct deriving `ParserDataAccess` for the same event handler,
+/// layouts reach its fields as `field.inner_field`. `Vec` fields marked `#[list]` hold such structs too,
+/// inside a list over the field `field.inner_field` is the field of the item being laid out
+#[proc_macro_derive(ParserDataAccess, attributes(event_handler, nested, list, variant, bind))]
 pub fn parser_data_acces(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast: syn::DeriveInput = syn::parse(item).unwrap();
     let struct_name = ast.ident.clone();
@@ -86,18 +133,53 @@ pub fn parser_data_acces(item: proc_macro::TokenStream) -> proc_macro::TokenStre
     };
 
     let mut numeric = Vec::<proc_macro2::TokenStream>::new();
+    let mut numeric_setters = Vec::<proc_macro2::TokenStream>::new();
     let mut boolean = Vec::<proc_macro2::TokenStream>::new();
-    let mut _text = Vec::<proc_macro2::TokenStream>::new();
+    let mut boolean_setters = Vec::<proc_macro2::TokenStream>::new();
+    let mut text = Vec::<proc_macro2::TokenStream>::new();
+    let mut text_setters = Vec::<proc_macro2::TokenStream>::new();
     let mut lists = Vec::<proc_macro2::TokenStream>::new();
+    let mut nested = Vec::<(String, syn::Ident)>::new();
+    let mut list_items = Vec::<(String, String, syn::Ident)>::new();
+    let mut variants = Vec::<(String, proc_macro2::TokenStream)>::new();
 
     if let syn::Data::Struct(data) = ast.data {
         for field in data.fields {
+            let bind = Bind::from_attributes(&field.attrs);
+            if bind.skip {
+                continue
+            }
+            if let Some(field_ident) = &field.ident
+            && field.attrs.iter().any(|attribute| attribute.path().is_ident("nested")) {
+                let field_name = bind.rename.unwrap_or(field_ident.to_string());
+                nested.push((format!("{}.", field_name), field_ident.clone()));
+                continue
+            }
+            let is_list = field.attrs.iter().any(|attribute| attribute.path().is_ident("list"));
+            let is_variant = field.attrs.iter().any(|attribute| attribute.path().is_ident("variant"));
             if let Some(field_ident) = field.ident
             && let syn::Type::Path(p) = field.ty 
             && let None = p.path.leading_colon
             && let Some(pp) = p.path.segments.get(0) {
                 let data_type = pp.ident.to_string();
-                let field_name = field_ident.clone().to_string();
+                let field_name = bind.rename.unwrap_or(field_ident.to_string());
+                let value = match &bind.with {
+                    Some(getter) => quote::quote! { #getter(self) },
+                    None => quote::quote! { self.#field_ident },
+                };
+                // fields are read only unless widgets may write them
+                let settable = bind.writable;
+                // enums are text, their variant's position as a number, and `field.Variant` is true for the one they hold
+                if is_variant {
+                    text.push(quote::quote! {
+                        s if s == symbol_table::static_symbol!(#field_name) => Some(LayoutEnum::variant_name(&#value)),
+                    });
+                    numeric.push(quote::quote! {
+                        s if s == symbol_table::static_symbol!(#field_name) => Some(LayoutEnum::variant_index(&#value) as f32),
+                    });
+                    variants.push((format!("{}.", field_name), value));
+                    continue
+                }
                 match data_type.as_str() {
                     "u8" |
                     "u16" |
@@ -109,16 +191,92 @@ pub fn parser_data_acces(item: proc_macro::TokenStream) -> proc_macro::TokenStre
                     "f16" |
                     "f32" => {
                         numeric.push(quote::quote! {
-                            s if s == symbol_table::static_symbol!(#field_name) => Some(self.#field_ident as f32),
+                            s if s == symbol_table::static_symbol!(#field_name) => Some(#value as f32),
                         });
+                        let field_type = pp.ident.clone();
+                        if settable {
+                            numeric_setters.push(quote::quote! {
+                                s if s == symbol_table::static_symbol!(#field_name) => self.#field_ident = value as #field_type,
+                            });
+                        }
                     }
                     "bool" => {
                         boolean.push(quote::quote! {
-                            s if s == symbol_table::static_symbol!(#field_name) => Some(self.#field_ident),
+                            s if s == symbol_table::static_symbol!(#field_name) => Some(#value),
                         });
+                        if settable {
+                            boolean_setters.push(quote::quote! {
+                                s if s == symbol_table::static_symbol!(#field_name) => self.#field_ident = value,
+                            });
+                        }
                     }
                     "String" => {
-
+                        // a getter for a `String` field gives a `&String`, like a field holding the formatted text
+                        let value = match bind.with.is_none() {
+                            true => quote::quote! { &#value },
+                            false => value,
+                        };
+                        text.push(quote::quote! {
+                            s if s == symbol_table::static_symbol!(#field_name) => Some(#value),
+                        });
+                        if settable {
+                            text_setters.push(quote::quote! {
+                                s if s == symbol_table::static_symbol!(#field_name) => self.#field_ident = value,
+                            });
+                        }
+                    }
+                    // `None` leaves the name unbound, so layouts fall back like for any name the app doesn't have
+                    "Option" => {
+                        if let PathArguments::AngleBracketed(args) = &pp.arguments
+                        && let Some(GenericArgument::Type(Type::Path(inner))) = args.args.get(0)
+                        && let Some(inner) = inner.path.segments.last() {
+                            let inner_type = inner.ident.clone();
+                            match inner_type.to_string().as_str() {
+                                "u8" |
+                                "u16" |
+                                "u32" |
+                                "i8" |
+                                "i16" |
+                                "i32" |
+                                "f8" |
+                                "f16" |
+                                "f32" => {
+                                    numeric.push(quote::quote! {
+                                        s if s == symbol_table::static_symbol!(#field_name) => #value.map(|value| value as f32),
+                                    });
+                                    if settable {
+                                        numeric_setters.push(quote::quote! {
+                                            s if s == symbol_table::static_symbol!(#field_name) => self.#field_ident = Some(value as #inner_type),
+                                        });
+                                    }
+                                }
+                                "bool" => {
+                                    boolean.push(quote::quote! {
+                                        s if s == symbol_table::static_symbol!(#field_name) => #value,
+                                    });
+                                    if settable {
+                                        boolean_setters.push(quote::quote! {
+                                            s if s == symbol_table::static_symbol!(#field_name) => self.#field_ident = Some(value),
+                                        });
+                                    }
+                                }
+                                "String" => {
+                                    let value = match bind.with.is_none() {
+                                        true => quote::quote! { #value.as_ref() },
+                                        false => value,
+                                    };
+                                    text.push(quote::quote! {
+                                        s if s == symbol_table::static_symbol!(#field_name) => #value,
+                                    });
+                                    if settable {
+                                        text_setters.push(quote::quote! {
+                                            s if s == symbol_table::static_symbol!(#field_name) => self.#field_ident = Some(value),
+                                        });
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
                     }
                     "Vec" => {
                         if let PathArguments::AngleBracketed(args) = &pp.arguments
@@ -132,6 +290,9 @@ pub fn parser_data_acces(item: proc_macro::TokenStream) -> proc_macro::TokenStre
                             lists.push(quote::quote! {
                                 s if s == symbol_table::static_symbol!(#field_name) => Some(self.#field_ident.len()),
                             });
+                            if is_list {
+                                list_items.push((format!("{}.", field_name), field_name.clone(), field_ident.clone()));
+                            }
                         }
                     }
                     _ => {}
@@ -141,35 +302,229 @@ pub fn parser_data_acces(item: proc_macro::TokenStream) -> proc_macro::TokenStre
         }
     }
 
+    // names under a nested field's prefix are handed to that field with the prefix taken off
+    // and names under a list field's prefix to the item `list_data` points at
+    let delegate = |method: proc_macro2::TokenStream| {
+        nested.iter().map(|(prefix, field_ident)| quote::quote! {
+            if let Some(inner) = name.as_str().strip_prefix(#prefix) {
+                return ParserDataAccess::<#event_handler>::#method(&self.#field_ident, &symbol_table::GlobalSymbol::new(inner), list_data)
+            }
+        }).chain(list_items.iter().map(|(prefix, field_name, field_ident)| quote::quote! {
+            if let Some(inner) = name.as_str().strip_prefix(#prefix) {
+                if let Some((index, item_data)) = list_item_index(#field_name, list_data) {
+                    if let Some(item) = self.#field_ident.get(index) {
+                        return ParserDataAccess::<#event_handler>::#method(item, &symbol_table::GlobalSymbol::new(inner), &item_data)
+                    }
+                }
+            }
+        })).collect::<Vec<proc_macro2::TokenStream>>()
+    };
+    let delegate_mut = |method: proc_macro2::TokenStream| {
+        nested.iter().map(|(prefix, field_ident)| quote::quote! {
+            if let Some(inner) = name.as_str().strip_prefix(#prefix) {
+                return ParserDataAccess::<#event_handler>::#method(&mut self.#field_ident, &symbol_table::GlobalSymbol::new(inner), _list_data, value)
+            }
+        }).chain(list_items.iter().map(|(prefix, field_name, field_ident)| quote::quote! {
+            if let Some(inner) = name.as_str().strip_prefix(#prefix) {
+                if let Some((index, item_data)) = list_item_index(#field_name, _list_data) {
+                    if let Some(item) = self.#field_ident.get_mut(index) {
+                        return ParserDataAccess::<#event_handler>::#method(item, &symbol_table::GlobalSymbol::new(inner), &item_data, value)
+                    }
+                }
+            }
+        })).collect::<Vec<proc_macro2::TokenStream>>()
+    };
+    let nested_bool = delegate(quote::quote! { get_bool }).into_iter().chain(variants.iter().map(|(prefix, value)| quote::quote! {
+        if let Some(variant) = name.as_str().strip_prefix(#prefix) {
+            return Some(LayoutEnum::variant_name(&#value) == variant)
+        }
+    })).collect::<Vec<proc_macro2::TokenStream>>();
+    let nested_bool_setters = delegate_mut(quote::quote! { set_bool });
+    let nested_numeric = delegate(quote::quote! { get_numeric });
+    let nested_numeric_setters = delegate_mut(quote::quote! { set_numeric });
+    let nested_text = delegate(quote::quote! { get_text });
+    let nested_text_setters = delegate_mut(quote::quote! { set_text });
+    let nested_lists = delegate(quote::quote! { get_list_length });
+
     quote::quote! {
         impl ParserDataAccess<#event_handler> for #struct_name {
             fn get_bool(&self, name: &symbol_table::GlobalSymbol, list_data: &Option<(symbol_table::GlobalSymbol, usize)>) -> Option<bool>{
                 match *name {
                     #(#boolean)*
-                    _ => None
+                    _ => {
+                        #(#nested_bool)*
+                        None
+                    }
+                }
+            }
+            fn set_bool(&mut self, name: &symbol_table::GlobalSymbol, _list_data: &Option<(symbol_table::GlobalSymbol, usize)>, value: bool) {
+                match *name {
+                    #(#boolean_setters)*
+                    _ => {
+                        #(#nested_bool_setters)*
+                    }
                 }
             }
             fn get_numeric(&self, name: &symbol_table::GlobalSymbol, list_data: &Option<(symbol_table::GlobalSymbol, usize)>) -> Option<f32>{
                 match *name {
                     #(#numeric)*
-                    _ => None
+                    _ => {
+                        #(#nested_numeric)*
+                        None
+                    }
+                }
+            }
+            fn set_numeric(&mut self, name: &symbol_table::GlobalSymbol, _list_data: &Option<(symbol_table::GlobalSymbol, usize)>, value: f32) {
+                match *name {
+                    #(#numeric_setters)*
+                    _ => {
+                        #(#nested_numeric_setters)*
+                    }
                 }
             }
             fn get_text<'render_pass, 'application>(&'application self, name: &symbol_table::GlobalSymbol, list_data: &Option<(symbol_table::GlobalSymbol, usize)>) -> Option<&'render_pass String> where 'application: 'render_pass{
                 match *name {
-                    _ => None
+                    #(#text)*
+                    _ => {
+                        #(#nested_text)*
+                        None
+                    }
+                }
+            }
+            fn set_text(&mut self, name: &symbol_table::GlobalSymbol, _list_data: &Option<(symbol_table::GlobalSymbol, usize)>, value: String) {
+                match *name {
+                    #(#text_setters)*
+                    _ => {
+                        #(#nested_text_setters)*
+                    }
                 }
             }
             fn get_list_length(&self, name: &symbol_table::GlobalSymbol, list_data: &Option<(symbol_table::GlobalSymbol, usize)>) -> Option<usize> {
                 match *name {
                     #(#lists)*
-                    _ => None
+                    _ => {
+                        #(#nested_lists)*
+                        None
+                    }
                 }
             }
         }
     }.into()
 }
 
+#[proc_macro_derive(LayoutEnum)]
+pub fn layout_enum(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast: syn::DeriveInput = syn::parse(item).unwrap();
+    let enum_name = ast.ident.clone();
+
+    let variants = match ast.data {
+        syn::Data::Enum(enum_data) => enum_data.variants.into_iter().map(|variant| variant.ident).collect::<Vec<syn::Ident>>(),
+        _ => panic!("#[derive(LayoutEnum)] can only be used on enums"),
+    };
+    let names = variants.iter().map(|variant| variant.to_string());
+    let indices = 0..variants.len();
+
+    quote::quote! {
+        impl LayoutEnum for #enum_name {
+            fn variant_name(&self) -> &'static String {
+                static NAMES: std::sync::LazyLock<Vec<String>> = std::sync::LazyLock::new(|| vec![#(#names.to_string()),*]);
+                &NAMES[self.variant_index()]
+            }
+            fn variant_index(&self) -> usize {
+                match self {
+                    #(#enum_name::#variants { .. } => #indices,)*
+                }
+            }
+        }
+    }.into()
+}
+
+/// layout configs whose plain text parameter is the name of an event
+const EVENT_CONFIGS: &[&str] = &[
+    "emit", "hovered", "unhovered", "hover", "focused", "unfocused", "focus", "left-pressed", "left-down",
+    "left-released", "left-clicked", "left-dbl-clicked", "left-tpl-clicked", "right-pressed", "right-down",
+    "right-released", "right-clicked",
+];
+
+struct CheckLayouts {
+    dir: syn::LitStr,
+    event_type: syn::Path,
+}
+
+impl syn::parse::Parse for CheckLayouts {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let dir = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let event_type = input.parse()?;
+        Ok(Self { dir, event_type })
+    }
+}
+
+/// `check_layouts!("src/layouts", MyEvents)` fails to compile when a layout sends an event `MyEvents` has no variant for.
+/// the directory is relative to the crate's manifest, events bound to app values are only known at runtime.
+/// events are matched to variants by name, so this only suits enums whose `FromStr` takes each variant's own name.
+/// only config lines are read, a list item holding nothing but the config and the event, outside code blocks.
+/// `validate_layouts` checks events through `FromStr` itself, for enums that name them any other way
+#[proc_macro]
+pub fn check_layouts(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let CheckLayouts { dir, event_type } = syn::parse_macro_input!(item as CheckLayouts);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let dir = std::path::Path::new(&manifest_dir).join(dir.value());
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            let message = format!("failed to read layouts in {}: {}", dir.display(), error);
+            return quote::quote! { compile_error!(#message); }.into()
+        }
+    };
+
+    let event_config = regex::Regex::new(r"^\s*[-*+]\s+`([a-z-]+)`\s+([A-Za-z_][A-Za-z0-9_]*)\s*$").expect("invalid regex");
+    let mut files = Vec::<String>::new();
+    let mut checks = Vec::<proc_macro2::TokenStream>::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "md") {
+            continue
+        }
+        let Ok(layout) = std::fs::read_to_string(&path) else {
+            continue
+        };
+        files.push(path.display().to_string());
+
+        let mut in_code_block = false;
+        for line in layout.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                continue
+            }
+            let Some(captures) = event_config.captures(line) else {
+                continue
+            };
+            if in_code_block || !EVENT_CONFIGS.contains(&&captures[1]) {
+                continue
+            }
+            let variant = proc_macro2::Ident::new(&captures[2], proc_macro2::Span::call_site());
+            checks.push(quote::quote! {
+                let _ = matches!(None::<#event_type>, Some(#event_type::#variant { .. }));
+            });
+        }
+    }
+
+    quote::quote! {
+        const _: () = {
+            // rebuilds when a layout changes
+            #(const _: &[u8] = include_bytes!(#files);)*
+
+            #[allow(unused)]
+            fn check_layouts() {
+                #(#checks)*
+            }
+        };
+    }.into()
+}
+
 #[proc_macro_derive(App)]
 pub fn app(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast: syn::DeriveInput = syn::parse(item).unwrap();